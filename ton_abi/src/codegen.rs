@@ -0,0 +1,526 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Typed Rust bindings generator.
+//!
+//! Generates Rust module with structs for every function input and output and every event
+//! of contract ABI. Integers are wrapped into width-specific types like `Uint128`, so values
+//! are checked against declared width when constructed. Intended to be used from build scripts:
+//!
+//! ```ignore
+//! // build.rs
+//! ton_abi::codegen::generate_to_file("Wallet.abi.json", out_dir.join("wallet.rs")).unwrap();
+//!
+//! // lib.rs
+//! mod wallet {
+//!     include!(concat!(env!("OUT_DIR"), "/wallet.rs"));
+//! }
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryInto;
+use std::fs;
+use std::path::Path;
+use {Contract, Event, Param, ParamType, Token, TokenValue, Int, Uint};
+use crate::error::*;
+
+pub use ton_block::{Grams, MsgAddress};
+pub use ton_types::{BuilderData, Cell, SliceData};
+pub use ed25519_dalek::Keypair;
+pub use lazy_static::lazy_static;
+
+/// Reads ABI from `abi_path` and writes generated bindings to `out_path`
+pub fn generate_to_file<P: AsRef<Path>, Q: AsRef<Path>>(abi_path: P, out_path: Q) -> AbiResult<()> {
+    let abi = fs::read_to_string(abi_path)?;
+    let code = generate(&abi)?;
+    fs::write(out_path, code)?;
+    Ok(())
+}
+
+/// Generates Rust bindings source code for contract described by `abi`
+pub fn generate(abi: &str) -> AbiResult<String> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let mut generator = Generator { code: String::new(), int_types: BTreeSet::new() };
+
+    generator.code += "// Generated by ton_abi::codegen. Do not edit.\n\n";
+    generator.code += "#[allow(unused_imports)]\n";
+    generator.code += "use ton_abi::{AbiResult, Contract, Param, ParamType, Token, TokenValue, Int, Uint};\n";
+    generator.code += "#[allow(unused_imports)]\n";
+    generator.code += "use ton_abi::codegen::{self, BuilderData, Cell, Grams, Keypair, MsgAddress, SliceData};\n";
+    generator.code += "#[allow(unused_imports)]\n";
+    generator.code += "use std::collections::HashMap;\n\n";
+    generator.code += &format!("pub const ABI: &str = r###\"{}\"###;\n\n", abi);
+    generator.code += "codegen::lazy_static! {\n";
+    generator.code += "    static ref CONTRACT: Result<Contract, String> =\n";
+    generator.code += "        Contract::load(ABI.as_bytes()).map_err(|err| err.to_string());\n";
+    generator.code += "}\n\n";
+    generator.code += "/// Returns contract ABI parsed once on first call\n";
+    generator.code += "pub fn contract() -> AbiResult<&'static Contract> {\n";
+    generator.code += "    codegen::loaded(&CONTRACT)\n";
+    generator.code += "}\n";
+
+    let mut functions: Vec<_> = contract.functions().values().collect();
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+    for function in functions {
+        let input = format!("{}Input", camel_case(&function.name));
+        let output = format!("{}Output", camel_case(&function.name));
+        generator.generate_struct(&input, &function.inputs)?;
+        generator.generate_struct(&output, &function.outputs)?;
+
+        generator.code += &format!(r#"
+#[allow(non_snake_case)]
+pub fn encode_{name}(input: {input}, internal: bool, pair: Option<&Keypair>) -> AbiResult<BuilderData> {{
+    contract()?.function("{name}")?.encode_input(&input.into_tokens(), internal, pair)
+}}
+
+#[allow(non_snake_case)]
+pub fn decode_{name}_input(body: SliceData, internal: bool) -> AbiResult<{input}> {{
+    {input}::from_tokens(contract()?.function("{name}")?.decode_input(body, internal)?)
+}}
+
+#[allow(non_snake_case)]
+pub fn decode_{name}_output(body: SliceData, internal: bool) -> AbiResult<{output}> {{
+    {output}::from_tokens(contract()?.function("{name}")?.decode_output(body, internal)?)
+}}
+"#,
+            name = function.name, input = input, output = output);
+    }
+
+    let mut events: Vec<_> = contract.events().values().collect();
+    events.sort_by(|a, b| a.name.cmp(&b.name));
+    for event in events {
+        let name = format!("{}Event", camel_case(&event.name));
+        generator.generate_struct(&name, &event.inputs)?;
+
+        generator.code += &format!(r#"
+#[allow(non_snake_case)]
+pub fn decode_{event}_event(body: SliceData) -> AbiResult<{name}> {{
+    let event = codegen::event(contract()?, "{event}")?;
+    {name}::from_tokens(event.decode_input(body)?)
+}}
+"#,
+            event = event.name, name = name);
+    }
+
+    generator.generate_int_types();
+
+    Ok(generator.code)
+}
+
+struct Generator {
+    code: String,
+    // signedness and width of integer types used by generated structs
+    int_types: BTreeSet<(bool, usize)>,
+}
+
+impl Generator {
+    fn generate_struct(&mut self, name: &str, params: &[Param]) -> AbiResult<()> {
+        let mut fields = String::new();
+        let mut into_tokens = String::new();
+        let mut from_tokens = String::new();
+
+        for param in params {
+            let field = field_name(&param.name);
+            let rust_type = self.rust_type(name, &param.name, &param.kind)?;
+            fields += &format!("    pub {}: {},\n", field, rust_type);
+            into_tokens += &format!("            Token::new({:?}, {}),\n",
+                param.name, to_token_value(&format!("self.{}", field), name, &param.name, &param.kind));
+            from_tokens += &format!("            {}: {},\n",
+                field,
+                from_token_value(
+                    &format!("codegen::next_value(&mut tokens, {:?})?", param.name),
+                    name, &param.name, &param.kind));
+        }
+
+        self.code += &format!(r#"
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct {name} {{
+{fields}}}
+
+impl {name} {{
+    pub fn into_tokens(self) -> Vec<Token> {{
+        vec![
+{into_tokens}        ]
+    }}
+
+    #[allow(unused_mut, unused_variables)]
+    pub fn from_tokens(tokens: Vec<Token>) -> AbiResult<Self> {{
+        codegen::check_count(&tokens, {count})?;
+        let mut tokens = tokens.into_iter();
+        Ok(Self {{
+{from_tokens}        }})
+    }}
+}}
+"#,
+            name = name,
+            fields = fields,
+            into_tokens = into_tokens,
+            from_tokens = from_tokens,
+            count = params.len());
+
+        Ok(())
+    }
+
+    fn rust_type(&mut self, owner: &str, name: &str, kind: &ParamType) -> AbiResult<String> {
+        Ok(match kind {
+            ParamType::Unknown => bail!(AbiErrorKind::InvalidData(
+                format!("Unknown type of parameter {}", name))),
+            ParamType::Uint(size) => {
+                self.int_types.insert((false, *size));
+                int_type_name(false, *size)
+            }
+            ParamType::Int(size) => {
+                self.int_types.insert((true, *size));
+                int_type_name(true, *size)
+            }
+            ParamType::Bool => "bool".to_owned(),
+            ParamType::Tuple(params) => {
+                let tuple_name = tuple_struct_name(owner, name);
+                self.generate_struct(&tuple_name, params)?;
+                tuple_name
+            }
+            ParamType::Array(item) => format!("Vec<{}>", self.rust_type(owner, name, item)?),
+            ParamType::FixedArray(item, size) =>
+                format!("[{}; {}]", self.rust_type(owner, name, item)?, size),
+            ParamType::Cell => "Cell".to_owned(),
+            ParamType::Map(_, value) =>
+                format!("HashMap<String, {}>", self.rust_type(owner, name, value)?),
+            ParamType::Address => "MsgAddress".to_owned(),
+            ParamType::Bytes => "Vec<u8>".to_owned(),
+            ParamType::FixedBytes(size) => format!("[u8; {}]", size),
+            ParamType::Gram => "Grams".to_owned(),
+            ParamType::String => "String".to_owned(),
+            ParamType::Optional(inner) =>
+                format!("Option<{}>", self.rust_type(owner, name, inner)?),
+        })
+    }
+
+    fn generate_int_types(&mut self) {
+        for (signed, size) in self.int_types.clone() {
+            let (base, number, check) = if signed {
+                ("Int", "i128", "check_int")
+            } else {
+                ("Uint", "u128", "check_uint")
+            };
+
+            self.code += &format!(r#"
+/// `{signature}` value
+#[derive(Debug, Clone, PartialEq)]
+pub struct {name}({base});
+
+impl {name} {{
+    pub const SIZE: usize = {size};
+
+    /// Creates value checking that it fits {size} bits
+    pub fn new(number: {number}) -> AbiResult<Self> {{
+        Self::from_value({base}::new(number, {size}))
+    }}
+
+    /// Checks that value has {size} bits width and fits it
+    pub fn from_value(value: {base}) -> AbiResult<Self> {{
+        codegen::{check}(&value, {size})?;
+        Ok({name}(value))
+    }}
+
+    pub fn value(&self) -> &{base} {{
+        &self.0
+    }}
+
+    pub fn into_value(self) -> {base} {{
+        self.0
+    }}
+}}
+"#,
+                signature = if signed { format!("int{}", size) } else { format!("uint{}", size) },
+                name = int_type_name(signed, size),
+                base = base,
+                number = number,
+                check = check,
+                size = size);
+        }
+    }
+}
+
+fn int_type_name(signed: bool, size: usize) -> String {
+    format!("{}{}", if signed { "Int" } else { "Uint" }, size)
+}
+
+fn to_token_value(expr: &str, owner: &str, name: &str, kind: &ParamType) -> String {
+    match kind {
+        ParamType::Unknown => unreachable!(),
+        ParamType::Uint(_) => format!("TokenValue::Uint({}.into_value())", expr),
+        ParamType::Int(_) => format!("TokenValue::Int({}.into_value())", expr),
+        ParamType::Bool => format!("TokenValue::Bool({})", expr),
+        ParamType::Tuple(_) => format!("TokenValue::Tuple({}.into_tokens())", expr),
+        ParamType::Array(item) => format!("TokenValue::Array({}.into_iter().map(|v| {}).collect())",
+            expr, to_token_value("v", owner, name, item)),
+        ParamType::FixedArray(item, _) => format!("TokenValue::FixedArray({}.to_vec().into_iter().map(|v| {}).collect())",
+            expr, to_token_value("v", owner, name, item)),
+        ParamType::Cell => format!("TokenValue::Cell({})", expr),
+        ParamType::Map(key, value) => format!("TokenValue::Map({}, {}.into_iter().map(|(k, v)| (k, {})).collect())",
+            param_type_expr(key), expr, to_token_value("v", owner, name, value)),
+        ParamType::Address => format!("TokenValue::Address({})", expr),
+        ParamType::Bytes => format!("TokenValue::Bytes({})", expr),
+        ParamType::FixedBytes(_) => format!("TokenValue::FixedBytes({}.to_vec())", expr),
+        ParamType::Gram => format!("TokenValue::Gram({})", expr),
+        ParamType::String => format!("TokenValue::String({})", expr),
+        ParamType::Optional(inner) => format!("TokenValue::Optional({}, {}.map(|v| Box::new({})))",
+            param_type_expr(inner), expr, to_token_value("v", owner, name, inner)),
+    }
+}
+
+fn from_token_value(expr: &str, owner: &str, name: &str, kind: &ParamType) -> String {
+    match kind {
+        ParamType::Unknown => unreachable!(),
+        ParamType::Uint(size) => format!("{}::from_value(codegen::uint({})?)?", int_type_name(false, *size), expr),
+        ParamType::Int(size) => format!("{}::from_value(codegen::int({})?)?", int_type_name(true, *size), expr),
+        ParamType::Bool => format!("codegen::boolean({})?", expr),
+        ParamType::Tuple(_) => format!("{}::from_tokens(codegen::tuple({})?)?",
+            tuple_struct_name(owner, name), expr),
+        ParamType::Array(item) => format!(
+            "codegen::array({})?.into_iter().map(|v| -> AbiResult<_> {{ Ok({}) }}).collect::<AbiResult<Vec<_>>>()?",
+            expr, from_token_value("v", owner, name, item)),
+        ParamType::FixedArray(item, _) => format!(
+            "codegen::fixed(codegen::array({})?.into_iter().map(|v| -> AbiResult<_> {{ Ok({}) }}).collect::<AbiResult<Vec<_>>>()?)?",
+            expr, from_token_value("v", owner, name, item)),
+        ParamType::Cell => format!("codegen::cell({})?", expr),
+        ParamType::Map(_, value) => format!(
+            "codegen::map({})?.into_iter().map(|(k, v)| -> AbiResult<_> {{ Ok((k, {})) }}).collect::<AbiResult<HashMap<_, _>>>()?",
+            expr, from_token_value("v", owner, name, value)),
+        ParamType::Address => format!("codegen::address({})?", expr),
+        ParamType::Bytes => format!("codegen::bytes({})?", expr),
+        ParamType::FixedBytes(_) => format!("codegen::fixed(codegen::bytes({})?)?", expr),
+        ParamType::Gram => format!("codegen::gram({})?", expr),
+        ParamType::String => format!("codegen::string({})?", expr),
+        ParamType::Optional(inner) => format!(
+            "codegen::optional({})?.map(|v| -> AbiResult<_> {{ Ok({}) }}).transpose()?",
+            expr, from_token_value("v", owner, name, inner)),
+    }
+}
+
+fn param_type_expr(kind: &ParamType) -> String {
+    match kind {
+        ParamType::Unknown => "ParamType::Unknown".to_owned(),
+        ParamType::Uint(size) => format!("ParamType::Uint({})", size),
+        ParamType::Int(size) => format!("ParamType::Int({})", size),
+        ParamType::Bool => "ParamType::Bool".to_owned(),
+        ParamType::Tuple(params) => format!("ParamType::Tuple(vec![{}])",
+            params.iter()
+                .map(|param| format!("Param::new({:?}, {})", param.name, param_type_expr(&param.kind)))
+                .collect::<Vec<String>>()
+                .join(", ")),
+        ParamType::Array(item) => format!("ParamType::Array(Box::new({}))", param_type_expr(item)),
+        ParamType::FixedArray(item, size) =>
+            format!("ParamType::FixedArray(Box::new({}), {})", param_type_expr(item), size),
+        ParamType::Cell => "ParamType::Cell".to_owned(),
+        ParamType::Map(key, value) => format!("ParamType::Map(Box::new({}), Box::new({}))",
+            param_type_expr(key), param_type_expr(value)),
+        ParamType::Address => "ParamType::Address".to_owned(),
+        ParamType::Bytes => "ParamType::Bytes".to_owned(),
+        ParamType::FixedBytes(size) => format!("ParamType::FixedBytes({})", size),
+        ParamType::Gram => "ParamType::Gram".to_owned(),
+        ParamType::String => "ParamType::String".to_owned(),
+        ParamType::Optional(inner) => format!("ParamType::Optional(Box::new({}))", param_type_expr(inner)),
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut upper = true;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn tuple_struct_name(owner: &str, name: &str) -> String {
+    format!("{}{}", owner, camel_case(name))
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+];
+
+fn field_name(name: &str) -> String {
+    if RUST_KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_owned()
+    }
+}
+
+// Helpers used by generated code
+
+fn wrong_type(value: &TokenValue, expected: &str) -> AbiError {
+    AbiErrorKind::InvalidData(format!("{} expected, {} provided", expected, value.get_param_type())).into()
+}
+
+/// Checks count of decoded tokens
+pub fn check_count(tokens: &[Token], count: usize) -> AbiResult<()> {
+    if tokens.len() != count {
+        bail!(AbiErrorKind::WrongParametersCount(count, tokens.len()));
+    }
+    Ok(())
+}
+
+/// Takes next token value checking its name
+pub fn next_value<I: Iterator<Item = Token>>(tokens: &mut I, name: &str) -> AbiResult<TokenValue> {
+    match tokens.next() {
+        Some(token) => if token.name == name {
+            Ok(token.value)
+        } else {
+            bail!(AbiErrorKind::InvalidName(token.name))
+        },
+        None => bail!(AbiErrorKind::InvalidName(name.to_owned())),
+    }
+}
+
+/// Returns contract parsed by generated `lazy_static` or the parsing error
+pub fn loaded(contract: &'static Result<Contract, String>) -> AbiResult<&'static Contract> {
+    contract.as_ref()
+        .map_err(|err| AbiErrorKind::InvalidData(format!("invalid contract ABI: {}", err)).into())
+}
+
+/// Checks that unsigned integer has given width and its value fits it
+pub fn check_uint(value: &Uint, size: usize) -> AbiResult<()> {
+    if value.size != size || !Uint::fits(&value.number, size) {
+        bail!(AbiErrorKind::InvalidData(
+            format!("value {} of uint{} does not fit uint{}", value.number, value.size, size)));
+    }
+    Ok(())
+}
+
+/// Checks that signed integer has given width and its value fits it
+pub fn check_int(value: &Int, size: usize) -> AbiResult<()> {
+    if value.size != size || !Int::fits(&value.number, size) {
+        bail!(AbiErrorKind::InvalidData(
+            format!("value {} of int{} does not fit int{}", value.number, value.size, size)));
+    }
+    Ok(())
+}
+
+/// Looks up event by name
+pub fn event<'a>(contract: &'a Contract, name: &str) -> AbiResult<&'a Event> {
+    contract.events()
+        .get(name)
+        .ok_or(AbiErrorKind::InvalidName(name.to_owned()).into())
+}
+
+/// Converts decoded values of `T[N]` or `fixedbytesN` into Rust array checking their count
+pub fn fixed<T, const N: usize>(values: Vec<T>) -> AbiResult<[T; N]> {
+    let len = values.len();
+    values.try_into().map_err(|_| AbiErrorKind::InvalidData(
+        format!("{} values of fixed size array expected, {} provided", N, len)).into())
+}
+
+pub fn uint(value: TokenValue) -> AbiResult<Uint> {
+    match value {
+        TokenValue::Uint(uint) => Ok(uint),
+        _ => Err(wrong_type(&value, "uint")),
+    }
+}
+
+pub fn int(value: TokenValue) -> AbiResult<Int> {
+    match value {
+        TokenValue::Int(int) => Ok(int),
+        _ => Err(wrong_type(&value, "int")),
+    }
+}
+
+pub fn boolean(value: TokenValue) -> AbiResult<bool> {
+    match value {
+        TokenValue::Bool(b) => Ok(b),
+        _ => Err(wrong_type(&value, "bool")),
+    }
+}
+
+pub fn tuple(value: TokenValue) -> AbiResult<Vec<Token>> {
+    match value {
+        TokenValue::Tuple(tokens) => Ok(tokens),
+        _ => Err(wrong_type(&value, "tuple")),
+    }
+}
+
+pub fn array(value: TokenValue) -> AbiResult<Vec<TokenValue>> {
+    match value {
+        TokenValue::Array(values) | TokenValue::FixedArray(values) => Ok(values),
+        _ => Err(wrong_type(&value, "array")),
+    }
+}
+
+pub fn cell(value: TokenValue) -> AbiResult<Cell> {
+    match value {
+        TokenValue::Cell(cell) => Ok(cell),
+        _ => Err(wrong_type(&value, "cell")),
+    }
+}
+
+pub fn map(value: TokenValue) -> AbiResult<HashMap<String, TokenValue>> {
+    match value {
+        TokenValue::Map(_, map) => Ok(map),
+        _ => Err(wrong_type(&value, "map")),
+    }
+}
+
+pub fn address(value: TokenValue) -> AbiResult<MsgAddress> {
+    match value {
+        TokenValue::Address(address) => Ok(address),
+        _ => Err(wrong_type(&value, "address")),
+    }
+}
+
+pub fn bytes(value: TokenValue) -> AbiResult<Vec<u8>> {
+    match value {
+        TokenValue::Bytes(bytes) | TokenValue::FixedBytes(bytes) => Ok(bytes),
+        _ => Err(wrong_type(&value, "bytes")),
+    }
+}
+
+pub fn gram(value: TokenValue) -> AbiResult<Grams> {
+    match value {
+        TokenValue::Gram(gram) => Ok(gram),
+        _ => Err(wrong_type(&value, "gram")),
+    }
+}
+
+pub fn string(value: TokenValue) -> AbiResult<String> {
+    match value {
+        TokenValue::String(string) => Ok(string),
+        _ => Err(wrong_type(&value, "string")),
+    }
+}
+
+pub fn optional(value: TokenValue) -> AbiResult<Option<TokenValue>> {
+    match value {
+        TokenValue::Optional(_, value) => Ok(value.map(|value| *value)),
+        _ => Err(wrong_type(&value, "optional")),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_codegen.rs"]
+mod tests;
@@ -17,13 +17,15 @@ use std::collections::HashMap;
 use serde::{Deserialize, Deserializer};
 use serde::de::{Unexpected, Error as SerdeError};
 use serde_json;
-use {Function, Event, Token, Param};
+use {AbiLayout, ExternalHeader, Function, Event, Token, TokenValue, Param};
+use schema;
 use ton_types::{SliceData, BuilderData};
-use ton_types::dictionary::HashmapE;
+use ton_types::dictionary::{HashmapE, HashmapType};
 use crate::error::*;
 use ton_block::Serializable;
 
-pub const   ABI_VERSION: u8 = 1;
+/// Latest supported ABI version
+pub const   ABI_VERSION: u8 = AbiLayout::VERSIONS[AbiLayout::VERSIONS.len() - 1].0;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct DataItem {
@@ -41,6 +43,8 @@ pub struct Contract {
     events: HashMap<String, Event>,
     /// Contract initila data.
     data: HashMap<String, DataItem>,
+    /// Layout of parameters defined by ABI version.
+    layout: AbiLayout,
 }
 
 impl<'a> Deserialize<'a> for Contract {
@@ -49,22 +53,22 @@ impl<'a> Deserialize<'a> for Contract {
         // struct `SerdeContract` containing necessary fields and then repack functions into HashMap
         let serde_contract = SerdeContract::deserialize(deserializer)?;
 
-        if serde_contract.abi_version != ABI_VERSION {
-            return Err(
-                <D::Error as SerdeError>::invalid_value(
-                    Unexpected::Unsigned(serde_contract.abi_version as u64),
-                    &format!("ABI version `{}`", ABI_VERSION).as_str())
-            );
-        }
+        let layout = AbiLayout::from_version(serde_contract.abi_version).map_err(|_|
+            <D::Error as SerdeError>::invalid_value(
+                Unexpected::Unsigned(serde_contract.abi_version as u64),
+                &format!("ABI version {}", AbiLayout::versions_text()).as_str())
+        )?;
 
         let mut result = Self {
             functions: HashMap::new(),
             events: HashMap::new(),
             data: HashMap::new(),
+            layout,
         };
 
         for mut function in serde_contract.functions {
             function.set_time = serde_contract.set_time;
+            function.layout = layout;
             if function.id.is_none() {
                 function.id = Some(function.get_function_id());
             }
@@ -72,6 +76,7 @@ impl<'a> Deserialize<'a> for Contract {
         }
 
         for mut event in serde_contract.events {
+            event.layout = layout;
             if event.id.is_none() {
                 event.id = Some(event.get_function_id());
             }
@@ -164,6 +169,16 @@ impl Contract {
         &self.data
     }
 
+    /// Returns layout of parameters defined by ABI version
+    pub fn layout(&self) -> AbiLayout {
+        self.layout
+    }
+
+    /// Returns JSON Schema of function inputs and outputs, events and data accepted by tokenizer
+    pub fn json_schema(&self) -> serde_json::Value {
+        schema::contract_schema(self)
+    }
+
     /// Decodes contract answer and returns name of the function called
     pub fn decode_output(&self, data: SliceData, internal: bool) -> AbiResult<DecodedMessage> {
         let original_data = data.clone();
@@ -207,6 +222,23 @@ impl Contract {
         })
     }
 
+    /// Decodes contract function call and returns header of external call. If `internal` is `None`
+    /// message kind is detected by `Function::decode_input_with_header`
+    pub fn decode_input_with_header(&self, data: SliceData, internal: Option<bool>)
+    -> AbiResult<(DecodedMessage, Option<ExternalHeader>)> {
+        let func_id = Function::decode_id(data.clone())?;
+
+        let func = self.function_by_id(func_id, true)?;
+
+        let (tokens, header) = func.decode_input_with_header(data, internal)?;
+
+        Ok((DecodedMessage {
+            function_name: func.name.clone(),
+            tokens,
+            params: func.input_params()
+        }, header))
+    }
+
     pub const DATA_MAP_KEYLEN: usize = 64;
 
     /// Changes initial values for public contract variables
@@ -217,7 +249,7 @@ impl Contract {
         );
 
         for token in tokens {
-            let builder = token.value.pack_into_chain()?;
+            let builder = token.value.pack_into_chain(self.layout)?;
             let key = self.data
                 .get(&token.name)
                 .ok_or(
@@ -250,6 +282,44 @@ impl Contract {
         )?;
         Ok(map.write_to_new_cell()?.into())
     }
+
+    /// Decodes public contract variables from contract data. Variables absent in data are skipped,
+    /// returned tokens are ordered by data keys
+    pub fn decode_data(&self, data: SliceData) -> AbiResult<Vec<Token>> {
+        let map = HashmapE::with_data(
+            Self::DATA_MAP_KEYLEN,
+            data,
+        );
+
+        let mut items: Vec<&DataItem> = self.data.values().collect();
+        items.sort_by_key(|item| item.key);
+
+        let mut tokens = Vec::new();
+        for item in items {
+            let value = map.get(item.key.write_to_new_cell()?.into())?;
+            if let Some(value) = value {
+                let (value, _) = TokenValue::read_from(&item.value.kind, value, self.layout)?;
+                tokens.push(Token {
+                    name: item.value.name.clone(),
+                    value,
+                });
+            }
+        }
+
+        Ok(tokens)
+    }
+
+    /// Reads public key from contract data
+    pub fn get_pubkey(data: SliceData) -> AbiResult<Option<Vec<u8>>> {
+        let map = HashmapE::with_data(
+            Self::DATA_MAP_KEYLEN,
+            data,
+        );
+        match map.get(0u64.write_to_new_cell()?.into())? {
+            Some(ref value) if value.remaining_bits() != 0 => Ok(Some(value.get_bytestring(0))),
+            _ => Ok(None)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -13,6 +13,7 @@
 */
 
 use ton_types::SliceData;
+use ParamType;
 
 error_chain! {
 
@@ -63,8 +64,16 @@ error_chain! {
             display("Wrong parameters count. Expected: {}, provided: {}", expected, provided)
         }
         WrongParameterType {
-            description("Not implemented"),
-            display("Not implemented")
+            description("Wrong parameter type"),
+            display("Wrong parameter type")
+        }
+        InvalidParameter(path: String, expected: ParamType, val: serde_json::Value, reason: String) {
+            description("Invalid parameter"),
+            display("Invalid parameter `{}`: {}. Expected type: {}, received: {}", path, reason, expected, val)
+        }
+        ParameterTypeMismatch(path: String, expected: ParamType, provided: ParamType) {
+            description("Parameter type mismatch"),
+            display("Wrong type of parameter `{}`. Expected: {}, provided: {}", path, expected, provided)
         }
         WrongDataFormat(val: serde_json::Value) {
             description("Wrong data format"),
@@ -97,6 +106,18 @@ error_chain! {
     }
 }
 
+impl serde::ser::Error for AbiError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        AbiErrorKind::InvalidData(msg.to_string()).into()
+    }
+}
+
+impl serde::de::Error for AbiError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        AbiErrorKind::InvalidData(msg.to_string()).into()
+    }
+}
+
 impl From<failure::Error> for AbiError {
     fn from(error: failure::Error) -> Self {
         AbiErrorKind::FailureError(error).into()
@@ -12,10 +12,9 @@
 * limitations under the License.
 */
 
-use {Function, Param, Token, TokenValue};
+use {AbiLayout, Function, Param, Token, TokenValue};
 use ton_types::SliceData;
 use crate::error::*;
-use super::contract::ABI_VERSION;
 
 /// Contract event specification.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -27,7 +26,10 @@ pub struct Event {
     pub inputs: Vec<Param>,
     #[serde(default)]
     #[serde(deserialize_with = "super::function::deserialize_opt_u32_from_string")]
-    pub id: Option<u32>
+    pub id: Option<u32>,
+    /// Layout of parameters defined by ABI version.
+    #[serde(skip_deserializing)]
+    pub layout: AbiLayout,
 }
 
 impl Event {
@@ -50,7 +52,7 @@ impl Event {
             .collect::<Vec<String>>()
             .join(",");
 
-        format!("{}({})v{}", self.name, input_types, ABI_VERSION)
+        format!("{}({})v{}", self.name, input_types, self.layout.version())
     }
 
     /// Computes function ID for contract function
@@ -78,7 +80,7 @@ impl Event {
         if id != self.get_id() { Err(AbiErrorKind::WrongId(id))? }
 
         for param in params {
-            let (token_value, new_cursor) = TokenValue::read_from(&param.kind, cursor)?;
+            let (token_value, new_cursor) = TokenValue::read_from(&param.kind, cursor, self.layout)?;
 
             cursor = new_cursor;
             tokens.push(Token { name: param.name, value: token_value });
@@ -0,0 +1,304 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! JSON encodings of addresses, cells and bytes.
+//!
+//! Tokenizer detects the encoding by the value itself:
+//! * addresses are accepted in raw `workchain:hex` form, user-friendly base64 and url-safe
+//!   base64 forms and as `addr_none`;
+//! * cells are accepted as base64 or hex bag of cells and as `CellTree` object like
+//!   `{"bits": "b5", "bitLength": 6, "refs": [{"bits": "01", "bitLength": 8}]}`;
+//! * bytes are accepted as hex string or as object with encoding marker:
+//!   `{"hex": "..."}`, `{"base64": "..."}` or `{"utf8": "..."}`.
+//!
+//! Detokenizer writes values in encodings selected by `ValueFormat`, its output is accepted
+//! back by tokenizer.
+
+use IntFormat;
+use ton_block::MsgAddress;
+use ton_types::{BuilderData, Cell, CellType};
+use crate::error::*;
+
+const USER_FRIENDLY_LENGTH: usize = 36;
+const USER_FRIENDLY_BASE64_LENGTH: usize = 48;
+const BOUNCEABLE_TAG: u8 = 0x11;
+const NON_BOUNCEABLE_TAG: u8 = 0x51;
+const TESTNET_FLAG: u8 = 0x80;
+
+/// Literal of empty address accepted by tokenizer
+pub const ADDR_NONE: &str = "addr_none";
+
+fn default_bounceable() -> bool {
+    true
+}
+
+/// Output encoding of addresses
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AddressFormat {
+    /// `workchain:hex` form
+    Raw,
+    /// User-friendly base64 form. Used for std addresses without anycast only,
+    /// other addresses are written in raw form
+    Base64 {
+        #[serde(default = "default_bounceable")]
+        bounceable: bool,
+        #[serde(default)]
+        testnet: bool,
+        /// Use url-safe base64 alphabet
+        #[serde(default)]
+        url: bool,
+    },
+}
+
+impl Default for AddressFormat {
+    fn default() -> Self {
+        AddressFormat::Raw
+    }
+}
+
+/// Output encoding of cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CellFormat {
+    /// Base64 bag of cells
+    Base64,
+    /// Hex bag of cells
+    Hex,
+    /// `CellTree` object
+    Tree,
+}
+
+impl Default for CellFormat {
+    fn default() -> Self {
+        CellFormat::Base64
+    }
+}
+
+/// Output encoding of bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BytesFormat {
+    /// Hex string
+    Hex,
+    /// `{"base64": "..."}` object
+    Base64,
+    /// `{"utf8": "..."}` object, bytes which are not valid UTF-8 are written as `{"hex": "..."}`
+    Utf8,
+}
+
+impl Default for BytesFormat {
+    fn default() -> Self {
+        BytesFormat::Hex
+    }
+}
+
+/// Output encodings of decoded values
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueFormat {
+    #[serde(default)]
+    pub int_format: IntFormat,
+    #[serde(default)]
+    pub address_format: AddressFormat,
+    #[serde(default)]
+    pub cell_format: CellFormat,
+    #[serde(default)]
+    pub bytes_format: BytesFormat,
+}
+
+impl From<IntFormat> for ValueFormat {
+    fn from(int_format: IntFormat) -> Self {
+        Self { int_format, ..Self::default() }
+    }
+}
+
+fn crc16(data: &[u8]) -> Vec<u8> {
+    let mut crc = crc_any::CRC::crc16xmodem();
+    crc.digest(data);
+    crc.get_crc_vec_be()
+}
+
+/// Std address parsed from user-friendly form together with its flags
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserFriendlyAddress {
+    pub address: MsgAddress,
+    pub bounceable: bool,
+    pub testnet: bool,
+    /// Url-safe base64 alphabet is used
+    pub url: bool,
+}
+
+/// Parses user-friendly address in standard or url-safe base64 checking its length, CRC16 and tag.
+/// Returns reason of failure on error
+pub fn decode_user_friendly_address(string: &str) -> Result<UserFriendlyAddress, String> {
+    if string.len() != USER_FRIENDLY_BASE64_LENGTH {
+        return Err(format!(
+            "user-friendly address must be {} characters long, got {}", USER_FRIENDLY_BASE64_LENGTH, string.len()));
+    }
+    let url = string.contains('-') || string.contains('_');
+    let data = base64::decode(&string.replace('_', "/").replace('-', "+"))
+        .map_err(|err| format!("invalid base64: {}", err))?;
+
+    if data.len() != USER_FRIENDLY_LENGTH {
+        return Err(format!("user-friendly address must be {} bytes long", USER_FRIENDLY_LENGTH));
+    }
+    let crc = crc16(&data[..34]);
+    if crc != &data[34..] {
+        return Err(format!(
+            "address CRC16 mismatch: expected {}, got {}", hex::encode(&crc), hex::encode(&data[34..])));
+    }
+    let tag = data[0] & !TESTNET_FLAG;
+    if tag != BOUNCEABLE_TAG && tag != NON_BOUNCEABLE_TAG {
+        return Err(format!("invalid address tag 0x{:02x}", data[0]));
+    }
+
+    Ok(UserFriendlyAddress {
+        address: MsgAddress::with_standart(None, data[1] as i8, data[2..34].into())
+            .map_err(|err| err.to_string())?,
+        bounceable: tag == BOUNCEABLE_TAG,
+        testnet: data[0] & TESTNET_FLAG != 0,
+        url,
+    })
+}
+
+/// Writes std address without anycast in user-friendly form, returns `None` for other addresses
+pub fn encode_user_friendly_address(address: &MsgAddress, bounceable: bool, testnet: bool, url: bool) -> Option<String> {
+    let address = match address {
+        MsgAddress::AddrStd(address) if address.anycast.is_none() => address,
+        _ => return None,
+    };
+
+    let mut tag = if bounceable { BOUNCEABLE_TAG } else { NON_BOUNCEABLE_TAG };
+    if testnet { tag |= TESTNET_FLAG };
+    let mut data = vec![tag];
+    data.extend_from_slice(&address.workchain_id.to_be_bytes());
+    data.append(&mut address.address.get_bytestring(0));
+    let mut crc = crc16(&data);
+    data.append(&mut crc);
+
+    let string = base64::encode(&data);
+    if url {
+        Some(string.replace('/', "_").replace('+', "-"))
+    } else {
+        Some(string)
+    }
+}
+
+/// Writes address in given format
+pub fn encode_address(address: &MsgAddress, format: AddressFormat) -> String {
+    match format {
+        AddressFormat::Base64 { bounceable, testnet, url } =>
+            encode_user_friendly_address(address, bounceable, testnet, url)
+                .unwrap_or_else(|| address.to_string()),
+        AddressFormat::Raw => address.to_string(),
+    }
+}
+
+/// Type of cell in tree form
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellTreeType {
+    Ordinary,
+    PrunedBranch,
+    LibraryReference,
+    MerkleProof,
+    MerkleUpdate,
+}
+
+impl Default for CellTreeType {
+    fn default() -> Self {
+        CellTreeType::Ordinary
+    }
+}
+
+impl From<CellType> for CellTreeType {
+    fn from(cell_type: CellType) -> Self {
+        match cell_type {
+            CellType::PrunedBranch => CellTreeType::PrunedBranch,
+            CellType::LibraryReference => CellTreeType::LibraryReference,
+            CellType::MerkleProof => CellTreeType::MerkleProof,
+            CellType::MerkleUpdate => CellTreeType::MerkleUpdate,
+            _ => CellTreeType::Ordinary,
+        }
+    }
+}
+
+impl From<CellTreeType> for CellType {
+    fn from(cell_type: CellTreeType) -> Self {
+        match cell_type {
+            CellTreeType::Ordinary => CellType::Ordinary,
+            CellTreeType::PrunedBranch => CellType::PrunedBranch,
+            CellTreeType::LibraryReference => CellType::LibraryReference,
+            CellTreeType::MerkleProof => CellType::MerkleProof,
+            CellTreeType::MerkleUpdate => CellType::MerkleUpdate,
+        }
+    }
+}
+
+/// Cell as a tree of JSON objects. `bits` is hex of `bit_length` data bits padded with zeros
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellTree {
+    #[serde(default)]
+    pub cell_type: CellTreeType,
+    pub bits: String,
+    pub bit_length: usize,
+    #[serde(default)]
+    pub refs: Vec<CellTree>,
+}
+
+impl CellTree {
+    pub fn from_cell(cell: &Cell) -> AbiResult<Self> {
+        let bit_length = cell.bit_length();
+        let mut bits = cell.data()[..(bit_length + 7) / 8].to_vec();
+        // clear completion tag and padding in the last incomplete byte
+        if bit_length % 8 != 0 {
+            let last = bits.len() - 1;
+            bits[last] &= 0xFFu8 << (8 - bit_length % 8);
+        }
+
+        let mut refs = Vec::new();
+        for i in 0..cell.references_count() {
+            refs.push(Self::from_cell(&cell.reference(i)?)?);
+        }
+
+        Ok(Self {
+            cell_type: cell.cell_type().into(),
+            bits: hex::encode(&bits),
+            bit_length,
+            refs,
+        })
+    }
+
+    pub fn to_cell(&self) -> AbiResult<Cell> {
+        let bits = hex::decode(&self.bits)
+            .map_err(|err| AbiErrorKind::InvalidData(format!("invalid hex `{}`: {}", self.bits, err)))?;
+        if bits.len() * 8 < self.bit_length {
+            bail!(AbiErrorKind::InvalidData(
+                format!("{} bits declared, but only {} bytes provided", self.bit_length, bits.len())));
+        }
+
+        if self.refs.len() > BuilderData::references_capacity() {
+            bail!(AbiErrorKind::InvalidData(format!("too many references: {}", self.refs.len())));
+        }
+
+        let mut builder = BuilderData::with_raw(bits, self.bit_length)?;
+        builder.set_type(self.cell_type.into());
+        for child in self.refs.iter() {
+            builder.append_reference_cell(child.to_cell()?);
+        }
+
+        Ok(builder.into())
+    }
+}
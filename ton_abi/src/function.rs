@@ -16,12 +16,21 @@
 
 use chrono::prelude::*;
 use sha2::{Digest, Sha256, Sha512};
-use {Param, Token, TokenValue};
+use {AbiLayout, Param, Token, TokenValue};
+use token::LazyToken;
 use ed25519_dalek::*;
 use serde::de::Error;
 use ton_types::{BuilderData, SliceData, Cell, IBitstring};
 use crate::error::*;
-use super::contract::ABI_VERSION;
+
+/// Header of external function call body
+#[derive(Debug, Clone)]
+pub struct ExternalHeader {
+    /// Signer public key with verified signature, `None` if body is not signed
+    pub public_key: Option<PublicKey>,
+    /// Message timestamp in milliseconds, `None` if function doesn't use timestamp
+    pub time: Option<u64>,
+}
 
 /// Contract function specification.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -42,6 +51,10 @@ pub struct Function {
     /// Set timestamp.
     #[serde(skip_deserializing)]
     pub set_time: bool,
+
+    /// Layout of parameters defined by ABI version.
+    #[serde(skip_deserializing)]
+    pub layout: AbiLayout,
 }
 
 impl Function {
@@ -86,7 +99,7 @@ impl Function {
             .collect::<Vec<String>>()
             .join(",");
 
-        format!("{}({})({})v{}", self.name, input_types, output_types, ABI_VERSION)
+        format!("{}({})({})v{}", self.name, input_types, output_types, self.layout.version())
     }
 
     pub fn calc_function_id(signature: &str) -> u32 {
@@ -129,9 +142,9 @@ impl Function {
         self.get_id() | 0x80000000
     }
 
-    /// Decodes provided params from SliceData
+    /// Decodes provided params from SliceData. Returns tokens and timestamp if it is extracted
     fn decode_params(&self, params: Vec<Param>, mut cursor: SliceData, expected_id: u32, exctract_time: bool
-        ) -> AbiResult<Vec<Token>> {
+        ) -> AbiResult<(Vec<Token>, Option<u64>)> {
         let mut tokens = vec![];
         let original = cursor.clone();
 
@@ -139,13 +152,15 @@ impl Function {
 
         if id != expected_id { Err(AbiErrorKind::WrongId(id))? }
 
-        if exctract_time {
-            cursor.get_next_u64()?;
-        }
+        let time = if exctract_time {
+            Some(cursor.get_next_u64()?)
+        } else {
+            None
+        };
 
         for param in params {
             // println!("{:?}", param);
-            let (token_value, new_cursor) = TokenValue::read_from(&param.kind, cursor)?;
+            let (token_value, new_cursor) = TokenValue::read_from(&param.kind, cursor, self.layout)?;
 
             cursor = new_cursor;
             tokens.push(Token { name: param.name, value: token_value });
@@ -154,13 +169,34 @@ impl Function {
         if cursor.remaining_references() != 0 || cursor.remaining_bits() != 0 {
             bail!(AbiErrorKind::IncompleteDeserializationError(original))
         } else {
-            Ok(tokens)
+            Ok((tokens, time))
         }
     }
 
-    /// Parses the ABI function output to list of tokens.
+    /// Parses the ABI function output to list of tokens. Output bodies of internal and
+    /// external messages have the same layout, so `internal` flag doesn't affect decoding
     pub fn decode_output(&self, data: SliceData, _internal: bool) -> AbiResult<Vec<Token>> {
         self.decode_params(self.output_params(), data, self.get_output_id(), false)
+            .map(|(tokens, _)| tokens)
+    }
+
+    /// Parses the ABI function output to list of lazily decoded tokens. Arrays and maps
+    /// are not decoded until they are accessed. Output layout is the same for internal
+    /// and external messages
+    pub fn decode_output_lazy(&self, mut data: SliceData) -> AbiResult<Vec<LazyToken>> {
+        let original = data.clone();
+
+        let id = data.get_next_u32()?;
+
+        if id != self.get_output_id() { Err(AbiErrorKind::WrongId(id))? }
+
+        let (tokens, cursor) = TokenValue::read_lazy_params(&self.output_params(), data, self.layout)?;
+
+        if cursor.remaining_references() != 0 || cursor.remaining_bits() != 0 {
+            bail!(AbiErrorKind::IncompleteDeserializationError(original))
+        } else {
+            Ok(tokens)
+        }
     }
 
     /// Parses the ABI function call to list of tokens.
@@ -171,6 +207,56 @@ impl Function {
         }
 
         self.decode_params(self.input_params(), data, self.get_input_id(), self.set_time && !internal)
+            .map(|(tokens, _)| tokens)
+    }
+
+    /// Parses the ABI function call to list of tokens and returns header of external call.
+    /// If `internal` is `None` message kind is detected: body is decoded both as external and
+    /// internal one and must match exactly one of them. Header is `None` for internal message body
+    pub fn decode_input_with_header(&self, data: SliceData, internal: Option<bool>)
+    -> AbiResult<(Vec<Token>, Option<ExternalHeader>)> {
+        let id = Self::decode_id(data.clone())?;
+        if id != self.get_input_id() {
+            bail!(AbiErrorKind::WrongId(id));
+        }
+
+        match internal {
+            Some(true) => Ok((self.decode_input(data, true)?, None)),
+            Some(false) => self.decode_external_input(data).map(|(tokens, header)| (tokens, Some(header))),
+            None => match (self.decode_external_input(data.clone()), self.decode_input(data, true)) {
+                (Ok((tokens, header)), Err(_)) => Ok((tokens, Some(header))),
+                (Err(_), Ok(tokens)) => Ok((tokens, None)),
+                (Ok(_), Ok(_)) => bail!(AbiErrorKind::InvalidInputData(format!(
+                    "body is both external and internal call of function {}, message kind must be set",
+                    self.name))),
+                (Err(external_err), Err(internal_err)) => bail!(AbiErrorKind::InvalidInputData(format!(
+                    "body is neither external ({}) nor internal ({}) call of function {}",
+                    external_err, internal_err, self.name)))
+            }
+        }
+    }
+
+    // Decodes external call body checking that signature reference is either empty
+    // or contains valid signature and public key
+    fn decode_external_input(&self, mut data: SliceData) -> AbiResult<(Vec<Token>, ExternalHeader)> {
+        let body = data.clone();
+        let sign = SliceData::from(data.checked_drain_reference()
+            .map_err(|err| AbiErrorKind::InvalidInputData(err.to_string()))?);
+
+        if sign.remaining_references() != 0 {
+            bail!(AbiErrorKind::InvalidInputData("Signature reference has references".to_owned()));
+        }
+        let public_key = match sign.remaining_bits() {
+            0 => None,
+            _ => match self.verify_signature(body)? {
+                (true, public_key) => Some(public_key),
+                (false, _) => bail!(AbiErrorKind::InvalidInputData("Invalid signature".to_owned()))
+            }
+        };
+
+        let (tokens, time) = self.decode_params(self.input_params(), data, self.get_input_id(), self.set_time)?;
+
+        Ok((tokens, ExternalHeader { public_key, time }))
     }
 
     /// Decodes function id from contract answer
@@ -210,12 +296,21 @@ impl Function {
         &self,
         tokens: &[Token],
         internal: bool
+    ) -> AbiResult<(BuilderData, Vec<u8>)> {
+        self.create_unsigned_call_with_time(tokens, internal, Utc::now().timestamp_millis() as u64)
+    }
+
+    /// Same as `create_unsigned_call`, but puts given time in milliseconds into the header
+    /// of external call instead of the current one
+    pub fn create_unsigned_call_with_time(
+        &self,
+        tokens: &[Token],
+        internal: bool,
+        time: u64
     ) -> AbiResult<(BuilderData, Vec<u8>)> {
         let params = self.input_params();
 
-        if !Token::types_check(tokens, params.as_slice()) {
-            bail!(AbiErrorKind::WrongParameterType);
-        }
+        Token::check_types(tokens, params.as_slice())?;
 
         // prepare standard message
         let mut builder = BuilderData::new();
@@ -223,8 +318,7 @@ impl Function {
 
         if !internal {
             if self.set_time {
-                let time = Utc::now().timestamp_millis();
-                builder.append_i64(time)?;
+                builder.append_i64(time as i64)?;
             }
             
             // reserve reference for sign
@@ -232,7 +326,7 @@ impl Function {
         }
 
         // encoding itself
-        builder = TokenValue::pack_values_into_chain(tokens, vec![builder])?;
+        builder = TokenValue::pack_values_into_chain(tokens, vec![builder], self.layout)?;
         if !internal {
             // delete sign reference before hash
             let mut slice = SliceData::from(builder);
@@ -267,6 +361,36 @@ impl Function {
         Ok(builder)
     }
 
+    /// Checks signature of the external call body. Signature and public key are taken
+    /// from the first reference, hash is calculated over the body without this reference
+    /// as in `create_unsigned_call`. Returns signature validity and signer public key.
+    pub fn verify_signature(&self, mut body: SliceData) -> AbiResult<(bool, PublicKey)> {
+        let mut sign = SliceData::from(body.checked_drain_reference()
+            .map_err(|err| AbiErrorKind::InvalidInputData(err.to_string()))?);
+
+        if sign.remaining_bits() == 0 {
+            bail!(AbiErrorKind::InvalidInputData("Message is not signed".to_owned()));
+        }
+        if sign.remaining_bits() != (SIGNATURE_LENGTH + PUBLIC_KEY_LENGTH) * 8 {
+            bail!(AbiErrorKind::InvalidInputData("Invalid signature reference length".to_owned()));
+        }
+
+        let id = Self::decode_id(body.clone())?;
+        if id != self.get_input_id() {
+            bail!(AbiErrorKind::WrongId(id));
+        }
+
+        let signature = Signature::from_bytes(&sign.get_next_bytes(SIGNATURE_LENGTH)?)
+            .map_err(|err| AbiErrorKind::InvalidInputData(err.to_string()))?;
+        let public_key = PublicKey::from_bytes(&sign.get_next_bytes(PUBLIC_KEY_LENGTH)?)
+            .map_err(|err| AbiErrorKind::InvalidInputData(err.to_string()))?;
+
+        let hash = Cell::from(&BuilderData::from_slice(&body)).repr_hash();
+        let valid = public_key.verify::<Sha512>(hash.as_slice(), &signature).is_ok();
+
+        Ok((valid, public_key))
+    }
+
     /// Check if message body is related to this function
     pub fn is_my_message(&self, data: SliceData, _internal: bool) -> Result<bool, AbiErrorKind> {
         let decoded_id = Self::decode_id(data)?;
@@ -12,7 +12,10 @@
 * limitations under the License.
 */
 
-use num_bigint::{BigInt, BigUint};
+//! Arbitrary-precision integers of ABI types `intN` and `uintN`.
+
+use num_bigint::{BigInt, BigUint, Sign};
+use crate::error::*;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Int {
@@ -31,6 +34,35 @@ impl Int {
     pub fn new(number: i128, size: usize) -> Self {
         Self { number: BigInt::from(number), size }
     }
+
+    pub fn from_big_int(number: BigInt, size: usize) -> Self {
+        Self { number, size }
+    }
+
+    /// Parses decimal or `0x` prefixed hex string like `-12345` or `-0x3039`.
+    /// Fails if the number doesn't fit `size` bits
+    pub fn parse(string: &str, size: usize) -> AbiResult<Self> {
+        let number = parse_big_int(string)
+            .ok_or_else(|| AbiErrorKind::InvalidData(format!("`{}` is not a valid number", string)))?;
+        if !Self::fits(&number, size) {
+            bail!(AbiErrorKind::InvalidData(format!("value {} does not fit int{}", number, size)))
+        }
+        Ok(Self { number, size })
+    }
+
+    /// Checks if given number can be fit into given bits count
+    pub fn fits(number: &BigInt, size: usize) -> bool {
+        // `BigInt::bits` returns fewest bits necessary to express the number, not including
+        // the sign and it works well for all values except `-2^n`. Such values can be encoded
+        // using `n` bits, but `bits` function returns `n` (and plus one bit for sign) so we
+        // have to explicitly check such situation by comparing bits sizes of given number
+        // and increased number
+        if number.sign() == Sign::Minus && number.bits() != (number + BigInt::from(1)).bits() {
+            number.bits() <= size
+        } else {
+            number.bits() < size
+        }
+    }
 }
 
 
@@ -38,4 +70,65 @@ impl Uint {
     pub fn new(number: u128, size: usize) -> Self {
         Self { number: BigUint::from(number), size }
     }
+
+    pub fn from_big_uint(number: BigUint, size: usize) -> Self {
+        Self { number, size }
+    }
+
+    /// Parses decimal or `0x` prefixed hex string like `12345` or `0x3039`.
+    /// Fails if the number doesn't fit `size` bits
+    pub fn parse(string: &str, size: usize) -> AbiResult<Self> {
+        let number = parse_big_uint(string)
+            .ok_or_else(|| AbiErrorKind::InvalidData(format!("`{}` is not a valid unsigned number", string)))?;
+        if !Self::fits(&number, size) {
+            bail!(AbiErrorKind::InvalidData(format!("value {} does not fit uint{}", number, size)))
+        }
+        Ok(Self { number, size })
+    }
+
+    /// Checks if given number can be fit into given bits count
+    pub fn fits(number: &BigUint, size: usize) -> bool {
+        number.bits() <= size
+    }
+}
+
+/// Parses unsigned decimal or `0x` prefixed hex number. Signs and whitespaces are not allowed
+pub fn parse_big_uint(string: &str) -> Option<BigUint> {
+    let (digits, radix) = if string.starts_with("0x") || string.starts_with("0X") {
+        (&string[2..], 16)
+    } else {
+        (string, 10)
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    BigUint::parse_bytes(digits.as_bytes(), radix)
+}
+
+/// Parses decimal or `0x` prefixed hex number with optional `-` sign before the prefix
+pub fn parse_big_int(string: &str) -> Option<BigInt> {
+    if string.starts_with('-') {
+        parse_big_uint(&string[1..]).map(|number| BigInt::from_biguint(Sign::Minus, number))
+    } else {
+        parse_big_uint(string).map(|number| BigInt::from_biguint(Sign::Plus, number))
+    }
+}
+
+/// Format of integer values in JSON produced by decoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntFormat {
+    /// `0x` prefixed hex string like `"0x3039"` or `"-0x3039"`
+    Hex,
+    /// Decimal string like `"12345"`
+    Decimal,
+    /// JSON number if the value is within ±(2^53 - 1), so it is exactly represented by
+    /// double precision numbers of JavaScript and other JSON parsers, decimal string otherwise
+    Number,
+}
+
+impl Default for IntFormat {
+    fn default() -> Self {
+        IntFormat::Hex
+    }
 }
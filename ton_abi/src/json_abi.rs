@@ -14,9 +14,10 @@
 
 use ed25519_dalek::*;
 use serde_json::Value;
-use token::{Detokenizer, Tokenizer};
+use token::{Detokenizer, Pagination, Tokenizer};
 use ton_types::{BuilderData, SliceData};
-use {Contract, Function};
+use {AbiRegistry, Contract, Function, Token, ValueFormat};
+use registry::DecodedBody;
 use crate::error::*;
 
 /// Encodes `parameters` for given `function` of contract described by `abi` into `BuilderData`
@@ -34,7 +35,7 @@ pub fn encode_function_call(
 
     let v: Value = serde_json::from_str(&parameters).map_err(|err| AbiErrorKind::SerdeError(err))?;
 
-    let tokens = Tokenizer::tokenize_all(&function.input_params(), &v)?;
+    let tokens = Tokenizer::tokenize_all_with_path(&function.input_params(), &v, "input")?;
 
     function.encode_input(&tokens, internal, pair)
 }
@@ -53,7 +54,7 @@ pub fn prepare_function_call_for_sign(
 
     let v: Value = serde_json::from_str(&parameters).map_err(|err| AbiErrorKind::SerdeError(err))?;
 
-    let tokens = Tokenizer::tokenize_all(&function.input_params(), &v)?;
+    let tokens = Tokenizer::tokenize_all_with_path(&function.input_params(), &v, "input")?;
 
     function.create_unsigned_call(&tokens, false)
 }
@@ -73,6 +74,17 @@ pub fn decode_function_response(
     function: String,
     response: SliceData,
     internal: bool,
+) -> AbiResult<String> {
+    decode_function_response_with_format(abi, function, response, internal, ValueFormat::default())
+}
+
+/// Decodes output parameters returned by contract function call, values are written in given format
+pub fn decode_function_response_with_format(
+    abi: String,
+    function: String,
+    response: SliceData,
+    internal: bool,
+    format: ValueFormat,
 ) -> AbiResult<String> {
     let contract = Contract::load(abi.as_bytes())?;
 
@@ -80,7 +92,30 @@ pub fn decode_function_response(
 
     let tokens = function.decode_output(response, internal)?;
 
-    Detokenizer::detokenize(&function.output_params(), &tokens)
+    Detokenizer::detokenize_with_format(&function.output_params(), &tokens, format)
+}
+
+/// Decodes output parameters returned by contract function call. Arrays and maps are decoded
+/// lazily, if `pagination` is set only elements within it are returned for each of them.
+/// Values are written in given format
+pub fn decode_function_response_paginated(
+    abi: String,
+    function: String,
+    response: SliceData,
+    pagination: Option<Pagination>,
+    format: ValueFormat,
+) -> AbiResult<String> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let function = contract.function(&function)?;
+
+    let mut tokens = vec![];
+    for token in function.decode_output_lazy(response)? {
+        tokens.push(Token::new(&token.name, token.value.into_page(pagination)?));
+    }
+
+    // paginated fixed arrays don't match their types, so tokens are not checked
+    Detokenizer::detokenize_tokens_with_format(&tokens, format)
 }
 
 pub struct DecodedMessage {
@@ -93,12 +128,23 @@ pub fn decode_unknown_function_response(
     abi: String,
     response: SliceData,
     internal: bool,
+) -> AbiResult<DecodedMessage> {
+    decode_unknown_function_response_with_format(abi, response, internal, ValueFormat::default())
+}
+
+/// Decodes output parameters returned by some function call, values are written in given format.
+/// Returns parametes and function name
+pub fn decode_unknown_function_response_with_format(
+    abi: String,
+    response: SliceData,
+    internal: bool,
+    format: ValueFormat,
 ) -> AbiResult<DecodedMessage> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let result = contract.decode_output(response, internal)?;
 
-    let output = Detokenizer::detokenize(&result.params, &result.tokens)?;
+    let output = Detokenizer::detokenize_with_format(&result.params, &result.tokens, format)?;
 
     Ok(DecodedMessage {
         function_name: result.function_name,
@@ -106,17 +152,62 @@ pub fn decode_unknown_function_response(
     })
 }
 
+pub struct VerifiedSignature {
+    pub function_name: String,
+    pub valid: bool,
+    pub public_key: Vec<u8>
+}
+
+/// Checks signature of external function call. If `function` is not set it is
+/// detected by the function id stored in the body
+pub fn verify_function_call_signature(
+    abi: String,
+    function: Option<String>,
+    body: SliceData,
+) -> AbiResult<VerifiedSignature> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let function = match function {
+        Some(name) => contract.function(&name)?,
+        None => {
+            let mut data = body.clone();
+            data.checked_drain_reference()
+                .map_err(|err| AbiErrorKind::InvalidInputData(err.to_string()))?;
+            contract.function_by_id(Function::decode_id(data)?, true)?
+        }
+    };
+
+    let (valid, public_key) = function.verify_signature(body)?;
+
+    Ok(VerifiedSignature {
+        function_name: function.name.clone(),
+        valid,
+        public_key: public_key.to_bytes().to_vec()
+    })
+}
+
 /// Decodes output parameters returned by some function call. Returns parametes and function name
 pub fn decode_unknown_function_call(
     abi: String,
     response: SliceData,
     internal: bool,
+) -> AbiResult<DecodedMessage> {
+    decode_unknown_function_call_with_format(abi, response, internal, ValueFormat::default())
+}
+
+/// Decodes input parameters of some function call, values are written in given format.
+/// Returns parametes and function name
+pub fn decode_unknown_function_call_with_format(
+    abi: String,
+    response: SliceData,
+    internal: bool,
+    format: ValueFormat,
 ) -> AbiResult<DecodedMessage> {
     let contract = Contract::load(abi.as_bytes())?;
 
     let result = contract.decode_input(response, internal)?;
 
-    let input = Detokenizer::detokenize(&result.params, &result.tokens)?;
+    let input = Detokenizer::detokenize_with_format(&result.params, &result.tokens, format)?;
 
     Ok(DecodedMessage {
         function_name: result.function_name,
@@ -124,6 +215,61 @@ pub fn decode_unknown_function_call(
     })
 }
 
+/// Function call decoded together with the message kind and external call header
+pub struct DecodedFunctionCall {
+    pub function_name: String,
+    pub params: String,
+    /// `true` if body is decoded as internal message body
+    pub internal: bool,
+    /// Signer public key of external call with verified signature, `None` if body is internal
+    /// or not signed
+    pub public_key: Option<Vec<u8>>,
+    /// Timestamp of external call, `None` if body is internal or function doesn't use timestamp
+    pub time: Option<u64>,
+}
+
+/// Decodes input parameters of some function call, values are written in given format.
+/// If `internal` is `None` message kind is detected by the body. Returns parameters, function name,
+/// message kind and signer public key and timestamp of external call
+pub fn decode_unknown_function_call_with_header(
+    abi: String,
+    body: SliceData,
+    internal: Option<bool>,
+    format: ValueFormat,
+) -> AbiResult<DecodedFunctionCall> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let (result, header) = contract.decode_input_with_header(body, internal)?;
+
+    let input = Detokenizer::detokenize_with_format(&result.params, &result.tokens, format)?;
+
+    Ok(DecodedFunctionCall {
+        function_name: result.function_name,
+        params: input,
+        internal: header.is_none(),
+        public_key: header.as_ref()
+            .and_then(|header| header.public_key.as_ref())
+            .map(|key| key.to_bytes().to_vec()),
+        time: header.and_then(|header| header.time),
+    })
+}
+
+/// Decodes message body by one of the ABIs in `registry`, values are written in given format.
+/// Returns decoded body and its parameters as JSON
+pub fn decode_any_message_body(
+    registry: &AbiRegistry,
+    body: SliceData,
+    code_hash: Option<&[u8]>,
+    internal: Option<bool>,
+    format: ValueFormat,
+) -> AbiResult<(DecodedBody, String)> {
+    let decoded = registry.decode(body, code_hash, internal)?;
+
+    let params = Detokenizer::detokenize_with_format(&decoded.params, &decoded.tokens, format)?;
+
+    Ok((decoded, params))
+}
+
 /// Changes initial values for public contract variables
 pub fn update_contract_data(abi: &str, parameters: &str, data: SliceData) -> AbiResult<SliceData> {
     let contract = Contract::load(abi.as_bytes())?;
@@ -136,11 +282,25 @@ pub fn update_contract_data(abi: &str, parameters: &str, data: SliceData) -> Abi
         .map(|item| item.value.clone())
         .collect();
 
-    let tokens = Tokenizer::tokenize_all(&params[..], &data_json)?;
+    let tokens = Tokenizer::tokenize_all_with_path(&params[..], &data_json, "data")?;
 
     contract.update_data(data, &tokens)
 }
 
+/// Decodes public contract variables from contract data
+pub fn decode_contract_data(abi: &str, data: SliceData) -> AbiResult<String> {
+    let contract = Contract::load(abi.as_bytes())?;
+
+    let tokens = contract.decode_data(data)?;
+
+    let params: Vec<_> = tokens
+        .iter()
+        .map(|token| contract.data()[&token.name].value.clone())
+        .collect();
+
+    Detokenizer::detokenize(&params, &tokens)
+}
+
 #[cfg(test)]
 #[path = "tests/full_stack_tests.rs"]
 mod tests;
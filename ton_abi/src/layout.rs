@@ -0,0 +1,75 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Layouts of encoded parameters in a chain of cells.
+//!
+//! Encoded parameters are appended one by one to the current cell. When a parameter doesn't fit
+//! into the current cell, a new cell is started and referenced by the last reference of the
+//! previous one. Layout must match the one produced by the contract compiler for the ABI version,
+//! so every supported ABI version is listed in `AbiLayout::VERSIONS` along with its layout.
+//! A new version is added there only together with encoding vectors from the compiler.
+
+use crate::error::*;
+
+/// Strategy of packing encoded parameters into a chain of cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbiLayout {
+    /// ABI version 1. Parameters are packed greedily and the last reference of every cell
+    /// is reserved for the chain continuation
+    V1Greedy,
+}
+
+impl AbiLayout {
+    /// Supported ABI versions and their layouts, the latest version goes last
+    pub const VERSIONS: [(u8, AbiLayout); 1] = [(1, AbiLayout::V1Greedy)];
+
+    /// Returns layout used by given ABI version
+    pub fn from_version(version: u8) -> AbiResult<Self> {
+        match Self::VERSIONS.iter().find(|(supported, _)| *supported == version) {
+            Some((_, layout)) => Ok(*layout),
+            None => bail!(AbiErrorKind::WrongVersion(version))
+        }
+    }
+
+    /// Returns ABI version using this layout
+    pub fn version(&self) -> u8 {
+        Self::VERSIONS.iter()
+            .find(|(_, layout)| layout == self)
+            .map(|(version, _)| *version)
+            .expect("every layout is listed in AbiLayout::VERSIONS")
+    }
+
+    /// Returns list of supported versions for error messages, e.g. "`1` or `2`"
+    pub fn versions_text() -> String {
+        Self::VERSIONS.iter()
+            .map(|(version, _)| format!("`{}`", version))
+            .collect::<Vec<String>>()
+            .join(" or ")
+    }
+
+    /// Checks if encoded parameter with given size can be appended to the cell with given
+    /// free space
+    pub(crate) fn fits(&self, bits_free: usize, refs_free: usize, bits: usize, refs: usize) -> bool {
+        match self {
+            // last reference of every cell is reserved for the chain continuation
+            AbiLayout::V1Greedy => bits_free >= bits && refs_free > refs,
+        }
+    }
+}
+
+impl Default for AbiLayout {
+    fn default() -> Self {
+        AbiLayout::V1Greedy
+    }
+}
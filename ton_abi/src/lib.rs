@@ -31,6 +31,8 @@ extern crate error_chain;
 extern crate base64;
 extern crate chrono;
 extern crate failure;
+extern crate crc_any;
+extern crate lazy_static;
 
 pub mod contract;
 pub mod function;
@@ -41,16 +43,27 @@ pub mod param_type;
 pub mod token;
 pub mod json_abi;
 pub mod error;
+pub mod codegen;
+pub mod signature;
+pub mod validator;
+pub mod layout;
+pub mod registry;
+pub mod format;
+pub mod schema;
 
 pub use param_type::ParamType;
 pub use contract::{Contract, DataItem};
 pub use token::{Token, TokenValue};
-pub use function::Function;
+pub use function::{ExternalHeader, Function};
 pub use event::Event;
 pub use json_abi::*;
 pub use param::Param;
-pub use int::{Int, Uint};
+pub use int::{Int, IntFormat, Uint};
 pub use error::*;
+pub use validator::{validate, ValidationError};
+pub use layout::AbiLayout;
+pub use registry::AbiRegistry;
+pub use format::{AddressFormat, BytesFormat, CellFormat, ValueFormat};
 
 #[cfg(test)]
 extern crate rand;
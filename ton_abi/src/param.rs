@@ -82,6 +82,12 @@ impl<'a> Deserialize<'a> for Param {
                 } else {
                    ParamType::Map(key_type, value_type)
                 },
+            ParamType::Optional(inner_type) =>
+                if let ParamType::Tuple(_) = *inner_type {
+                    ParamType::Optional(Box::new(ParamType::Tuple(serde_param.components)))
+                } else {
+                    ParamType::Optional(inner_type)
+                },
             _ => result.kind,
         };
 
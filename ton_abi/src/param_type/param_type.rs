@@ -44,7 +44,11 @@ pub enum ParamType {
     /// fixed size byte array
     FixedBytes(usize),
     /// Nanograms
-    Gram
+    Gram,
+    /// UTF-8 string stored as bytes chain
+    String,
+    /// optional(T): value of the type T or nothing
+    Optional(Box<ParamType>),
 }
 
 impl fmt::Display for ParamType {
@@ -80,15 +84,24 @@ impl ParamType {
             ParamType::Bytes => format!("bytes"),
             ParamType::FixedBytes(size) => format!("fixedbytes{}", size),
             ParamType::Gram => format!("gram"),
+            ParamType::String => format!("string"),
+            ParamType::Optional(ref param_type) => format!("optional({})", param_type.type_signature()),
         }
     }
 
-    /// Returns type bit_len for hashmap key
+    /// Returns type bit_len for hashmap key. Zero means that type can not be used as a key
     pub fn bit_len(&self) -> usize {
         match self {
-            ParamType::Uint(size) => *size,
-            ParamType::Int(size) => *size,
+            ParamType::Uint(size) | ParamType::Int(size) if *size <= Self::MAX_MAP_KEY_INT_SIZE => *size,
+            // addr_std$10 anycast:(Maybe Anycast) workchain_id:int8 address:bits256
+            ParamType::Address => 267,
+            ParamType::FixedBytes(size) if *size <= Self::MAX_MAP_KEY_BYTES => *size * 8,
             _ => 0
         }
     }
+
+    /// Max integer width allowed for hashmap key
+    pub const MAX_MAP_KEY_INT_SIZE: usize = 256;
+    /// Max fixed bytes length allowed for hashmap key (key must fit into one cell)
+    pub const MAX_MAP_KEY_BYTES: usize = 127;
 }
@@ -64,7 +64,7 @@ impl Reader {
                 ParamType::Uint(len)
             },
             s if s.starts_with("map(") && s.ends_with(")") => {
-                let types: Vec<&str> = name[5..name.len() - 1].split(",").collect();
+                let types: Vec<&str> = name[4..name.len() - 1].split(",").collect();
                 if types.len() != 2 {
                     bail!(AbiErrorKind::InvalidName(name.to_owned()));
                 }
@@ -72,13 +72,12 @@ impl Reader {
                 let key_type = Reader::read(types[0])?;
                 let value_type = Reader::read(types[1])?;
 
-                match key_type
-                {
-                    ParamType::Int(_) | ParamType::Uint(_) =>
-                        ParamType::Map(Box::new(key_type), Box::new(value_type)),
-                    _ => bail!(AbiErrorKind::InvalidName(
-                            "Only int and uint types can be map keys".to_owned())),
+                if key_type.bit_len() == 0 {
+                    bail!(AbiErrorKind::InvalidName(
+                        "Only int, uint, address and fixedbytes types can be map keys".to_owned()));
                 }
+
+                ParamType::Map(Box::new(key_type), Box::new(value_type))
             },
             "cell" => {
                 ParamType::Cell
@@ -92,6 +91,13 @@ impl Reader {
             "bytes" => {
                 ParamType::Bytes
             }
+            "string" => {
+                ParamType::String
+            }
+            s if s.starts_with("optional(") && s.ends_with(")") => {
+                let inner_type = Reader::read(&name[9..name.len() - 1])?;
+                ParamType::Optional(Box::new(inner_type))
+            }
             s if s.starts_with("fixedbytes") => {
                 let len = usize::from_str_radix(&s[10..], 10)
                     .map_err(|_| AbiErrorKind::InvalidName(name.to_owned()))?;
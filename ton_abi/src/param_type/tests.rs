@@ -41,6 +41,48 @@ mod reader_tests {
             Reader::read("tuple[4]").unwrap(),
             ParamType::FixedArray(Box::new(ParamType::Tuple(vec![])), 4));
     }
+
+    #[test]
+    fn test_read_map() {
+        assert_eq!(
+            Reader::read("map(uint8,bool)").unwrap(),
+            ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::Bool)));
+
+        assert_eq!(
+            Reader::read("map(int256,cell)").unwrap(),
+            ParamType::Map(Box::new(ParamType::Int(256)), Box::new(ParamType::Cell)));
+
+        assert_eq!(
+            Reader::read("map(address,uint128)").unwrap(),
+            ParamType::Map(Box::new(ParamType::Address), Box::new(ParamType::Uint(128))));
+
+        assert_eq!(
+            Reader::read("map(fixedbytes32,address)").unwrap(),
+            ParamType::Map(Box::new(ParamType::FixedBytes(32)), Box::new(ParamType::Address)));
+
+        assert!(Reader::read("map(uint257,bool)").is_err());
+        assert!(Reader::read("map(bytes,bool)").is_err());
+        assert!(Reader::read("map(bool,bool)").is_err());
+    }
+
+    #[test]
+    fn test_read_string_and_optional() {
+        assert_eq!(Reader::read("string").unwrap(), ParamType::String);
+        assert_eq!(
+            Reader::read("optional(uint8)").unwrap(),
+            ParamType::Optional(Box::new(ParamType::Uint(8))));
+        assert_eq!(
+            Reader::read("optional(string)[]").unwrap(),
+            ParamType::Array(Box::new(ParamType::Optional(Box::new(ParamType::String)))));
+        assert_eq!(
+            Reader::read("map(uint8,optional(bytes))").unwrap(),
+            ParamType::Map(
+                Box::new(ParamType::Uint(8)),
+                Box::new(ParamType::Optional(Box::new(ParamType::Bytes)))));
+
+        assert!(Reader::read("optional(uint8").is_err());
+        assert!(Reader::read("optional()").is_err());
+    }
 }
 
 mod param_type_tests {
@@ -81,6 +123,11 @@ mod param_type_tests {
         assert_eq!(
             ParamType::FixedArray(Box::new(ParamType::Tuple(tuple_params)), 4).type_signature(),
             "(uint123,int8)[4]".to_owned());
+
+        assert_eq!(ParamType::String.type_signature(), "string".to_owned());
+        assert_eq!(
+            ParamType::Optional(Box::new(ParamType::Array(Box::new(ParamType::String)))).type_signature(),
+            "optional(string[])".to_owned());
     }
 }
 
@@ -0,0 +1,196 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Registry of contract ABIs decoding message bodies of unknown contracts.
+//!
+//! Functions and events of all registered ABIs are indexed by the ids their message bodies
+//! start with. A body is decoded by every candidate with the matching id in the order ABIs
+//! were added until one of them decodes the body completely. If the code hash of the
+//! contract is registered, only the ABI bound to it is tried.
+
+use std::collections::HashMap;
+use {Contract, ExternalHeader, Function, Param, Token};
+use ton_types::SliceData;
+use crate::error::*;
+
+/// Kind of decoded message body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BodyType {
+    /// Function call
+    FunctionInput,
+    /// Function answer
+    FunctionOutput,
+    /// Emitted event
+    Event,
+}
+
+/// Message body decoded by one of the registered ABIs
+#[derive(Debug, Clone)]
+pub struct DecodedBody {
+    /// Name the ABI is registered with
+    pub abi_name: String,
+    pub body_type: BodyType,
+    /// Function or event name
+    pub name: String,
+    pub params: Vec<Param>,
+    pub tokens: Vec<Token>,
+    /// Header of external function call, `None` for other bodies
+    pub header: Option<ExternalHeader>,
+}
+
+// Function or event which message bodies start with indexed id
+#[derive(Debug, Clone)]
+struct Candidate {
+    abi: usize,
+    body_type: BodyType,
+    name: String,
+}
+
+/// Collection of named contract ABIs indexed by function and event ids and code hashes
+#[derive(Debug, Clone, Default)]
+pub struct AbiRegistry {
+    abis: Vec<(String, Contract)>,
+    candidates: HashMap<u32, Vec<Candidate>>,
+    code_hashes: HashMap<Vec<u8>, usize>,
+}
+
+impl AbiRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds contract ABI with unique name
+    pub fn add(&mut self, name: &str, contract: Contract) -> AbiResult<()> {
+        if self.abi_index(name).is_some() {
+            bail!(AbiErrorKind::InvalidName(format!("ABI `{}` is already registered", name)));
+        }
+        let abi = self.abis.len();
+
+        let mut functions: Vec<&Function> = contract.functions().values().collect();
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        for function in functions {
+            self.index(function.get_input_id(), abi, BodyType::FunctionInput, &function.name);
+            self.index(function.get_output_id(), abi, BodyType::FunctionOutput, &function.name);
+        }
+        let mut events: Vec<_> = contract.events().values().collect();
+        events.sort_by(|a, b| a.name.cmp(&b.name));
+        for event in events {
+            self.index(event.get_id(), abi, BodyType::Event, &event.name);
+        }
+
+        self.abis.push((name.to_owned(), contract));
+        Ok(())
+    }
+
+    /// Loads contract ABI from JSON and adds it with unique name
+    pub fn add_json(&mut self, name: &str, abi: &str) -> AbiResult<()> {
+        self.add(name, Contract::load(abi.as_bytes())?)
+    }
+
+    /// Binds code hash to registered ABI, bodies of contracts with this code hash
+    /// are decoded by this ABI only
+    pub fn add_code_hash(&mut self, name: &str, code_hash: &[u8]) -> AbiResult<()> {
+        let abi = self.abi_index(name)
+            .ok_or_else(|| AbiErrorKind::InvalidName(format!("ABI `{}` is not registered", name)))?;
+        self.code_hashes.insert(code_hash.to_vec(), abi);
+        Ok(())
+    }
+
+    /// Returns registered ABI with given name
+    pub fn contract(&self, name: &str) -> Option<&Contract> {
+        self.abi_index(name).map(|abi| &self.abis[abi].1)
+    }
+
+    /// Returns names of registered ABIs in the order they were added
+    pub fn names(&self) -> Vec<&str> {
+        self.abis.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    fn abi_index(&self, name: &str) -> Option<usize> {
+        self.abis.iter().position(|(abi_name, _)| abi_name == name)
+    }
+
+    fn index(&mut self, id: u32, abi: usize, body_type: BodyType, name: &str) {
+        self.candidates.entry(id).or_insert_with(Vec::new).push(Candidate {
+            abi,
+            body_type,
+            name: name.to_owned(),
+        });
+    }
+
+    /// Decodes message body by the first registered function or event which decodes it completely.
+    /// `code_hash` of the contract restricts candidates to the ABI bound to it if there is one.
+    /// `internal` is the message kind of function call bodies, it is detected if not set
+    pub fn decode(&self, body: SliceData, code_hash: Option<&[u8]>, internal: Option<bool>)
+    -> AbiResult<DecodedBody> {
+        let id = Function::decode_id(body.clone())?;
+        let bound_abi = code_hash.and_then(|hash| self.code_hashes.get(hash));
+
+        let candidates = self.candidates.get(&id)
+            .map(|candidates| candidates.iter()
+                .filter(|candidate| bound_abi.map(|abi| *abi == candidate.abi).unwrap_or(true))
+                .collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        if candidates.is_empty() {
+            bail!(AbiErrorKind::InvalidFunctionId(id));
+        }
+
+        let mut errors = Vec::new();
+        for candidate in candidates {
+            match self.decode_by(candidate, body.clone(), internal) {
+                Ok(decoded) => return Ok(decoded),
+                Err(err) => errors.push(format!("{}.{}: {}", self.abis[candidate.abi].0, candidate.name, err)),
+            }
+        }
+
+        bail!(AbiErrorKind::InvalidInputData(
+            format!("body can not be decoded by any candidate ({})", errors.join("; "))))
+    }
+
+    fn decode_by(&self, candidate: &Candidate, body: SliceData, internal: Option<bool>) -> AbiResult<DecodedBody> {
+        let (abi_name, contract) = &self.abis[candidate.abi];
+
+        let (params, tokens, header) = match candidate.body_type {
+            BodyType::FunctionInput => {
+                let function = contract.function(&candidate.name)?;
+                let (tokens, header) = function.decode_input_with_header(body, internal)?;
+                (function.input_params(), tokens, header)
+            }
+            BodyType::FunctionOutput => {
+                let function = contract.function(&candidate.name)?;
+                (function.output_params(), function.decode_output(body, false)?, None)
+            }
+            BodyType::Event => {
+                let event = contract.events().get(&candidate.name)
+                    .ok_or_else(|| AbiErrorKind::InvalidName(candidate.name.clone()))?;
+                (event.input_params(), event.decode_input(body)?, None)
+            }
+        };
+
+        Ok(DecodedBody {
+            abi_name: abi_name.clone(),
+            body_type: candidate.body_type,
+            name: candidate.name.clone(),
+            params,
+            tokens,
+            header,
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_registry.rs"]
+mod tests;
@@ -0,0 +1,268 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! JSON Schema (draft-07) generator describing JSON accepted by `Tokenizer`.
+//!
+//! Contract schema has the following shape:
+//!
+//! ```json
+//! {
+//!     "$schema": "http://json-schema.org/draft-07/schema#",
+//!     "definitions": { "cellTree": { ... } },
+//!     "functions": { "<name>": { "input": { ... }, "output": { ... } } },
+//!     "events": { "<name>": { ... } },
+//!     "data": { ... }
+//! }
+//! ```
+//!
+//! Nested schemas refer to `#/definitions` of the contract schema, so they should be
+//! validated as parts of the whole document. `params_schema` produces standalone schema.
+//!
+//! Schemas follow tokenizer rules: integers are accepted as JSON numbers within 64 bits or as
+//! decimal or `0x` prefixed hex strings, value range of strings can not be expressed by schema
+//! and is checked by tokenizer only.
+
+use serde_json::{json, Map, Value};
+use {Contract, Param, ParamType};
+use format::{ADDR_NONE, CellTreeType};
+
+/// JSON Schema version of generated schemas
+pub const SCHEMA_VERSION: &str = "http://json-schema.org/draft-07/schema#";
+
+const CELL_TREE_REF: &str = "#/definitions/cellTree";
+
+/// Builds schema of the whole contract ABI: inputs and outputs of every function,
+/// every event and the data section
+pub fn contract_schema(contract: &Contract) -> Value {
+    let mut functions = Map::new();
+    let mut names: Vec<&String> = contract.functions().keys().collect();
+    names.sort();
+    for name in names {
+        let function = &contract.functions()[name];
+        functions.insert(name.clone(), json!({
+            "input": object_schema(&function.inputs),
+            "output": object_schema(&function.outputs),
+        }));
+    }
+
+    let mut events = Map::new();
+    let mut names: Vec<&String> = contract.events().keys().collect();
+    names.sort();
+    for name in names {
+        events.insert(name.clone(), object_schema(&contract.events()[name].inputs));
+    }
+
+    let mut items: Vec<_> = contract.data().values().collect();
+    items.sort_by_key(|item| item.key);
+    let data: Vec<Param> = items.into_iter().map(|item| item.value.clone()).collect();
+
+    json!({
+        "$schema": SCHEMA_VERSION,
+        "definitions": definitions(),
+        "functions": functions,
+        "events": events,
+        "data": object_schema(&data),
+    })
+}
+
+/// Builds standalone schema of JSON object with given parameters
+pub fn params_schema(params: &[Param]) -> Value {
+    let mut schema = object_schema(params);
+    schema["$schema"] = json!(SCHEMA_VERSION);
+    schema["definitions"] = definitions();
+    schema
+}
+
+fn definitions() -> Value {
+    let cell_types: Vec<Value> = [
+        CellTreeType::Ordinary,
+        CellTreeType::PrunedBranch,
+        CellTreeType::LibraryReference,
+        CellTreeType::MerkleProof,
+        CellTreeType::MerkleUpdate,
+    ].iter().map(|cell_type| json!(cell_type)).collect();
+
+    json!({
+        "cellTree": {
+            "type": "object",
+            "properties": {
+                "cellType": { "enum": cell_types },
+                "bits": { "type": "string", "pattern": "^([0-9a-fA-F]{2})*$" },
+                "bitLength": { "type": "integer", "minimum": 0, "maximum": 1023 },
+                "refs": { "type": "array", "maxItems": 4, "items": { "$ref": CELL_TREE_REF } },
+            },
+            "required": ["bits", "bitLength"],
+            "additionalProperties": false,
+        }
+    })
+}
+
+/// Every parameter is required and unknown parameters are rejected
+fn object_schema(params: &[Param]) -> Value {
+    let mut properties = Map::new();
+    for param in params {
+        properties.insert(param.name.clone(), type_schema(&param.kind));
+    }
+    let required: Vec<&String> = params.iter().map(|param| &param.name).collect();
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Builds schema of a value of given type. Refers to `#/definitions/cellTree` for cells
+pub fn type_schema(kind: &ParamType) -> Value {
+    let mut schema = match kind {
+        ParamType::Unknown => return json!(false),
+        ParamType::Uint(size) => uint_schema(*size),
+        ParamType::Int(size) => int_schema(*size),
+        ParamType::Gram => uint_schema(120),
+        ParamType::Bool => json!({
+            "anyOf": [{ "type": "boolean" }, { "enum": ["true", "false"] }]
+        }),
+        ParamType::Tuple(params) => object_schema(params),
+        ParamType::Array(item_type) => json!({
+            "type": "array",
+            "items": type_schema(item_type),
+        }),
+        ParamType::FixedArray(item_type, size) => json!({
+            "type": "array",
+            "items": type_schema(item_type),
+            "minItems": size,
+            "maxItems": size,
+        }),
+        ParamType::Cell => json!({
+            "anyOf": [
+                { "type": "string", "description": "base64 or hex bag of cells, empty string is empty cell" },
+                { "$ref": CELL_TREE_REF },
+            ]
+        }),
+        ParamType::Map(key_type, value_type) => json!({
+            "type": "object",
+            "propertyNames": key_schema(key_type),
+            "additionalProperties": type_schema(value_type),
+        }),
+        ParamType::Address => address_schema(),
+        ParamType::Bytes => bytes_schema(None),
+        ParamType::FixedBytes(size) => bytes_schema(Some(*size)),
+        ParamType::String => json!({ "type": "string" }),
+        ParamType::Optional(inner_type) => json!({
+            "anyOf": [{ "type": "null" }, type_schema(inner_type)]
+        }),
+    };
+    schema["description"] = json!(kind.type_signature());
+    schema
+}
+
+/// Map keys are tokenized from JSON strings, so only string forms of key types are accepted
+fn key_schema(key_type: &ParamType) -> Value {
+    if key_type.bit_len() == 0 {
+        return json!(false);
+    }
+    match key_type {
+        ParamType::Uint(_) => uint_string_schema(),
+        ParamType::Int(_) => int_string_schema(),
+        ParamType::Address => address_schema(),
+        ParamType::FixedBytes(size) => hex_schema(Some(*size)),
+        _ => json!(false),
+    }
+}
+
+fn uint_string_schema() -> Value {
+    json!({ "type": "string", "pattern": "^(0[xX][0-9a-fA-F]+|[0-9]+)$" })
+}
+
+fn int_string_schema() -> Value {
+    json!({ "type": "string", "pattern": "^-?(0[xX][0-9a-fA-F]+|[0-9]+)$" })
+}
+
+/// JSON numbers are accepted within 64 bits only
+fn uint_schema(size: usize) -> Value {
+    let maximum = if size >= 64 { u64::max_value() } else { (1u64 << size) - 1 };
+    json!({
+        "anyOf": [
+            { "type": "integer", "minimum": 0, "maximum": maximum },
+            uint_string_schema(),
+        ]
+    })
+}
+
+/// JSON numbers are accepted within signed or unsigned 64 bits
+fn int_schema(size: usize) -> Value {
+    let (minimum, maximum) = match size {
+        0 => (json!(0), json!(0)),
+        1..=63 => (json!(-(1i64 << (size - 1))), json!((1i64 << (size - 1)) - 1)),
+        64 => (json!(i64::min_value()), json!(i64::max_value())),
+        _ => (json!(i64::min_value()), json!(u64::max_value())),
+    };
+    json!({
+        "anyOf": [
+            { "type": "integer", "minimum": minimum, "maximum": maximum },
+            int_string_schema(),
+        ]
+    })
+}
+
+fn address_schema() -> Value {
+    json!({
+        "anyOf": [
+            { "enum": ["", ADDR_NONE] },
+            { "type": "string", "pattern": "^-?[0-9]+:[0-9a-fA-F]+$" },
+            { "type": "string", "pattern": "^[A-Za-z0-9+/_-]{48}$" },
+        ]
+    })
+}
+
+/// Fixed bytes longer than declared size are truncated by tokenizer
+fn hex_schema(size: Option<usize>) -> Value {
+    let mut schema = json!({ "type": "string", "pattern": "^([0-9a-fA-F]{2})*$" });
+    if let Some(size) = size {
+        schema["minLength"] = json!(size * 2);
+    }
+    schema
+}
+
+fn marker_schema(marker: &str, schema: Value) -> Value {
+    let mut properties = Map::new();
+    properties.insert(marker.to_owned(), schema);
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": [marker],
+        "additionalProperties": false,
+    })
+}
+
+fn bytes_schema(size: Option<usize>) -> Value {
+    let mut utf8 = json!({ "type": "string" });
+    if let Some(size) = size {
+        // one character takes up to 4 bytes in UTF-8
+        utf8["minLength"] = json!((size + 3) / 4);
+    }
+    json!({
+        "anyOf": [
+            hex_schema(size),
+            marker_schema("hex", hex_schema(size)),
+            marker_schema("base64", json!({ "type": "string", "pattern": "^[A-Za-z0-9+/]*={0,2}$" })),
+            marker_schema("utf8", utf8),
+        ]
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/test_schema.rs"]
+mod tests;
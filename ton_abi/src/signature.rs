@@ -0,0 +1,239 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Human-readable function and event signatures.
+//!
+//! Signatures look like `transfer(address dest, uint128 value) returns (bool ok)`.
+//! Tuples are written as parenthesized parameter lists, e.g. `(uint8 a, bool b)[] items`,
+//! maps as `map(uint32,(uint8 a, bool b))`, optional values as `optional(string)`.
+//! Parameter names can be omitted, unnamed parameters are called `value0`, `value1` and so on.
+
+use {AbiLayout, Event, Function, Param, ParamType};
+use param_type::Reader;
+use validator::check_type;
+use crate::error::*;
+
+#[cfg(test)]
+#[path = "tests/test_signature.rs"]
+mod tests;
+
+impl Function {
+    /// Creates function from human-readable signature like
+    /// `transfer(address dest, uint128 value) returns (bool)`.
+    /// Leading `function` keyword is optional. Timestamp header is enabled as it is by default in ABI JSON
+    pub fn from_signature(signature: &str) -> AbiResult<Self> {
+        let mut parser = Parser::new(signature);
+        parser.keyword("function");
+        let name = parser.name()?
+            .ok_or_else(|| parser.error("function name expected"))?;
+        let inputs = parser.param_list()?;
+        let outputs = if parser.keyword("returns") {
+            parser.param_list()?
+        } else {
+            Vec::new()
+        };
+        parser.end()?;
+
+        Ok(Function {
+            name,
+            inputs,
+            outputs,
+            id: None,
+            set_time: true,
+            layout: AbiLayout::default(),
+        })
+    }
+}
+
+impl Event {
+    /// Creates event from human-readable signature like `event Transfer(address dest, uint128 value)`.
+    /// Leading `event` keyword is optional
+    pub fn from_signature(signature: &str) -> AbiResult<Self> {
+        let mut parser = Parser::new(signature);
+        parser.keyword("event");
+        let name = parser.name()?
+            .ok_or_else(|| parser.error("event name expected"))?;
+        let inputs = parser.param_list()?;
+        parser.end()?;
+
+        Ok(Event {
+            name,
+            inputs,
+            id: None,
+            layout: AbiLayout::default(),
+        })
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self { source, chars: source.chars().collect(), pos: 0 }
+    }
+
+    fn error(&self, msg: &str) -> AbiError {
+        AbiErrorKind::InvalidName(
+            format!("{} at position {} in `{}`", msg, self.pos, self.source)).into()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> AbiResult<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("`{}` expected", c)))
+        }
+    }
+
+    fn end(&mut self) -> AbiResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("unexpected symbol")),
+        }
+    }
+
+    // Reads identifier, returns `None` if there is no identifier at current position
+    fn name(&mut self) -> AbiResult<Option<String>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.pos < self.chars.len() &&
+            (self.chars[self.pos].is_ascii_alphanumeric() || self.chars[self.pos] == '_')
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+        if self.chars[start].is_ascii_digit() {
+            self.pos = start;
+            return Err(self.error("identifier can not start with digit"));
+        }
+        Ok(Some(self.chars[start..self.pos].iter().collect()))
+    }
+
+    // Consumes keyword if it is followed by another identifier or parameters list
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let start = self.pos;
+        match self.name() {
+            Ok(Some(ref name)) if name == keyword => {
+                match self.peek() {
+                    Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '(' => return true,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        self.pos = start;
+        false
+    }
+
+    fn param_list(&mut self) -> AbiResult<Vec<Param>> {
+        self.expect('(')?;
+        let mut params = Vec::new();
+        if self.eat(')') {
+            return Ok(params);
+        }
+        loop {
+            let kind = self.param_type()?;
+            let name = self.name()?
+                .unwrap_or_else(|| format!("value{}", params.len()));
+            params.push(Param { name, kind });
+            if self.eat(')') {
+                return Ok(params);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn param_type(&mut self) -> AbiResult<ParamType> {
+        let start = self.pos;
+        let mut kind = if self.peek() == Some('(') {
+            ParamType::Tuple(self.param_list()?)
+        } else {
+            let name = self.name()?
+                .ok_or_else(|| self.error("type expected"))?;
+            match name.as_str() {
+                "map" => {
+                    self.expect('(')?;
+                    let key = self.param_type()?;
+                    self.expect(',')?;
+                    let value = self.param_type()?;
+                    self.expect(')')?;
+                    ParamType::Map(Box::new(key), Box::new(value))
+                }
+                "optional" => {
+                    self.expect('(')?;
+                    let inner = self.param_type()?;
+                    self.expect(')')?;
+                    ParamType::Optional(Box::new(inner))
+                }
+                "tuple" => {
+                    self.pos = start;
+                    return Err(self.error("tuple components must be listed in parentheses"));
+                }
+                _ => Reader::read(&name).map_err(|_| {
+                    self.pos = start;
+                    self.error(&format!("unknown type `{}`", name))
+                })?
+            }
+        };
+
+        while self.eat('[') {
+            let size_start = self.pos;
+            while self.pos < self.chars.len() && self.chars[self.pos].is_ascii_digit() {
+                self.pos += 1;
+            }
+            kind = if size_start == self.pos {
+                ParamType::Array(Box::new(kind))
+            } else {
+                let size: String = self.chars[size_start..self.pos].iter().collect();
+                let size = size.parse::<usize>()
+                    .map_err(|_| self.error("invalid array size"))?;
+                ParamType::FixedArray(Box::new(kind), size)
+            };
+            self.expect(']')?;
+        }
+
+        check_type(&kind).map_err(|msg| {
+            self.pos = start;
+            self.error(&msg)
+        })?;
+
+        Ok(kind)
+    }
+}
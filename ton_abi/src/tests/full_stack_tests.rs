@@ -399,4 +399,29 @@ fn test_update_contract_data() {
     .unwrap();
 
     assert_eq!(owner_slice.get_bytestring(0), vec![0x22; 32]);
+}
+
+#[test]
+fn test_decode_contract_data() {
+    let mut test_map = HashmapE::with_bit_len(Contract::DATA_MAP_KEYLEN);
+    test_map.set(
+        0u64.write_to_new_cell().unwrap().into(),
+        &BuilderData::with_raw(vec![0x33; 32], 256).unwrap().into(),
+    ).unwrap();
+
+    let params = r#"{
+        "subscription": "0:1111111111111111111111111111111111111111111111111111111111111111",
+        "owner": "0x2222222222222222222222222222222222222222222222222222222222222222"
+     }
+    "#;
+
+    let data = test_map.write_to_new_cell().unwrap();
+    let new_data = update_contract_data(WALLET_ABI, params, data.into()).unwrap();
+
+    let decoded = decode_contract_data(WALLET_ABI, new_data.clone()).unwrap();
+    assert_eq!(
+        decoded,
+        r#"{"owner":"0x2222222222222222222222222222222222222222222222222222222222222222","subscription":"0:1111111111111111111111111111111111111111111111111111111111111111"}"#);
+
+    assert_eq!(Contract::get_pubkey(new_data).unwrap(), Some(vec![0x33; 32]));
 }
\ No newline at end of file
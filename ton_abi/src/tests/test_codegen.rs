@@ -0,0 +1,167 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+
+const TEST_ABI: &str = r#"
+{
+    "ABI version": 1,
+    "functions": [{
+            "name": "transfer",
+            "inputs": [
+                {"name": "dest", "type": "address"},
+                {"name": "value", "type": "uint128"},
+                {"name": "type", "type": "uint8"},
+                {"name": "payload", "type": "map(uint32,bytes)"}
+            ],
+            "outputs": [
+                {"name": "result", "type": "tuple", "components": [
+                    {"name": "ok", "type": "bool"},
+                    {"name": "code", "type": "int32[]"}
+                ]}
+            ]
+        }],
+    "events": [{
+            "name": "transfer_done",
+            "inputs": [
+                {"name": "value", "type": "gram"}
+            ]
+        }],
+    "data": []
+}"#;
+
+#[test]
+fn test_generate_bindings() {
+    let code = generate(TEST_ABI).unwrap();
+
+    assert!(code.contains("pub struct TransferInput {"));
+    assert!(code.contains("    pub dest: MsgAddress,\n"));
+    assert!(code.contains("    pub value: Uint128,\n"));
+    assert!(code.contains("    pub type_: Uint8,\n"));
+    assert!(code.contains("    pub payload: HashMap<String, Vec<u8>>,\n"));
+    assert!(code.contains("Token::new(\"type\", TokenValue::Uint(self.type_.into_value())),"));
+    assert!(code.contains(
+        "TokenValue::Map(ParamType::Uint(32), self.payload.into_iter().map(|(k, v)| (k, TokenValue::Bytes(v))).collect())"));
+
+    assert!(code.contains("pub struct TransferOutput {"));
+    assert!(code.contains("    pub result: TransferOutputResult,\n"));
+    assert!(code.contains("pub struct TransferOutputResult {"));
+    assert!(code.contains("    pub code: Vec<Int32>,\n"));
+    assert!(code.contains("map(|v| -> AbiResult<_> { Ok(Int32::from_value(codegen::int(v)?)?) })"));
+
+    // integer types are generated once for every used width
+    assert_eq!(code.matches("pub struct Uint128(Uint);").count(), 1);
+    assert!(code.contains("pub struct Uint8(Uint);"));
+    assert!(code.contains("pub struct Uint32(Uint);"));
+    assert!(code.contains("pub struct Int32(Int);"));
+
+    // ABI is parsed once
+    assert!(code.contains("codegen::lazy_static! {"));
+    assert!(code.contains("pub fn contract() -> AbiResult<&'static Contract> {"));
+
+    assert!(code.contains("pub fn encode_transfer(input: TransferInput, internal: bool, pair: Option<&Keypair>) -> AbiResult<BuilderData> {"));
+    assert!(code.contains("pub fn decode_transfer_input(body: SliceData, internal: bool) -> AbiResult<TransferInput> {"));
+    assert!(code.contains("pub fn decode_transfer_output(body: SliceData, internal: bool) -> AbiResult<TransferOutput> {"));
+
+    assert!(code.contains("pub struct TransferDoneEvent {"));
+    assert!(code.contains("    pub value: Grams,\n"));
+    assert!(code.contains("pub fn decode_transfer_done_event(body: SliceData) -> AbiResult<TransferDoneEvent> {"));
+}
+
+#[test]
+fn test_generated_helpers() {
+    let tokens = vec![
+        Token::new("a", TokenValue::Uint(Uint::new(1, 8))),
+        Token::new("b", TokenValue::Bool(true)),
+    ];
+
+    assert!(check_count(&tokens, 2).is_ok());
+    assert!(check_count(&tokens, 3).is_err());
+
+    let mut iter = tokens.into_iter();
+    assert_eq!(uint(next_value(&mut iter, "a").unwrap()).unwrap(), Uint::new(1, 8));
+    assert!(next_value(&mut iter, "c").is_err());
+    assert!(next_value(&mut iter, "b").is_err());
+
+    assert!(check_uint(&Uint::new(255, 8), 8).is_ok());
+    assert!(check_uint(&Uint::new(256, 8), 8).is_err());
+    assert!(check_uint(&Uint::new(1, 16), 8).is_err());
+    assert!(check_int(&Int::new(-128, 8), 8).is_ok());
+    assert!(check_int(&Int::new(128, 8), 8).is_err());
+    assert!(check_int(&Int::new(1, 32), 8).is_err());
+}
+
+#[test]
+fn test_generate_string_and_optional() {
+    let abi = r#"{
+        "ABI version": 1,
+        "functions": [{
+            "name": "setName",
+            "inputs": [
+                {"name": "name", "type": "string"},
+                {"name": "symbol", "type": "optional(string)"}
+            ],
+            "outputs": []
+        }],
+        "events": [],
+        "data": []
+    }"#;
+    let code = generate(abi).unwrap();
+
+    assert!(code.contains("    pub name: String,\n"));
+    assert!(code.contains("    pub symbol: Option<String>,\n"));
+    assert!(code.contains(
+        "TokenValue::Optional(ParamType::String, self.symbol.map(|v| Box::new(TokenValue::String(v))))"));
+
+    assert_eq!(string(TokenValue::String("TON".to_owned())).unwrap(), "TON");
+    assert_eq!(optional(TokenValue::Optional(ParamType::Bool, None)).unwrap(), None);
+    assert!(optional(TokenValue::Bool(true)).is_err());
+}
+
+#[test]
+fn test_generate_fixed_sizes() {
+    let abi = r#"{
+        "ABI version": 1,
+        "functions": [{
+            "name": "setKeys",
+            "inputs": [
+                {"name": "keys", "type": "uint256[3]"},
+                {"name": "salt", "type": "fixedbytes8"}
+            ],
+            "outputs": []
+        }],
+        "events": [],
+        "data": []
+    }"#;
+    let code = generate(abi).unwrap();
+
+    assert!(code.contains("    pub keys: [Uint256; 3],
+"));
+    assert!(code.contains("    pub salt: [u8; 8],
+"));
+    assert!(code.contains("TokenValue::FixedBytes(self.salt.to_vec())"));
+    assert!(code.contains("salt: codegen::fixed(codegen::bytes("));
+    assert!(code.contains("keys: codegen::fixed(codegen::array("));
+
+    assert_eq!(fixed::<u8, 2>(vec![1, 2]).unwrap(), [1, 2]);
+    assert!(fixed::<u8, 2>(vec![1]).is_err());
+    assert!(fixed::<u8, 2>(vec![1, 2, 3]).is_err());
+}
+
+#[test]
+fn test_generated_fixture_is_up_to_date() {
+    // `tests/test_generated.rs` compiles this fixture and round-trips calls through it
+    let code = generate(include_str!("../../tests/generated/Transfer.abi.json")).unwrap();
+    assert_eq!(code, include_str!("../../tests/generated/transfer.rs"));
+}
@@ -12,8 +12,10 @@
 * limitations under the License.
 */
 
-use {Contract, Function, Event, Param, ParamType, DataItem};
+use {AbiLayout, Contract, Function, Event, Param, ParamType, DataItem, Token, TokenValue};
+use ton_types::{BuilderData, SliceData};
 use std::collections::HashMap;
+use super::ABI_VERSION;
 
 const TEST_ABI: &str = r#"
 {
@@ -85,6 +87,7 @@ fn test_abi_parse() {
                 Param { name: "b".to_owned(), kind: ParamType::Uint(8) },
             ],
             set_time: true,
+            layout: AbiLayout::V1Greedy,
             id: Some(Function::calc_function_id("input_and_output(time,uint64,uint8[],bytes)(int16,uint8)v1"))
         });
 
@@ -97,6 +100,7 @@ fn test_abi_parse() {
             ],
             outputs: vec![],
             set_time: true,
+            layout: AbiLayout::V1Greedy,
             id: Some(Function::calc_function_id("no_output(time,uint15)()v1"))
         });
 
@@ -109,6 +113,7 @@ fn test_abi_parse() {
                 Param { name: "a".to_owned(), kind: ParamType::Uint(8) },
             ],
             set_time: true,
+            layout: AbiLayout::V1Greedy,
             id: Some(Function::calc_function_id("no_input(time)(uint8)v1"))
         });
 
@@ -119,6 +124,7 @@ fn test_abi_parse() {
             inputs: vec![],
             outputs: vec![],
             set_time: true,
+            layout: AbiLayout::V1Greedy,
             id: Some(Function::calc_function_id("constructor(time)()v1"))
         });
 
@@ -129,6 +135,7 @@ fn test_abi_parse() {
             inputs: vec![],
             outputs: vec![],
             set_time: true,
+            layout: AbiLayout::V1Greedy,
             id: Some(0x01234567)
         });
 
@@ -141,6 +148,7 @@ fn test_abi_parse() {
             inputs: vec![
                 Param { name: "a".to_owned(), kind: ParamType::Uint(64) },
             ],
+            layout: AbiLayout::V1Greedy,
             id: Some(Function::calc_function_id("input(uint64)v1"))
         });
 
@@ -149,6 +157,7 @@ fn test_abi_parse() {
         Event {
             name: "no_input".to_owned(),
             inputs: vec![],
+            layout: AbiLayout::V1Greedy,
             id: Some(Function::calc_function_id("no_input()v1"))
         });
 
@@ -157,6 +166,7 @@ fn test_abi_parse() {
         Event {
             name: "has_id".to_owned(),
             inputs: vec![],
+            layout: AbiLayout::V1Greedy,
             id: Some(0x89abcdef)
         });
 
@@ -172,7 +182,7 @@ fn test_abi_parse() {
             key: 100
         });
 
-    let expected_contract = Contract { functions, events, data };
+    let expected_contract = Contract { functions, events, data, layout: AbiLayout::V1Greedy };
 
     assert_eq!(parsed_contract, expected_contract);
 }
@@ -217,3 +227,59 @@ const TEST_ABI_WRONG_VERSION: &str = r#"
 fn test_abi_wrong_version() {
     assert!(Contract::load(TEST_ABI_WRONG_VERSION.as_bytes()).is_err());
 }
+
+const TEST_ABI_STORE: &str = r#"
+{
+    "ABI version": 1,
+    "functions": [{
+            "name": "store",
+            "inputs": [
+                {"name": "a", "type": "cell"},
+                {"name": "b", "type": "cell"},
+                {"name": "c", "type": "cell"},
+                {"name": "d", "type": "cell"}
+            ],
+            "outputs": []
+        }],
+    "events": [{
+            "name": "stored",
+            "inputs": []
+        }]
+}"#;
+
+#[test]
+fn test_abi_versions() {
+    assert_eq!(AbiLayout::from_version(1).unwrap(), AbiLayout::V1Greedy);
+    assert_eq!(AbiLayout::V1Greedy.version(), 1);
+    assert_eq!(ABI_VERSION, 1);
+
+    // version is accepted only after its encoding is checked against compiler output
+    assert!(AbiLayout::from_version(2).is_err());
+    let error = Contract::load(TEST_ABI_STORE.replace("\"ABI version\": 1", "\"ABI version\": 2").as_bytes())
+        .unwrap_err();
+    assert!(error.to_string().contains("ABI version `1`"));
+}
+
+#[test]
+fn test_abi_v1_layout() {
+    let contract = Contract::load(TEST_ABI_STORE.as_bytes()).unwrap();
+
+    let function = contract.function("store").unwrap();
+    assert_eq!(function.layout, AbiLayout::V1Greedy);
+    assert_eq!(function.get_function_signature(), "store(time,cell,cell,cell,cell)()v1");
+    assert_eq!(function.get_id(), Function::calc_function_id("store(time,cell,cell,cell,cell)()v1"));
+
+    let event = contract.events().get("stored").unwrap();
+    assert_eq!(event.layout, AbiLayout::V1Greedy);
+    assert_eq!(event.get_id(), Function::calc_function_id("stored()v1") & 0x7FFFFFFF);
+
+    let tokens: Vec<Token> = ["a", "b", "c", "d"].iter().enumerate()
+        .map(|(i, name)| Token::new(name, TokenValue::Cell(BuilderData::with_raw(vec![i as u8; 8], 64).unwrap().into())))
+        .collect();
+
+    // the last reference of the body is reserved for the chain continuation
+    let body = function.encode_input(&tokens, true, None).unwrap();
+    assert_eq!(body.references_used(), 4);
+    assert_eq!(SliceData::from(body.clone()).reference(3).unwrap().references_count(), 1);
+    assert_eq!(function.decode_input(body.into(), true).unwrap(), tokens);
+}
@@ -16,13 +16,14 @@ use ed25519_dalek::*;
 use num_bigint::{BigInt, BigUint};
 use sha2::{Digest, Sha256, Sha512};
 use chrono::prelude::*;
+use std::collections::HashMap;
 
 use ton_types::{BuilderData, IBitstring, SliceData};
 use ton_types::dictionary::{HashmapE, HashmapType};
 use ton_block::{AnycastInfo, BlockResult, Grams, MsgAddress, Serializable};
 use ton_vm::types::AccountId;
 
-use {Function, Event, Int, Param, ParamType, Token, TokenValue, Uint};
+use {AbiLayout, Function, Event, Int, Param, ParamType, Token, TokenValue, Uint};
 
 fn get_function_id(signature: &[u8]) -> u32 {
     // Sha256 hash of signature
@@ -92,6 +93,7 @@ fn test_parameters_set(
         inputs: input_params.clone(),
         outputs: input_params.clone(),
         set_time: false,
+        layout: AbiLayout::V1Greedy,
         id: None
     };
 
@@ -270,6 +272,34 @@ fn test_with_address() {
     );
 }
 
+#[test]
+fn test_string_and_optional() {
+    // builder with reserved signature reference and function ID
+    let mut builder = BuilderData::new();
+    builder.append_u32(0).unwrap();
+    builder.append_reference(BuilderData::new());
+
+    builder.append_reference(BuilderData::with_raw(b"TON Crystal".to_vec(), 11 * 8).unwrap());
+    builder.append_bit_one().unwrap();
+    builder.append_u8(5).unwrap();
+    builder.append_bit_zero().unwrap();
+
+    let values = vec![
+        TokenValue::String("TON Crystal".to_owned()),
+        TokenValue::Optional(ParamType::Uint(8), Some(Box::new(TokenValue::Uint(Uint::new(5, 8))))),
+        TokenValue::Optional(ParamType::Uint(8), None),
+    ];
+
+    test_parameters_set(
+        "test_string_and_optional",
+        b"test_string_and_optional(string,optional(uint8),optional(uint8))(string,optional(uint8),optional(uint8))v1",
+        b"test_string_and_optional(time,string,optional(uint8),optional(uint8))(string,optional(uint8),optional(uint8))v1",
+        &tokens_from_values(values),
+        None,
+        builder,
+    );
+}
+
 #[test]
 fn test_one_input_and_output_by_data() {
     // builder with reserved signature reference and function ID
@@ -512,6 +542,69 @@ fn test_dynamic_array_of_ints() {
     );
 }
 
+#[test]
+fn test_map_keys() {
+    // builder with reserved signature reference and function ID
+    let mut builder = BuilderData::new();
+    builder.append_u32(0).unwrap();
+    builder.append_reference(BuilderData::new());
+
+    let big_key = BigInt::from_bytes_be(num_bigint::Sign::Plus, &[0x7F; 32]);
+
+    // map(int256,uint32)
+    let mut map = HashmapE::with_bit_len(256);
+    map.set(
+        BuilderData::with_raw(vec![0xFF; 32], 256).unwrap().into(),
+        &17u32.write_to_new_cell().unwrap().into()).unwrap();
+    map.set(
+        BuilderData::with_raw(vec![0x7F; 32], 256).unwrap().into(),
+        &42u32.write_to_new_cell().unwrap().into()).unwrap();
+    map.write_to(&mut builder).unwrap();
+
+    let mut int_map = HashMap::new();
+    int_map.insert("-1".to_owned(), TokenValue::Uint(Uint::new(17, 32)));
+    int_map.insert(big_key.to_str_radix(10), TokenValue::Uint(Uint::new(42, 32)));
+
+    // map(address,bool)
+    let first = MsgAddress::with_standart(None, 0, AccountId::from([0x11; 32])).unwrap();
+    let second = MsgAddress::with_standart(None, -1, AccountId::from([0x22; 32])).unwrap();
+    let mut map = HashmapE::with_bit_len(267);
+    map.set(first.write_to_new_cell().unwrap().into(), &true.write_to_new_cell().unwrap().into()).unwrap();
+    map.set(second.write_to_new_cell().unwrap().into(), &false.write_to_new_cell().unwrap().into()).unwrap();
+    map.write_to(&mut builder).unwrap();
+
+    let mut address_map = HashMap::new();
+    address_map.insert(first.to_string(), TokenValue::Bool(true));
+    address_map.insert(second.to_string(), TokenValue::Bool(false));
+
+    // map(fixedbytes4,uint8) - doesn't fit into first cell because of reserved signature reference
+    let mut map = HashmapE::with_bit_len(32);
+    map.set(
+        BuilderData::with_raw(vec![1, 2, 3, 4], 32).unwrap().into(),
+        &5u8.write_to_new_cell().unwrap().into()).unwrap();
+    let mut new_builder = BuilderData::new();
+    map.write_to(&mut new_builder).unwrap();
+    builder.append_reference(new_builder);
+
+    let mut bytes_map = HashMap::new();
+    bytes_map.insert("01020304".to_owned(), TokenValue::Uint(Uint::new(5, 8)));
+
+    let values = vec![
+        TokenValue::Map(ParamType::Int(256), int_map),
+        TokenValue::Map(ParamType::Address, address_map),
+        TokenValue::Map(ParamType::FixedBytes(4), bytes_map),
+    ];
+
+    test_parameters_set(
+        "test_map_keys",
+        b"test_map_keys(map(int256,uint32),map(address,bool),map(fixedbytes4,uint8))(map(int256,uint32),map(address,bool),map(fixedbytes4,uint8))v1",
+        b"test_map_keys(time,map(int256,uint32),map(address,bool),map(fixedbytes4,uint8))(map(int256,uint32),map(address,bool),map(fixedbytes4,uint8))v1",
+        &tokens_from_values(values),
+        None,
+        builder,
+    );
+}
+
 struct TupleDwordBool(u32, bool);
 
 impl Serializable for TupleDwordBool {
@@ -678,6 +771,7 @@ fn test_add_signature() {
         inputs: params_from_tokens(&tokens),
         outputs: vec![],
         set_time: false,
+        layout: AbiLayout::V1Greedy,
         id: None
     };
 
@@ -691,6 +785,133 @@ fn test_add_signature() {
     assert_eq!(function.decode_input(msg.into(), false).unwrap(), tokens);
 }
 
+#[test]
+fn test_verify_signature() {
+    let tokens = tokens_from_values(vec![TokenValue::Uint(Uint::new(456, 32))]);
+
+    let function = Function {
+        name: "test_verify_signature".to_owned(),
+        inputs: params_from_tokens(&tokens),
+        outputs: vec![],
+        set_time: true,
+        layout: AbiLayout::V1Greedy,
+        id: None
+    };
+
+    let pair = Keypair::generate::<Sha512, _>(&mut rand::rngs::OsRng::new().unwrap());
+    let msg = function.encode_input(&tokens, false, Some(&pair)).unwrap();
+
+    let (valid, public_key) = function.verify_signature(msg.into()).unwrap();
+    assert!(valid);
+    assert_eq!(public_key, pair.public);
+
+    // signature made by other key
+    let (msg, data_to_sign) = function.create_unsigned_call(&tokens, false).unwrap();
+    let other = Keypair::generate::<Sha512, _>(&mut rand::rngs::OsRng::new().unwrap());
+    let signature = other.sign::<Sha512>(&data_to_sign).to_bytes().to_vec();
+    let msg = Function::add_sign_to_encoded_input(&signature, &pair.public.to_bytes(), msg.into()).unwrap();
+
+    let (valid, public_key) = function.verify_signature(msg.into()).unwrap();
+    assert!(!valid);
+    assert_eq!(public_key, pair.public);
+
+    // unsigned message
+    let msg = function.encode_input(&tokens, false, None).unwrap();
+    assert!(function.verify_signature(msg.into()).is_err());
+}
+
+#[test]
+fn test_decode_input_with_header() {
+    let tokens = tokens_from_values(vec![
+        TokenValue::Uint(Uint::new(456, 32)),
+        TokenValue::Cell(BuilderData::with_raw(vec![1, 2, 3], 24).unwrap().into()),
+    ]);
+
+    let function = Function {
+        name: "test_decode_input_with_header".to_owned(),
+        inputs: params_from_tokens(&tokens),
+        outputs: vec![],
+        set_time: true,
+        layout: AbiLayout::V1Greedy,
+        id: None
+    };
+
+    // signed external message
+    let pair = Keypair::generate::<Sha512, _>(&mut rand::rngs::OsRng::new().unwrap());
+    let before = Utc::now().timestamp_millis() as u64;
+    let msg: SliceData = function.encode_input(&tokens, false, Some(&pair)).unwrap().into();
+    let after = Utc::now().timestamp_millis() as u64;
+
+    let (decoded, header) = function.decode_input_with_header(msg.clone(), None).unwrap();
+    assert_eq!(decoded, tokens);
+    let header = header.unwrap();
+    assert_eq!(header.public_key, Some(pair.public));
+    let time = header.time.unwrap();
+    assert!(before <= time && time <= after);
+    assert!(function.decode_input_with_header(msg.clone(), Some(false)).unwrap().1.is_some());
+    assert!(function.decode_input_with_header(msg, Some(true)).is_err());
+
+    // public key is reported only with valid signature
+    let (unsigned, _) = function.create_unsigned_call(&tokens, false).unwrap();
+    let signature = pair.sign::<Sha512>(&[0; 32]).to_bytes();
+    let msg = Function::add_sign_to_encoded_input(&signature, &pair.public.to_bytes(), unsigned.into()).unwrap();
+    let error = function.decode_input_with_header(msg.into(), Some(false)).unwrap_err().to_string();
+    assert!(error.contains("Invalid signature"), "{}", error);
+
+    // unsigned external message
+    let msg = function.encode_input(&tokens, false, None).unwrap();
+    let (decoded, header) = function.decode_input_with_header(msg.into(), None).unwrap();
+    assert_eq!(decoded, tokens);
+    let header = header.unwrap();
+    assert!(header.public_key.is_none());
+    assert!(header.time.is_some());
+
+    // internal message
+    let msg: SliceData = function.encode_input(&tokens, true, None).unwrap().into();
+    let (decoded, header) = function.decode_input_with_header(msg.clone(), None).unwrap();
+    assert_eq!(decoded, tokens);
+    assert!(header.is_none());
+    assert!(function.decode_input_with_header(msg.clone(), Some(true)).unwrap().1.is_none());
+    assert!(function.decode_input_with_header(msg.clone(), Some(false)).is_err());
+
+    // body of other function
+    let other = Function { name: "other".to_owned(), ..function.clone() };
+    assert!(other.decode_input_with_header(msg.clone(), None).unwrap_err().to_string()
+        .contains("Wrong function ID"));
+
+    // body matching neither layout
+    let mut builder = BuilderData::from_slice(&msg);
+    builder.append_u8(0).unwrap();
+    let error = function.decode_input_with_header(builder.into(), None).unwrap_err().to_string();
+    assert!(error.contains("neither external"), "{}", error);
+}
+
+#[test]
+fn test_decode_input_with_header_ambiguous() {
+    // internal body `a: Some(empty cell), b: Some(4)` is also unsigned external body with
+    // timestamp in the bits of `b` and both optional values unset
+    let tokens = tokens_from_values(vec![
+        TokenValue::Optional(ParamType::Cell, Some(Box::new(TokenValue::Cell(BuilderData::new().into())))),
+        TokenValue::Optional(ParamType::Uint(64), Some(Box::new(TokenValue::Uint(Uint::new(4, 64))))),
+    ]);
+
+    let function = Function {
+        name: "test_decode_input_with_header_ambiguous".to_owned(),
+        inputs: params_from_tokens(&tokens),
+        outputs: vec![],
+        set_time: true,
+        layout: AbiLayout::V1Greedy,
+        id: None
+    };
+
+    let msg: SliceData = function.encode_input(&tokens, true, None).unwrap().into();
+    assert_eq!(function.decode_input_with_header(msg.clone(), Some(true)).unwrap().0, tokens);
+    assert!(function.decode_input_with_header(msg.clone(), Some(false)).unwrap().1.is_some());
+
+    let error = function.decode_input_with_header(msg, None).unwrap_err().to_string();
+    assert!(error.contains("both external and internal"), "{}", error);
+}
+
 #[test]
 fn test_decode_event() {
     let mut builder = BuilderData::new();
@@ -702,6 +923,7 @@ fn test_decode_event() {
     let event = Event {
         name: "event".to_owned(),
         inputs: params_from_tokens(&tokens),
+        layout: AbiLayout::V1Greedy,
         id: None
     };
 
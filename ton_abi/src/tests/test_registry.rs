@@ -0,0 +1,164 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use {AbiRegistry, Token, TokenValue, Uint};
+use registry::BodyType;
+use ton_types::{BuilderData, IBitstring, SliceData};
+
+const WALLET_ABI: &str = r#"{
+    "ABI version": 1,
+    "functions": [{
+        "name": "sendTransaction",
+        "id": "0x00000001",
+        "inputs": [{"name": "value", "type": "uint32"}],
+        "outputs": [{"name": "ok", "type": "bool"}]
+    }, {
+        "name": "getBalance",
+        "inputs": [],
+        "outputs": [{"name": "balance", "type": "uint64"}]
+    }],
+    "events": [{
+        "name": "Received",
+        "inputs": [{"name": "value", "type": "uint32"}]
+    }]
+}"#;
+
+const TOKEN_ABI: &str = r#"{
+    "ABI version": 1,
+    "functions": [{
+        "name": "mint",
+        "id": "0x00000001",
+        "inputs": [{"name": "amount", "type": "uint8"}],
+        "outputs": []
+    }, {
+        "name": "getBalance",
+        "inputs": [],
+        "outputs": [{"name": "balance", "type": "uint64"}]
+    }]
+}"#;
+
+fn registry() -> AbiRegistry {
+    let mut registry = AbiRegistry::new();
+    registry.add_json("token", TOKEN_ABI).unwrap();
+    registry.add_json("wallet", WALLET_ABI).unwrap();
+    registry.add_code_hash("wallet", &[1; 32]).unwrap();
+    registry
+}
+
+fn body(id: u32, tokens: &[Token]) -> SliceData {
+    let mut builder = BuilderData::new();
+    builder.append_u32(id).unwrap();
+    TokenValue::pack_values_into_chain(tokens, vec![builder], Default::default()).unwrap().into()
+}
+
+#[test]
+fn test_decode_colliding_ids() {
+    let registry = registry();
+    let wallet = registry.contract("wallet").unwrap().function("sendTransaction").unwrap();
+    let token = registry.contract("token").unwrap().function("mint").unwrap();
+    assert_eq!(wallet.get_input_id(), token.get_input_id());
+
+    // `mint` of the token ABI is tried first and fails on the longer body
+    let tokens = vec![Token::new("value", TokenValue::Uint(Uint::new(1000, 32)))];
+    let decoded = registry.decode(wallet.encode_input(&tokens, true, None).unwrap().into(), None, None).unwrap();
+    assert_eq!(decoded.abi_name, "wallet");
+    assert_eq!(decoded.body_type, BodyType::FunctionInput);
+    assert_eq!(decoded.name, "sendTransaction");
+    assert_eq!(decoded.tokens, tokens);
+    assert!(decoded.header.is_none());
+
+    let tokens = vec![Token::new("amount", TokenValue::Uint(Uint::new(10, 8)))];
+    let decoded = registry.decode(token.encode_input(&tokens, true, None).unwrap().into(), None, None).unwrap();
+    assert_eq!(decoded.abi_name, "token");
+    assert_eq!(decoded.name, "mint");
+    assert_eq!(decoded.tokens, tokens);
+
+    // external call header is detected
+    let tokens = vec![Token::new("value", TokenValue::Uint(Uint::new(1000, 32)))];
+    let decoded = registry.decode(wallet.encode_input(&tokens, false, None).unwrap().into(), None, None).unwrap();
+    assert_eq!(decoded.name, "sendTransaction");
+    assert!(decoded.header.unwrap().time.is_some());
+    let body = wallet.encode_input(&tokens, false, None).unwrap().into();
+    assert!(registry.decode(body, None, Some(true)).is_err());
+
+    // body matching neither candidate
+    let error = registry.decode(body_with_extra_bits(wallet.get_input_id()), None, None).unwrap_err().to_string();
+    assert!(error.contains("token.mint") && error.contains("wallet.sendTransaction"), "{}", error);
+}
+
+fn body_with_extra_bits(id: u32) -> SliceData {
+    let mut builder = BuilderData::new();
+    builder.append_u32(id).unwrap();
+    builder.append_u64(0).unwrap();
+    builder.into()
+}
+
+#[test]
+fn test_decode_outputs_and_events() {
+    let registry = registry();
+    let wallet = registry.contract("wallet").unwrap();
+
+    let tokens = vec![Token::new("ok", TokenValue::Bool(true))];
+    let output_id = wallet.function("sendTransaction").unwrap().get_output_id();
+    let decoded = registry.decode(body(output_id, &tokens), None, None).unwrap();
+    assert_eq!(decoded.abi_name, "wallet");
+    assert_eq!(decoded.body_type, BodyType::FunctionOutput);
+    assert_eq!(decoded.name, "sendTransaction");
+    assert_eq!(decoded.tokens, tokens);
+
+    let tokens = vec![Token::new("value", TokenValue::Uint(Uint::new(5, 32)))];
+    let event_id = wallet.events()["Received"].get_id();
+    let decoded = registry.decode(body(event_id, &tokens), None, None).unwrap();
+    assert_eq!(decoded.abi_name, "wallet");
+    assert_eq!(decoded.body_type, BodyType::Event);
+    assert_eq!(decoded.name, "Received");
+    assert_eq!(decoded.params[0].name, "value");
+    assert_eq!(decoded.tokens, tokens);
+}
+
+#[test]
+fn test_decode_by_code_hash() {
+    let registry = registry();
+
+    // both ABIs have the same `getBalance` function
+    let tokens = vec![Token::new("balance", TokenValue::Uint(Uint::new(7, 64)))];
+    let output_id = registry.contract("token").unwrap().function("getBalance").unwrap().get_output_id();
+
+    let decoded = registry.decode(body(output_id, &tokens), None, None).unwrap();
+    assert_eq!(decoded.abi_name, "token");
+    let decoded = registry.decode(body(output_id, &tokens), Some(&[2; 32]), None).unwrap();
+    assert_eq!(decoded.abi_name, "token");
+    let decoded = registry.decode(body(output_id, &tokens), Some(&[1; 32]), None).unwrap();
+    assert_eq!(decoded.abi_name, "wallet");
+
+    // ABI bound to code hash doesn't have `mint` function
+    let tokens = vec![Token::new("amount", TokenValue::Uint(Uint::new(10, 8)))];
+    let mint = registry.contract("token").unwrap().function("mint").unwrap();
+    let body: SliceData = mint.encode_input(&tokens, true, None).unwrap().into();
+    assert!(registry.decode(body.clone(), Some(&[1; 32]), None).is_err());
+    assert_eq!(registry.decode(body, Some(&[2; 32]), None).unwrap().name, "mint");
+}
+
+#[test]
+fn test_registry_errors() {
+    let mut registry = registry();
+
+    assert_eq!(registry.names(), vec!["token", "wallet"]);
+    assert!(registry.add_json("wallet", WALLET_ABI).is_err());
+    assert!(registry.add_code_hash("unknown", &[3; 32]).is_err());
+    assert!(registry.add_json("invalid", "{}").is_err());
+
+    let error = registry.decode(body(0x7fffffff, &[]), None, None).unwrap_err().to_string();
+    assert!(error.contains("Invalid function id"), "{}", error);
+}
@@ -0,0 +1,121 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use token::Tokenizer;
+
+const ABI: &str = r#"{
+    "ABI version": 1,
+    "functions": [{
+        "name": "transfer",
+        "inputs": [
+            {"name": "dest", "type": "address"},
+            {"name": "value", "type": "uint128"},
+            {"name": "flags", "type": "int8[2]"},
+            {"name": "payload", "type": "cell"},
+            {"name": "memo", "type": "optional(bytes)"}
+        ],
+        "outputs": [{"name": "ok", "type": "bool"}]
+    }, {
+        "name": "getBalances",
+        "inputs": [],
+        "outputs": [
+            {"name": "balances", "type": "map(address,tuple)", "components": [
+                {"name": "amount", "type": "gram"},
+                {"name": "key", "type": "fixedbytes4"}
+            ]}
+        ]
+    }],
+    "events": [{
+        "name": "Received",
+        "inputs": [{"name": "from", "type": "map(int16,string)"}]
+    }],
+    "data": [
+        {"key": 101, "name": "limit", "type": "uint64"},
+        {"key": 100, "name": "owner", "type": "uint256"}
+    ]
+}"#;
+
+#[test]
+fn test_contract_schema() {
+    let contract = Contract::load(ABI.as_bytes()).unwrap();
+    let schema = contract.json_schema();
+
+    assert_eq!(schema["$schema"], SCHEMA_VERSION);
+    assert_eq!(schema["definitions"]["cellTree"]["properties"]["refs"]["items"]["$ref"], CELL_TREE_REF);
+
+    let input = &schema["functions"]["transfer"]["input"];
+    assert_eq!(input["type"], "object");
+    assert_eq!(input["required"], json!(["dest", "value", "flags", "payload", "memo"]));
+    assert_eq!(input["additionalProperties"], false);
+
+    let value = &input["properties"]["value"];
+    assert_eq!(value["description"], "uint128");
+    assert_eq!(value["anyOf"][0]["maximum"], u64::max_value());
+    assert_eq!(value["anyOf"][1]["pattern"], "^(0[xX][0-9a-fA-F]+|[0-9]+)$");
+
+    let flags = &input["properties"]["flags"];
+    assert_eq!(flags["minItems"], 2);
+    assert_eq!(flags["maxItems"], 2);
+    assert_eq!(flags["items"]["anyOf"][0]["minimum"], -128);
+    assert_eq!(flags["items"]["anyOf"][0]["maximum"], 127);
+
+    assert_eq!(input["properties"]["payload"]["anyOf"][1]["$ref"], CELL_TREE_REF);
+    assert_eq!(input["properties"]["memo"]["anyOf"][0]["type"], "null");
+    assert_eq!(input["properties"]["memo"]["anyOf"][1]["anyOf"][3]["required"], json!(["utf8"]));
+
+    let output = &schema["functions"]["transfer"]["output"];
+    assert_eq!(output["properties"]["ok"]["anyOf"][1]["enum"], json!(["true", "false"]));
+
+    let balances = &schema["functions"]["getBalances"]["output"]["properties"]["balances"];
+    assert_eq!(balances["type"], "object");
+    assert_eq!(balances["propertyNames"]["anyOf"][0]["enum"], json!(["", "addr_none"]));
+    let item = &balances["additionalProperties"];
+    assert_eq!(item["required"], json!(["amount", "key"]));
+    assert_eq!(item["properties"]["amount"]["description"], "gram");
+    assert_eq!(item["properties"]["key"]["anyOf"][0]["minLength"], 8);
+    assert_eq!(schema["functions"]["getBalances"]["input"]["required"], json!([]));
+
+    let event = &schema["events"]["Received"];
+    assert_eq!(event["properties"]["from"]["propertyNames"]["pattern"], "^-?(0[xX][0-9a-fA-F]+|[0-9]+)$");
+    assert_eq!(event["properties"]["from"]["additionalProperties"]["type"], "string");
+
+    // data items are ordered by keys
+    assert_eq!(schema["data"]["required"], json!(["owner", "limit"]));
+}
+
+#[test]
+fn test_schema_follows_tokenizer() {
+    // map keys are strings even for integer keys
+    let map_type = ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::Bool));
+    assert_eq!(type_schema(&map_type)["propertyNames"]["type"], "string");
+    assert!(Tokenizer::tokenize_parameter(&map_type, &json!({"0x10": "true", "7": false})).is_ok());
+
+    // JSON numbers are limited by 64 bits while strings are not
+    assert_eq!(type_schema(&ParamType::Int(256))["anyOf"][0]["maximum"], u64::max_value());
+    assert_eq!(type_schema(&ParamType::Int(64))["anyOf"][0]["maximum"], i64::max_value());
+    assert_eq!(type_schema(&ParamType::Uint(8))["anyOf"][0]["maximum"], 255);
+    assert!(Tokenizer::tokenize_parameter(&ParamType::Int(256), &json!("-0x10")).is_ok());
+
+    let tuple = ParamType::Tuple(vec![Param::new("a", ParamType::Address)]);
+    let schema = params_schema(&[Param::new("t", tuple.clone())]);
+    assert_eq!(schema["$schema"], SCHEMA_VERSION);
+    assert!(schema["definitions"]["cellTree"].is_object());
+    assert_eq!(schema["properties"]["t"]["additionalProperties"], false);
+    assert!(Tokenizer::tokenize_parameter(&tuple, &json!({"a": "addr_none", "b": 1})).is_err());
+
+    // types which can not be map keys reject all keys
+    let map_type = ParamType::Map(Box::new(ParamType::Cell), Box::new(ParamType::Bool));
+    assert_eq!(type_schema(&map_type)["propertyNames"], false);
+}
@@ -0,0 +1,108 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use {Contract};
+
+#[test]
+fn test_function_from_signature() {
+    let function = Function::from_signature(
+        "transfer(address dest, uint128 value) returns (bool)").unwrap();
+
+    assert_eq!(function.name, "transfer");
+    assert_eq!(function.inputs, vec![
+        Param::new("dest", ParamType::Address),
+        Param::new("value", ParamType::Uint(128)),
+    ]);
+    assert_eq!(function.outputs, vec![Param::new("value0", ParamType::Bool)]);
+    assert_eq!(function.get_function_signature(), "transfer(time,address,uint128)(bool)v1");
+
+    // the same function described in JSON has the same id
+    let abi = r#"{
+        "ABI version": 1,
+        "functions": [{
+            "name": "transfer",
+            "inputs": [{"name":"dest","type":"address"},{"name":"value","type":"uint128"}],
+            "outputs": [{"name":"ok","type":"bool"}]
+        }]
+    }"#;
+    let contract = Contract::load(abi.as_bytes()).unwrap();
+    assert_eq!(contract.function("transfer").unwrap().get_id(), function.get_id());
+}
+
+#[test]
+fn test_complex_types_from_signature() {
+    let function = Function::from_signature(
+        "function submit( (uint8 kind, bytes data)[] items, map(uint32,(bool ok, int8 code)) results,
+            fixedbytes32[2] hashes )").unwrap();
+
+    assert_eq!(function.outputs, vec![]);
+    assert_eq!(function.inputs[0].kind, ParamType::Array(Box::new(ParamType::Tuple(vec![
+        Param::new("kind", ParamType::Uint(8)),
+        Param::new("data", ParamType::Bytes),
+    ]))));
+    assert_eq!(function.inputs[1].kind, ParamType::Map(
+        Box::new(ParamType::Uint(32)),
+        Box::new(ParamType::Tuple(vec![
+            Param::new("ok", ParamType::Bool),
+            Param::new("code", ParamType::Int(8)),
+        ]))));
+    assert_eq!(function.inputs[2].kind,
+        ParamType::FixedArray(Box::new(ParamType::FixedBytes(32)), 2));
+    assert_eq!(function.get_function_signature(),
+        "submit(time,(uint8,bytes)[],map(uint32,(bool,int8)),fixedbytes32[2])()v1");
+}
+
+#[test]
+fn test_string_and_optional_from_signature() {
+    let function = Function::from_signature(
+        "setInfo(string name, optional(string) symbol, optional((uint8 decimals, bool mintable)) extra)").unwrap();
+
+    assert_eq!(function.inputs[0].kind, ParamType::String);
+    assert_eq!(function.inputs[1].kind, ParamType::Optional(Box::new(ParamType::String)));
+    assert_eq!(function.inputs[2].kind, ParamType::Optional(Box::new(ParamType::Tuple(vec![
+        Param::new("decimals", ParamType::Uint(8)),
+        Param::new("mintable", ParamType::Bool),
+    ]))));
+    assert_eq!(function.get_function_signature(),
+        "setInfo(time,string,optional(string),optional((uint8,bool)))()v1");
+
+    assert!(Function::from_signature("f(optional uint8 a)").is_err());
+}
+
+#[test]
+fn test_event_from_signature() {
+    let event = Event::from_signature("event Transfer(address to, gram value)").unwrap();
+    assert_eq!(event.name, "Transfer");
+    assert_eq!(event.get_function_signature(), "Transfer(address,gram)v1");
+
+    assert_eq!(Event::from_signature("Done()").unwrap().inputs, vec![]);
+}
+
+#[test]
+fn test_invalid_signatures() {
+    for signature in &[
+        "transfer(address dest",
+        "transfer(uint0 value)",
+        "transfer(int300 value)",
+        "transfer(map(bool,uint8) values)",
+        "transfer(tuple value)",
+        "transfer(float value)",
+        "transfer() returns",
+        "transfer() extra",
+        "(uint8 value)",
+    ] {
+        assert!(Function::from_signature(signature).is_err(), "{}", signature);
+    }
+}
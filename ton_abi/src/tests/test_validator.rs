@@ -0,0 +1,107 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+
+#[test]
+fn test_valid_abi() {
+    let abi = r#"{
+        "ABI version": 1,
+        "functions": [{
+            "name": "transfer",
+            "inputs": [
+                {"name":"dest","type":"address"},
+                {"name":"items","type":"tuple[]","components":[{"name":"a","type":"uint8"}]}
+            ],
+            "outputs": []
+        }],
+        "events": [],
+        "data": [{"key":100,"name":"owner","type":"uint256"}]
+    }"#;
+
+    assert_eq!(validate(abi), vec![]);
+}
+
+#[test]
+fn test_invalid_abi() {
+    let abi = r#"{
+    "ABI version": 1,
+    "functions": [
+        {
+            "name": "transfer",
+            "inputs": [
+                {"name":"a","type":"uint0"},
+                {"name":"b","type":"int300"},
+                {"name":"c","type":"float"},
+                {"name":"d","type":"map(bool,uint8)"},
+                {"name":"e","type":"tuple"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "get",
+            "inputs": [],
+            "outputs": []
+        },
+        {
+            "name": "get",
+            "inputs": [],
+            "outputs": []
+        },
+        {
+            "name": "other",
+            "id": "0x00000001",
+            "outputs": []
+        },
+        {
+            "name": "another",
+            "id": "0x80000001",
+            "outputs": []
+        }
+    ],
+    "data": [
+        {"key":1,"name":"a","type":"uint8"},
+        {"key":1,"name":"b","type":"uint8"}
+    ]
+}"#;
+
+    let errors = validate(abi);
+    let find = |path: &str| errors.iter()
+        .find(|error| error.path == path)
+        .unwrap_or_else(|| panic!("no error at {}: {:?}", path, errors));
+
+    let error = find("functions[0].inputs[0].type");
+    assert_eq!(error.line, Some(7));
+    assert!(error.message.contains("uint0"));
+    assert!(find("functions[0].inputs[1].type").message.contains("int300"));
+    assert!(find("functions[0].inputs[2].type").message.contains("float"));
+    assert!(find("functions[0].inputs[3].type").message.contains("map"));
+    // missing field is reported at the line of the parent object
+    assert_eq!(find("functions[0].inputs[4].components").line, Some(11));
+
+    assert_eq!(find("functions[2].name").line, Some(21));
+    assert!(find("functions[2]").message.contains("duplicates id of functions[1]"));
+    assert!(find("functions[4]").message.contains("duplicates id of functions[3]"));
+    assert_eq!(find("data[1].key").line, Some(38));
+
+    let errors = validate("{\n\"ABI version\": 1,\n\"functions\": [}");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, Some(3));
+
+    let errors = validate(r#"{"ABI version": 3, "functions": []}"#);
+    assert_eq!(errors[0].path, "ABI version");
+
+    let errors = validate(r#"{"ABI version": 2, "functions": []}"#);
+    assert_eq!(errors[0].path, "ABI version");
+}
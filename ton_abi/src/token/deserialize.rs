@@ -14,7 +14,6 @@
 
 use int::{Int, Uint};
 use {Param, ParamType};
-use serde_json;
 use super::*;
 use crate::error::*;
 
@@ -25,7 +24,8 @@ use ton_block::types::Grams;
 
 impl TokenValue {
     /// Deserializes value from `SliceData` to `TokenValue`
-    pub fn read_from(param_type: &ParamType, mut cursor: SliceData) -> AbiResult<(Self, SliceData)> {
+    pub fn read_from(param_type: &ParamType, mut cursor: SliceData, layout: AbiLayout)
+    -> AbiResult<(Self, SliceData)> {
         match param_type {
             ParamType::Unknown => bail!(AbiErrorKind::DeserializationError("Unknown ParamType", cursor)),
             ParamType::Uint(size) => Self::read_uint(*size, cursor),
@@ -34,26 +34,46 @@ impl TokenValue {
                 cursor = find_next_bits(cursor, 1)?;
                 Ok((TokenValue::Bool(cursor.get_next_bit()?), cursor))
             }
-            ParamType::Tuple(tuple_params) => Self::read_tuple(tuple_params, cursor),
-            ParamType::Array(param_type) => Self::read_array(&param_type, cursor),
+            ParamType::Tuple(tuple_params) => Self::read_tuple(tuple_params, cursor, layout),
+            ParamType::Array(param_type) => Self::read_array(&param_type, cursor, layout),
             ParamType::FixedArray(param_type, size) => {
-                Self::read_fixed_array(&param_type, *size, cursor)
+                Self::read_fixed_array(&param_type, *size, cursor, layout)
             }
-            ParamType::Cell => Self::read_cell(cursor)
+            ParamType::Cell => Self::read_cell(cursor, layout)
                 .map(|(cell, cursor)| (TokenValue::Cell(cell), cursor)),
-            ParamType::Map(key_type, value_type) => Self::read_hashmap(key_type, value_type, cursor),
+            ParamType::Map(key_type, value_type) => Self::read_hashmap(key_type, value_type, cursor, layout),
             ParamType::Address => {
                 cursor = find_next_bits(cursor, 1)?;
                 let address = <MsgAddress as ton_block::Deserializable>::construct_from(&mut cursor)?;
                 Ok((TokenValue::Address(address), cursor))
             }
-            ParamType::Bytes => Self::read_bytes(None, cursor),
-            ParamType::FixedBytes(size) => Self::read_bytes(Some(*size), cursor),
+            ParamType::Bytes => Self::read_bytes(None, cursor, layout),
+            ParamType::FixedBytes(size) => Self::read_bytes(Some(*size), cursor, layout),
             ParamType::Gram => {
                 cursor = find_next_bits(cursor, 1)?;
                 let gram = <Grams as ton_block::Deserializable>::construct_from(&mut cursor)?;
                 Ok((TokenValue::Gram(gram), cursor))
             }
+            ParamType::String => Self::read_string(cursor, layout),
+            ParamType::Optional(inner_type) => Self::read_optional(inner_type, cursor, layout),
+        }
+    }
+
+    fn read_string(cursor: SliceData, layout: AbiLayout) -> AbiResult<(Self, SliceData)> {
+        let (data, cursor) = Self::read_bytes_chain(cursor, layout)?;
+        let string = String::from_utf8(data)
+            .map_err(|err| AbiErrorKind::InvalidData(format!("Invalid UTF-8 string: {}", err)))?;
+        Ok((TokenValue::String(string), cursor))
+    }
+
+    fn read_optional(inner_type: &ParamType, mut cursor: SliceData, layout: AbiLayout)
+    -> AbiResult<(Self, SliceData)> {
+        cursor = find_next_bits(cursor, 1)?;
+        if cursor.get_next_bit()? {
+            let (value, cursor) = Self::read_from(inner_type, cursor, layout)?;
+            Ok((TokenValue::Optional(inner_type.clone(), Some(Box::new(value))), cursor))
+        } else {
+            Ok((TokenValue::Optional(inner_type.clone(), None), cursor))
         }
     }
 
@@ -69,11 +89,12 @@ impl TokenValue {
         Ok((TokenValue::Int(Int { number, size }), cursor))
     }
 
-    fn read_tuple(tuple_params: &[Param], cursor: SliceData) -> AbiResult<(Self, SliceData)> {
+    fn read_tuple(tuple_params: &[Param], cursor: SliceData, layout: AbiLayout)
+    -> AbiResult<(Self, SliceData)> {
         let mut tokens = Vec::new();
         let mut cursor = cursor;
         for param in tuple_params {
-            let (token_value, new_cursor) = TokenValue::read_from(&param.kind, cursor)?;
+            let (token_value, new_cursor) = TokenValue::read_from(&param.kind, cursor, layout)?;
             tokens.push(Token {
                 name: param.name.clone(),
                 value: token_value,
@@ -83,7 +104,7 @@ impl TokenValue {
         Ok((TokenValue::Tuple(tokens), cursor))
     }
 
-    fn read_array_from_map(param_type: &ParamType, mut cursor: SliceData, size: usize)
+    fn read_array_from_map(param_type: &ParamType, mut cursor: SliceData, size: usize, layout: AbiLayout)
     -> AbiResult<(Vec<Self>, SliceData)> {
         let original = cursor.clone();
         cursor = find_next_bits(cursor, 1)?;
@@ -93,13 +114,7 @@ impl TokenValue {
             let mut index = BuilderData::new();
             index.append_u32(i as u32)?;
             match map.get(index.into()) {
-                Ok(Some(item_slice)) => {
-                    let (token, item_slice) = Self::read_from(param_type, item_slice)?;
-                    if item_slice.remaining_references() != 0 || item_slice.remaining_bits() != 0 {
-                        bail!(AbiErrorKind::IncompleteDeserializationError(original))
-                    }
-                    result.push(token);
-                }
+                Ok(Some(item_slice)) => result.push(Self::read_array_item(param_type, item_slice, layout)?),
                 _ => bail!(AbiErrorKind::DeserializationError("", original))
             }
         }
@@ -107,23 +122,43 @@ impl TokenValue {
         Ok((result, cursor))
     }
 
-    fn read_array(param_type: &ParamType, mut cursor: SliceData) -> AbiResult<(Self, SliceData)> {
+    /// Deserializes array element from dictionary value checking that it is decoded completely
+    pub(crate) fn read_array_item(param_type: &ParamType, item_slice: SliceData, layout: AbiLayout)
+    -> AbiResult<Self> {
+        let original = item_slice.clone();
+        let (token, item_slice) = Self::read_from(param_type, item_slice, layout)?;
+        if item_slice.remaining_references() != 0 || item_slice.remaining_bits() != 0 {
+            bail!(AbiErrorKind::IncompleteDeserializationError(original))
+        }
+        Ok(token)
+    }
+
+    fn read_array(param_type: &ParamType, mut cursor: SliceData, layout: AbiLayout) -> AbiResult<(Self, SliceData)> {
         cursor = find_next_bits(cursor, 32)?;
         let size = cursor.get_next_u32()?;
-        let (result, cursor) = Self::read_array_from_map(param_type, cursor, size as usize)?;
+        let (result, cursor) = Self::read_array_from_map(param_type, cursor, size as usize, layout)?;
 
         Ok((TokenValue::Array(result), cursor))
     }
 
-    fn read_fixed_array(param_type: &ParamType, size: usize, cursor: SliceData) -> AbiResult<(Self, SliceData)> {
-        let (result, cursor) = Self::read_array_from_map(param_type, cursor, size)?;
+    fn read_fixed_array(param_type: &ParamType, size: usize, cursor: SliceData, layout: AbiLayout)
+    -> AbiResult<(Self, SliceData)> {
+        let (result, cursor) = Self::read_array_from_map(param_type, cursor, size, layout)?;
 
         Ok((TokenValue::FixedArray(result), cursor))
     }
 
-    fn read_cell(mut cursor: SliceData) -> AbiResult<(Cell, SliceData)> {
+    // Only one remaining reference is either the cell value or the chain continuation
+    fn is_chain_reference(cursor: &SliceData, layout: AbiLayout) -> bool {
+        match layout {
+            // last reference of the full cell is always the chain continuation
+            AbiLayout::V1Greedy => cursor.cell().references_count() == BuilderData::references_capacity(),
+        }
+    }
+
+    fn read_cell(mut cursor: SliceData, layout: AbiLayout) -> AbiResult<(Cell, SliceData)> {
         let cell = match cursor.remaining_references() {
-            1 if cursor.cell().references_count() == BuilderData::references_capacity() => {
+            1 if Self::is_chain_reference(&cursor, layout) => {
                 cursor = SliceData::from(cursor.reference(0)?);
                 cursor.checked_drain_reference()?
             }
@@ -132,24 +167,51 @@ impl TokenValue {
         Ok((cell.clone(), cursor))
     }
 
-    fn read_hashmap(key_type: &ParamType, value_type: &ParamType, mut cursor: SliceData)
+    /// Deserializes map key from dictionary key bits
+    pub fn read_map_key(key_type: &ParamType, mut key: SliceData) -> AbiResult<Self> {
+        match key_type {
+            ParamType::FixedBytes(size) if key_type.bit_len() != 0 => {
+                Ok(TokenValue::FixedBytes(key.get_next_bits(size * 8)?))
+            }
+            _ if key_type.bit_len() != 0 => Ok(Self::read_from(key_type, key, AbiLayout::default())?.0),
+            _ => bail!(AbiErrorKind::InvalidData(format!("Type {} can not be a map key", key_type)))
+        }
+    }
+
+    fn read_hashmap(key_type: &ParamType, value_type: &ParamType, mut cursor: SliceData, layout: AbiLayout)
     -> AbiResult<(Self, SliceData)> {
         cursor = find_next_bits(cursor, 1)?;
         let mut new_map = HashMap::new();
-        let hashmap = HashmapE::with_data(32, cursor.get_dictionary()?);
+        let hashmap = HashmapE::with_data(key_type.bit_len(), cursor.get_dictionary()?);
         hashmap.iterate(&mut |key, value| -> AbiResult<bool> {
-            let key = Self::read_from(key_type, key)?.0;
-            let key = serde_json::to_string(&key)?;
-            let value = Self::read_from(value_type, value)?.0;
-            new_map.insert(key, value);
+            let key = Token::detokenize_map_key(&Self::read_map_key(key_type, key)?)?;
+            new_map.insert(key, Self::read_map_value(value_type, value, layout)?);
             Ok(true)
         })?;
-        Ok((TokenValue::Map(value_type.clone(), new_map), cursor))
+        Ok((TokenValue::Map(key_type.clone(), new_map), cursor))
     }
 
-    fn read_bytes(size: Option<usize>, cursor: SliceData) -> AbiResult<(Self, SliceData)> {
+    /// Deserializes map value from dictionary value. Unlike array elements, data remaining
+    /// after the value is ignored
+    pub(crate) fn read_map_value(value_type: &ParamType, value: SliceData, layout: AbiLayout)
+    -> AbiResult<Self> {
+        Ok(Self::read_from(value_type, value, layout)?.0)
+    }
+
+    fn read_bytes(size: Option<usize>, cursor: SliceData, layout: AbiLayout)
+    -> AbiResult<(Self, SliceData)> {
         let original = cursor.clone();
-        let (mut cell, cursor) = Self::read_cell(cursor)?;
+        let (data, cursor) = Self::read_bytes_chain(cursor, layout)?;
+
+        match size {
+            Some(size) if size == data.len() => Ok((TokenValue::FixedBytes(data), cursor)),
+            Some(_) => bail!(AbiErrorKind::DeserializationError("Size of fixed bytes is not correspond to expected size", original)),
+            None => Ok((TokenValue::Bytes(data), cursor))
+        }
+    }
+
+    fn read_bytes_chain(cursor: SliceData, layout: AbiLayout) -> AbiResult<(Vec<u8>, SliceData)> {
+        let (mut cell, cursor) = Self::read_cell(cursor, layout)?;
 
         let mut data = vec![];
         loop {
@@ -160,11 +222,7 @@ impl TokenValue {
                 Err(_) => break
             };
         }
-        match size {
-            Some(size) if size == data.len() => Ok((TokenValue::FixedBytes(data), cursor)),
-            Some(_) => bail!(AbiErrorKind::DeserializationError("Size of fixed bytes is not correspond to expected size", original)),
-            None => Ok((TokenValue::Bytes(data), cursor))
-        }
+        Ok((data, cursor))
     }
 }
 
@@ -173,7 +231,7 @@ fn get_next_bits_from_chain(mut cursor: SliceData, bits: usize) -> AbiResult<(Ve
     Ok((cursor.get_next_bits(bits)?, cursor))
 }
 
-fn find_next_bits(mut cursor: SliceData, bits: usize) -> AbiResult<SliceData> {
+pub(super) fn find_next_bits(mut cursor: SliceData, bits: usize) -> AbiResult<SliceData> {
     debug_assert!(bits != 0);
     let original = cursor.clone();
     if cursor.remaining_bits() == 0 {
@@ -12,34 +12,60 @@
 * limitations under the License.
 */
 
-use serde::ser::{Serialize, Serializer, SerializeMap};
+use serde::ser::{Serialize, Serializer, SerializeMap, SerializeSeq};
 use std::collections::HashMap;
-use {Param, ParamType, Token, TokenValue};
-use num_bigint::{BigInt, BigUint};
+use {BytesFormat, CellFormat, IntFormat, Param, ParamType, Token, TokenValue, ValueFormat};
+use format::{CellTree, encode_address};
+use num_bigint::{BigInt, BigUint, Sign};
 use ton_types::cells_serialization::serialize_tree_of_cells;
 use ton_types::Cell;
 use crate::error::*;
 
+/// Max integer written as JSON number by `IntFormat::Number`, larger integers lose precision
+/// in double precision numbers
+pub const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
 pub struct Detokenizer;
 
 impl Detokenizer {
     pub fn detokenize(params: &[Param], tokens: &[Token]) -> AbiResult<String> {
+        Self::detokenize_with_format(params, tokens, ValueFormat::default())
+    }
+
+    /// Serializes tokens into JSON object, values are written in given format
+    pub fn detokenize_with_format(params: &[Param], tokens: &[Token], format: ValueFormat) -> AbiResult<String> {
         //println!("Params len = {}, tokens len = {}", params.len(), tokens.len());
 
         if params.len() != tokens.len() {
             bail!(AbiErrorKind::WrongParametersCount(params.len(), tokens.len()));
         }
 
-        if !Token::types_check(tokens, params) {
-             bail!(AbiErrorKind::WrongParameterType);
-        }
+        Token::check_types(tokens, params)?;
+
+        Ok(serde_json::to_string(&FunctionParams{params: tokens, format})?)
+    }
 
-        Ok(serde_json::to_string(&FunctionParams{params: tokens})?)
+    /// Serializes tokens into JSON object without checking them against ABI params
+    pub fn detokenize_tokens(tokens: &[Token]) -> AbiResult<String> {
+        Self::detokenize_tokens_with_format(tokens, ValueFormat::default())
+    }
+
+    /// Serializes tokens into JSON object without checking them against ABI params,
+    /// values are written in given format
+    pub fn detokenize_tokens_with_format(tokens: &[Token], format: ValueFormat) -> AbiResult<String> {
+        Ok(serde_json::to_string(&FunctionParams{params: tokens, format})?)
     }
 }
 
 pub struct FunctionParams<'a> {
     params: &'a [Token],
+    format: ValueFormat,
+}
+
+/// Token value serialized in given format
+pub struct FormattedValue<'a> {
+    pub value: &'a TokenValue,
+    pub format: ValueFormat,
 }
 
 impl<'a> Serialize for FunctionParams<'a> {
@@ -50,7 +76,7 @@ impl<'a> Serialize for FunctionParams<'a> {
         let mut map = serializer.serialize_map(Some(self.params.len()))?;
 
         for token in self.params {
-                map.serialize_entry(&token.name, &token.value)?;
+                map.serialize_entry(&token.name, &FormattedValue { value: &token.value, format: self.format })?;
             }
 
         map.end()
@@ -58,59 +84,109 @@ impl<'a> Serialize for FunctionParams<'a> {
 }
 
 impl Token {
-    pub fn detokenize_big_int<S>(number: &BigInt, serializer: S) -> Result<S::Ok, S::Error>
+    pub fn detokenize_big_int<S>(number: &BigInt, format: IntFormat, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut int_str = number.to_str_radix(16);
-        
-        if int_str.starts_with("-") {
-            int_str.insert_str(1, "0x");
-        } else {
-            int_str.insert_str(0, "0x");
-        };
+        match format {
+            IntFormat::Hex => {
+                let mut int_str = number.to_str_radix(16);
 
-        serializer.serialize_str(&int_str)
+                if int_str.starts_with("-") {
+                    int_str.insert_str(1, "0x");
+                } else {
+                    int_str.insert_str(0, "0x");
+                };
+
+                serializer.serialize_str(&int_str)
+            }
+            IntFormat::Decimal => serializer.serialize_str(&number.to_str_radix(10)),
+            IntFormat::Number => {
+                let decimal = number.to_str_radix(10);
+                match decimal.parse::<i64>() {
+                    Ok(number) if -MAX_SAFE_INTEGER <= number && number <= MAX_SAFE_INTEGER => {
+                        serializer.serialize_i64(number)
+                    }
+                    _ => serializer.serialize_str(&decimal)
+                }
+            }
+        }
     }
 
-    pub fn detokenize_big_uint<S>(number: &BigUint, serializer: S) -> Result<S::Ok, S::Error>
+    pub fn detokenize_big_uint<S>(number: &BigUint, format: IntFormat, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let uint_str = "0x".to_owned() + &number.to_str_radix(16);
+        Self::detokenize_big_int(&BigInt::from_biguint(Sign::Plus, number.clone()), format, serializer)
+    }
 
-        serializer.serialize_str(&uint_str)
+    /// Returns canonical string representation of map key: decimal number for integers,
+    /// `workchain:hex` for addresses and hex for fixed bytes
+    pub fn detokenize_map_key(key: &TokenValue) -> AbiResult<String> {
+        match key {
+            TokenValue::Uint(uint) => Ok(uint.number.to_str_radix(10)),
+            TokenValue::Int(int) => Ok(int.number.to_str_radix(10)),
+            TokenValue::Address(address) => Ok(address.to_string()),
+            TokenValue::FixedBytes(ref arr) => Ok(hex::encode(arr)),
+            _ => bail!(AbiErrorKind::InvalidData(
+                format!("Type {} can not be a map key", key.get_param_type())))
+        }
     }
 
-    pub fn detokenize_hashmap<S>(_key_type: &ParamType, values: &HashMap<String, TokenValue>, serializer: S) -> Result<S::Ok, S::Error>
+    /// Serializes map as JSON object. Keys are always written in canonical form returned
+    /// by `detokenize_map_key`, `format` is applied to values only
+    pub fn detokenize_hashmap<S>(key_type: &ParamType, values: &HashMap<String, TokenValue>, format: ValueFormat, serializer: S)
+    -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(Some(values.len()))?;
-        for (k, v) in values {
-            map.serialize_entry(k, v)?;
+        let entries = TokenValue::sorted_map_entries(key_type, values)
+            .map_err(|err| serde::ser::Error::custom(err.to_string()))?;
+
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (k, v) in entries {
+            map.serialize_entry(k, &FormattedValue { value: v, format })?;
         }
         map.end()
     }
 
-    pub fn detokenize_cell<S>(cell: &Cell, serializer: S) -> Result<S::Ok, S::Error>
+    pub fn detokenize_cell<S>(cell: &Cell, format: CellFormat, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
+        if format == CellFormat::Tree {
+            return CellTree::from_cell(cell)
+                .map_err(|err| serde::ser::Error::custom(err.to_string()))?
+                .serialize(serializer);
+        }
+
         let mut data = vec![];
         serialize_tree_of_cells(cell, &mut data)
             .map_err(|err| serde::ser::Error::custom(err.to_string()))?;
 
-        let data = base64::encode(&data);
-        serializer.serialize_str(&data)
+        match format {
+            CellFormat::Hex => serializer.serialize_str(&hex::encode(&data)),
+            _ => serializer.serialize_str(&base64::encode(&data)),
+        }
     }
 
-    pub fn detokenize_bytes<S>(arr: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error>
+    /// Serializes bytes as hex string or as object with encoding marker field
+    pub fn detokenize_bytes<S>(arr: &Vec<u8>, format: BytesFormat, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let data = hex::encode(arr);
-        serializer.serialize_str(&data)
+        let (marker, data) = match format {
+            BytesFormat::Hex => return serializer.serialize_str(&hex::encode(arr)),
+            BytesFormat::Base64 => ("base64", base64::encode(arr)),
+            BytesFormat::Utf8 => match std::str::from_utf8(arr) {
+                Ok(string) => ("utf8", string.to_owned()),
+                Err(_) => ("hex", hex::encode(arr)),
+            },
+        };
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(marker, &data)?;
+        map.end()
     }
 }
 
@@ -119,21 +195,50 @@ impl Serialize for TokenValue {
     where
         S: Serializer,
     {
-        match self {
-            TokenValue::Uint(uint) => Token::detokenize_big_uint(&uint.number, serializer),
-            TokenValue::Int(int) => Token::detokenize_big_int(&int.number, serializer),
+        FormattedValue { value: self, format: IntFormat::default() }.serialize(serializer)
+    }
+}
+
+impl<'a> FormattedValue<'a> {
+    fn serialize_array<S>(&self, tokens: &[TokenValue], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(tokens.len()))?;
+        for token in tokens {
+            seq.serialize_element(&FormattedValue { value: token, format: self.format })?;
+        }
+        seq.end()
+    }
+}
+
+impl<'a> Serialize for FormattedValue<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.value {
+            TokenValue::Uint(uint) => Token::detokenize_big_uint(&uint.number, self.format.int_format, serializer),
+            TokenValue::Int(int) => Token::detokenize_big_int(&int.number, self.format.int_format, serializer),
             TokenValue::Bool(b) => serializer.serialize_bool(b.clone()),
             TokenValue::Tuple(tokens) => {
-                FunctionParams {params: tokens}.serialize(serializer)
+                FunctionParams {params: tokens, format: self.format}.serialize(serializer)
             },
-            TokenValue::Array(ref tokens) => tokens.serialize(serializer),
-            TokenValue::FixedArray(ref tokens) => tokens.serialize(serializer),
-            TokenValue::Cell(ref cell) => Token::detokenize_cell(cell, serializer),
-            TokenValue::Map(key_type, ref map) => Token::detokenize_hashmap(key_type, map, serializer),
-            TokenValue::Address(ref address) => serializer.serialize_str(&address.to_string()),
-            TokenValue::Bytes(ref arr) => Token::detokenize_bytes(arr, serializer),
-            TokenValue::FixedBytes(ref arr) => Token::detokenize_bytes(arr, serializer),
-            TokenValue::Gram(gram) => Token::detokenize_big_int(gram.value(), serializer),
+            TokenValue::Array(ref tokens) => self.serialize_array(tokens, serializer),
+            TokenValue::FixedArray(ref tokens) => self.serialize_array(tokens, serializer),
+            TokenValue::Cell(ref cell) => Token::detokenize_cell(cell, self.format.cell_format, serializer),
+            TokenValue::Map(key_type, ref map) => Token::detokenize_hashmap(key_type, map, self.format, serializer),
+            TokenValue::Address(ref address) => {
+                serializer.serialize_str(&encode_address(address, self.format.address_format))
+            }
+            TokenValue::Bytes(ref arr) => Token::detokenize_bytes(arr, self.format.bytes_format, serializer),
+            TokenValue::FixedBytes(ref arr) => Token::detokenize_bytes(arr, self.format.bytes_format, serializer),
+            TokenValue::Gram(gram) => Token::detokenize_big_int(gram.value(), self.format.int_format, serializer),
+            TokenValue::String(ref string) => serializer.serialize_str(string),
+            TokenValue::Optional(_, Some(ref value)) => {
+                FormattedValue { value, format: self.format }.serialize(serializer)
+            }
+            TokenValue::Optional(_, None) => serializer.serialize_none(),
         }
     }
 }
\ No newline at end of file
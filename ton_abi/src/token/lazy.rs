@@ -0,0 +1,383 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Lazy decoding of arrays and maps.
+//!
+//! `TokenValue::read_from` decodes every array element and map entry at once. Values read by
+//! `TokenValue::read_lazy` keep arrays and maps as dictionaries and decode elements only when
+//! they are accessed, so large registries can be walked page by page.
+
+use {Param, ParamType};
+use super::*;
+use super::deserialize::find_next_bits;
+use crate::error::*;
+
+use ton_types::{BuilderData, SliceData, IBitstring};
+use ton_types::dictionary::{HashmapE, HashmapType};
+
+/// Range of array elements or map entries to decode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pagination {
+    /// Count of elements to skip
+    pub offset: usize,
+    /// Max count of elements to decode
+    pub limit: usize,
+}
+
+/// Parameter with lazily decoded value
+#[derive(Debug, Clone)]
+pub struct LazyToken {
+    pub name: String,
+    pub value: LazyValue,
+}
+
+/// Value which arrays and maps are not decoded yet
+#[derive(Debug, Clone)]
+pub enum LazyValue {
+    /// Decoded value of any type except arrays and maps
+    Value(TokenValue),
+    /// `T[]` or `T[k]` array
+    Array(LazyArray),
+    /// `map(K,V)` dictionary
+    Map(LazyMap),
+}
+
+impl LazyValue {
+    /// Decodes the value completely
+    pub fn into_token_value(self) -> AbiResult<TokenValue> {
+        self.into_page(None)
+    }
+
+    /// Decodes the value. If value is an array or a map only elements within `pagination`
+    /// are decoded, fixed arrays are decoded as dynamic ones in this case
+    pub fn into_page(self, pagination: Option<Pagination>) -> AbiResult<TokenValue> {
+        match self {
+            LazyValue::Value(value) => Ok(value),
+            LazyValue::Array(array) => {
+                let items = match pagination {
+                    Some(page) => array.range(page.offset, page.limit),
+                    None => array.iter(),
+                }.collect::<AbiResult<Vec<_>>>()?;
+                match (array.fixed, pagination) {
+                    (true, None) => Ok(TokenValue::FixedArray(items)),
+                    _ => Ok(TokenValue::Array(items)),
+                }
+            }
+            LazyValue::Map(map) => {
+                let entries = match pagination {
+                    Some(page) => map.range(page.offset, page.limit)?,
+                    None => map.iter()?,
+                }.collect::<AbiResult<HashMap<_, _>>>()?;
+                Ok(TokenValue::Map(map.key_type, entries))
+            }
+        }
+    }
+}
+
+/// Lazily decoded array. Elements are read from the array dictionary on access
+#[derive(Debug, Clone)]
+pub struct LazyArray {
+    item_type: ParamType,
+    size: usize,
+    fixed: bool,
+    dictionary: SliceData,
+    layout: AbiLayout,
+}
+
+impl LazyArray {
+    /// Returns type of array elements
+    pub fn item_type(&self) -> &ParamType {
+        &self.item_type
+    }
+
+    /// Returns count of array elements
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Decodes element with given index
+    pub fn get(&self, index: usize) -> AbiResult<TokenValue> {
+        if index >= self.size {
+            bail!(AbiErrorKind::InvalidData(
+                format!("Array index {} is out of bounds, array length is {}", index, self.size)))
+        }
+
+        let mut key = BuilderData::new();
+        key.append_u32(index as u32)?;
+        let hashmap = HashmapE::with_data(32, self.dictionary.clone().get_dictionary()?);
+        match hashmap.get(key.into()) {
+            Ok(Some(item)) => TokenValue::read_array_item(&self.item_type, item, self.layout),
+            _ => bail!(AbiErrorKind::DeserializationError("Array item is missing", self.dictionary.clone()))
+        }
+    }
+
+    /// Returns iterator decoding all elements one by one
+    pub fn iter(&self) -> LazyArrayIter {
+        self.range(0, self.size)
+    }
+
+    /// Returns iterator decoding at most `limit` elements starting from `offset`
+    pub fn range(&self, offset: usize, limit: usize) -> LazyArrayIter {
+        let start = std::cmp::min(offset, self.size);
+        LazyArrayIter {
+            array: self,
+            next: start,
+            end: start + std::cmp::min(limit, self.size - start),
+        }
+    }
+}
+
+/// Iterator over lazily decoded array elements
+pub struct LazyArrayIter<'a> {
+    array: &'a LazyArray,
+    next: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for LazyArrayIter<'a> {
+    type Item = AbiResult<TokenValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let item = self.array.get(self.next);
+        self.next += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.next;
+        (len, Some(len))
+    }
+}
+
+/// Lazily decoded map. Entries are read from the dictionary on access
+#[derive(Debug, Clone)]
+pub struct LazyMap {
+    key_type: ParamType,
+    value_type: ParamType,
+    dictionary: SliceData,
+    layout: AbiLayout,
+}
+
+impl LazyMap {
+    /// Returns type of map keys
+    pub fn key_type(&self) -> &ParamType {
+        &self.key_type
+    }
+
+    /// Returns type of map values
+    pub fn value_type(&self) -> &ParamType {
+        &self.value_type
+    }
+
+    fn hashmap(&self) -> AbiResult<HashmapE> {
+        Ok(HashmapE::with_data(self.key_type.bit_len(), self.dictionary.clone().get_dictionary()?))
+    }
+
+    /// Decodes value with given key. Key has the same format as map keys in JSON
+    pub fn get(&self, key: &str) -> AbiResult<Option<TokenValue>> {
+        let key = Tokenizer::tokenize_map_key(&self.key_type, key)?.write_map_key()?;
+        match self.hashmap()?.get(key.into())? {
+            Some(value) => Ok(Some(TokenValue::read_map_value(&self.value_type, value, self.layout)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns iterator decoding all entries one by one in the order of dictionary keys
+    pub fn iter(&self) -> AbiResult<LazyMapIter> {
+        self.walk(0, None)
+    }
+
+    /// Returns iterator decoding at most `limit` entries starting from `offset`
+    /// in the order of dictionary keys
+    pub fn range(&self, offset: usize, limit: usize) -> AbiResult<LazyMapIter> {
+        self.walk(offset, Some(limit))
+    }
+
+    fn walk(&self, offset: usize, limit: Option<usize>) -> AbiResult<LazyMapIter> {
+        let mut forks = Vec::new();
+        if let Some(root) = self.dictionary.clone().get_dictionary()? {
+            forks.push((SliceData::from(root), BuilderData::new(), self.key_type.bit_len()));
+        }
+        Ok(LazyMapIter { map: self, forks, skip: offset, limit })
+    }
+}
+
+/// Iterator over lazily decoded map entries. Keys are returned in the JSON format.
+/// Dictionary is walked on demand, skipped entries are not decoded
+pub struct LazyMapIter<'a> {
+    map: &'a LazyMap,
+    // dictionary nodes to visit with their key prefixes and remaining key lengths,
+    // the next node is on top
+    forks: Vec<(SliceData, BuilderData, usize)>,
+    skip: usize,
+    limit: Option<usize>,
+}
+
+impl<'a> LazyMapIter<'a> {
+    // Walks to the next leaf returning raw key and value
+    fn next_leaf(&mut self) -> AbiResult<Option<(SliceData, SliceData)>> {
+        while let Some((mut node, mut key, bits)) = self.forks.pop() {
+            let label = read_label(&mut node, &mut key, bits)?;
+            if label == bits {
+                return Ok(Some((key.into(), node)));
+            }
+            let bits = bits - label - 1;
+            let mut left = key.clone();
+            left.append_bit_zero()?;
+            key.append_bit_one()?;
+            self.forks.push((SliceData::from(node.reference(1)?), key, bits));
+            self.forks.push((SliceData::from(node.reference(0)?), left, bits));
+        }
+        Ok(None)
+    }
+
+    fn decode(&self, key: SliceData, value: SliceData) -> AbiResult<(String, TokenValue)> {
+        let key = Token::detokenize_map_key(&TokenValue::read_map_key(&self.map.key_type, key)?)?;
+        Ok((key, TokenValue::read_map_value(&self.map.value_type, value, self.map.layout)?))
+    }
+}
+
+impl<'a> Iterator for LazyMapIter<'a> {
+    type Item = AbiResult<(String, TokenValue)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.limit == Some(0) {
+            return None;
+        }
+        while self.skip != 0 {
+            match self.next_leaf() {
+                Ok(Some(_)) => self.skip -= 1,
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.limit = self.limit.map(|limit| limit - 1);
+        match self.next_leaf() {
+            Ok(Some((key, value))) => Some(self.decode(key, value)),
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+// Reads label of dictionary node appending its bits to `key`, returns label length.
+// Label of node with `max` remaining key bits is one of
+// `hml_short$0 len:(Unary ~n) s:(n * Bit)`, `hml_long$10 n:(#<= max) s:(n * Bit)`
+// or `hml_same$11 v:Bit n:(#<= max)`
+fn read_label(node: &mut SliceData, key: &mut BuilderData, max: usize) -> AbiResult<usize> {
+    let len_bits = 64 - (max as u64).leading_zeros() as usize;
+    let len = if !node.get_next_bit()? {
+        let mut len = 0;
+        while node.get_next_bit()? {
+            len += 1;
+        }
+        key.append_raw(&node.get_next_bits(len)?, len)?;
+        len
+    } else if !node.get_next_bit()? {
+        let len = read_length(node, len_bits)?;
+        key.append_raw(&node.get_next_bits(len)?, len)?;
+        len
+    } else {
+        let bit = node.get_next_bit()?;
+        let len = read_length(node, len_bits)?;
+        for _ in 0..len {
+            key.append_bit_bool(bit)?;
+        }
+        len
+    };
+    if len > max {
+        bail!(AbiErrorKind::DeserializationError("Dictionary label is longer than key", node.clone()))
+    }
+    Ok(len)
+}
+
+fn read_length(node: &mut SliceData, bits: usize) -> AbiResult<usize> {
+    let mut len = 0;
+    for _ in 0..bits {
+        len = len << 1 | node.get_next_bit()? as usize;
+    }
+    Ok(len)
+}
+
+impl TokenValue {
+    /// Deserializes value from `SliceData` like `read_from` does, but top level arrays and maps
+    /// are not decoded
+    pub fn read_lazy(param_type: &ParamType, mut cursor: SliceData, layout: AbiLayout)
+    -> AbiResult<(LazyValue, SliceData)> {
+        match param_type {
+            ParamType::Array(item_type) => {
+                cursor = find_next_bits(cursor, 32)?;
+                let size = cursor.get_next_u32()? as usize;
+                let (dictionary, cursor) = Self::skip_dictionary(cursor)?;
+                Ok((LazyValue::Array(LazyArray {
+                    item_type: (**item_type).clone(),
+                    size,
+                    fixed: false,
+                    dictionary,
+                    layout,
+                }), cursor))
+            }
+            ParamType::FixedArray(item_type, size) => {
+                let (dictionary, cursor) = Self::skip_dictionary(cursor)?;
+                Ok((LazyValue::Array(LazyArray {
+                    item_type: (**item_type).clone(),
+                    size: *size,
+                    fixed: true,
+                    dictionary,
+                    layout,
+                }), cursor))
+            }
+            ParamType::Map(key_type, value_type) => {
+                let (dictionary, cursor) = Self::skip_dictionary(cursor)?;
+                Ok((LazyValue::Map(LazyMap {
+                    key_type: (**key_type).clone(),
+                    value_type: (**value_type).clone(),
+                    dictionary,
+                    layout,
+                }), cursor))
+            }
+            _ => {
+                let (value, cursor) = Self::read_from(param_type, cursor, layout)?;
+                Ok((LazyValue::Value(value), cursor))
+            }
+        }
+    }
+
+    // Returns slice starting with dictionary and cursor moved after the dictionary
+    fn skip_dictionary(mut cursor: SliceData) -> AbiResult<(SliceData, SliceData)> {
+        cursor = find_next_bits(cursor, 1)?;
+        let dictionary = cursor.clone();
+        cursor.get_dictionary()?;
+        Ok((dictionary, cursor))
+    }
+
+    /// Deserializes parameters lazily one by one
+    pub fn read_lazy_params(params: &[Param], mut cursor: SliceData, layout: AbiLayout)
+    -> AbiResult<(Vec<LazyToken>, SliceData)> {
+        let mut tokens = Vec::new();
+        for param in params.iter() {
+            let (value, new_cursor) = Self::read_lazy(&param.kind, cursor, layout)?;
+            cursor = new_cursor;
+            tokens.push(LazyToken { name: param.name.clone(), value });
+        }
+        Ok((tokens, cursor))
+    }
+}
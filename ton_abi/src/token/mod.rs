@@ -15,21 +15,27 @@
 //! TON ABI params.
 use int::{Int, Uint};
 use {Param, ParamType};
+use layout::AbiLayout;
 
 use std::collections::HashMap;
 use std::fmt;
 use ton_block::{Grams, MsgAddress};
 use ton_types::Cell;
+use crate::error::*;
 
 mod tokenizer;
 mod detokenizer;
 mod serialize;
 mod deserialize;
+mod serde_tokens;
+mod lazy;
 
 pub use self::tokenizer::*;
 pub use self::detokenizer::*;
 pub use self::serialize::*;
 pub use self::deserialize::*;
+pub use self::serde_tokens::*;
+pub use self::lazy::*;
 
 #[cfg(test)]
 mod tests;
@@ -99,7 +105,15 @@ pub enum TokenValue {
     FixedBytes(Vec<u8>),
     /// Nanograms
     /// 
-    Gram(Grams)
+    Gram(Grams),
+    /// UTF-8 string
+    ///
+    /// Encoded as separate cells chain like `Bytes`
+    String(String),
+    /// optional(T): value of the type T or nothing. Holds type T to be able to encode empty value
+    ///
+    /// Encoded as one bit flag followed by the value encoding if value is present
+    Optional(ParamType, Option<Box<TokenValue>>),
 }
 
 impl fmt::Display for TokenValue {
@@ -139,11 +153,54 @@ impl fmt::Display for TokenValue {
             TokenValue::Address(a) => write!(f, "{}", a),
             TokenValue::Bytes(ref arr) | TokenValue::FixedBytes(ref arr) => write!(f, "{:?}", arr),
             TokenValue::Gram(g) => write!(f, "{}", g),
+            TokenValue::String(s) => write!(f, "{:?}", s),
+            TokenValue::Optional(_, Some(value)) => write!(f, "{}", value),
+            TokenValue::Optional(_, None) => write!(f, "null"),
         }
     }
 }
 
 impl TokenValue {
+    /// Checks the type of the token like `type_check` does, but reports path and types
+    /// of the first mismatched value
+    pub fn check_type(&self, param_type: &ParamType, path: &str) -> AbiResult<()> {
+        match (self, param_type) {
+            (TokenValue::Tuple(tokens), ParamType::Tuple(params)) => {
+                Token::check_types_with_path(tokens, params, path)
+            }
+            (TokenValue::Array(tokens), ParamType::Array(item_type)) => {
+                for (i, token) in tokens.iter().enumerate() {
+                    token.check_type(item_type, &item_path(path, i))?;
+                }
+                Ok(())
+            }
+            (TokenValue::FixedArray(tokens), ParamType::FixedArray(item_type, size))
+                if tokens.len() == *size =>
+            {
+                for (i, token) in tokens.iter().enumerate() {
+                    token.check_type(item_type, &item_path(path, i))?;
+                }
+                Ok(())
+            }
+            (TokenValue::Optional(inner_type, Some(value)), ParamType::Optional(param_inner_type))
+                if inner_type == param_inner_type.as_ref() =>
+            {
+                value.check_type(inner_type, path)
+            }
+            (TokenValue::Map(map_key_type, values), ParamType::Map(key_type, value_type))
+                if map_key_type == key_type.as_ref() =>
+            {
+                for (key, value) in values {
+                    value.check_type(value_type, &item_path(path, key))?;
+                }
+                Ok(())
+            }
+            _ if self.type_check(param_type) => Ok(()),
+            _ => bail!(AbiErrorKind::ParameterTypeMismatch(
+                path.to_owned(), param_type.clone(), self.get_param_type())),
+        }
+    }
+
     /// Check whether the type of the token matches the given parameter type.
     ///
     /// Numeric types (`Int` and `Uint`) type check if the size of the token
@@ -187,6 +244,16 @@ impl TokenValue {
             TokenValue::Bytes(_) => *param_type == ParamType::Bytes,
             TokenValue::FixedBytes(ref arr) => *param_type == ParamType::FixedBytes(arr.len()),
             TokenValue::Gram(_) => *param_type == ParamType::Gram,
+            TokenValue::String(_) => *param_type == ParamType::String,
+            TokenValue::Optional(ref inner_type, ref value) => {
+                if let ParamType::Optional(ref param_inner_type) = *param_type {
+                    let param_inner_type: &ParamType = param_inner_type;
+                    inner_type == param_inner_type &&
+                        value.as_ref().map(|value| value.type_check(inner_type)).unwrap_or(true)
+                } else {
+                    false
+                }
+            }
         }
     }
 
@@ -213,6 +280,8 @@ impl TokenValue {
             TokenValue::Bytes(_) => ParamType::Bytes,
             TokenValue::FixedBytes(ref arr) => ParamType::FixedBytes(arr.len()),
             TokenValue::Gram(_) => ParamType::Gram,
+            TokenValue::String(_) => ParamType::String,
+            TokenValue::Optional(inner_type, _) => ParamType::Optional(Box::new(inner_type.clone())),
         }
     }
 }
@@ -228,6 +297,27 @@ impl Token {
         }
     }
 
+    /// Checks types of the tokens like `types_check` does, but returns error describing
+    /// the first mismatched parameter
+    pub fn check_types(tokens: &[Token], params: &[Param]) -> AbiResult<()> {
+        Self::check_types_with_path(tokens, params, "")
+    }
+
+    fn check_types_with_path(tokens: &[Token], params: &[Param], path: &str) -> AbiResult<()> {
+        if params.len() != tokens.len() {
+            bail!(AbiErrorKind::WrongParametersCount(params.len(), tokens.len()));
+        }
+        for (param, token) in params.iter().zip(tokens) {
+            if token.name != param.name {
+                bail!(AbiErrorKind::InvalidData(format!(
+                    "Parameter `{}` expected, `{}` provided",
+                    field_path(path, &param.name), field_path(path, &token.name))));
+            }
+            token.value.check_type(&param.kind, &field_path(path, &param.name))?;
+        }
+        Ok(())
+    }
+
     /// Rerturns `Param` the token represents
     pub fn get_param(&self) -> Param {
         Param {
@@ -235,4 +325,18 @@ impl Token {
             kind: self.value.get_param_type(),
         }
     }
+}
+
+// Builds path to the named field of the value at `path` for error messages
+pub(crate) fn field_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+// Builds path to the array item or map value of the value at `path` for error messages
+pub(crate) fn item_path<T: fmt::Display>(path: &str, index: T) -> String {
+    format!("{}[{}]", path, index)
 }
\ No newline at end of file
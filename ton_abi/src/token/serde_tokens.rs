@@ -0,0 +1,758 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Serde `Serializer` and `Deserializer` converting Rust values directly
+//! to and from ABI tokens.
+//!
+//! Scalar values are converted by the same rules as JSON input in `Tokenizer`,
+//! so `u64` field may be passed to `uint64` param, `u128` to `gram` and so on.
+//! `Vec<u8>` maps to `bytes`, maps map to `map(K,V)`, structs map to tuples by
+//! field names. Addresses and cells are represented by strings, use
+//! `#[serde(with = "ton_abi::token::serde_address")]` and
+//! `#[serde(with = "ton_abi::token::serde_cell")]` for `MsgAddressInt` and `Cell` fields.
+
+use {Param, ParamType, Token, TokenValue};
+use serde::de::{self, Deserialize, DeserializeOwned, Deserializer, DeserializeSeed, IntoDeserializer,
+    MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use serde::ser::{self, Impossible, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use num_bigint::{BigInt, BigUint};
+use ton_types::cells_serialization::serialize_tree_of_cells;
+use crate::error::*;
+use super::Tokenizer;
+
+/// Converts Rust value into tokens according to provided params
+pub fn to_tokens<T: Serialize + ?Sized>(value: &T, params: &[Param]) -> AbiResult<Vec<Token>> {
+    let param_type = ParamType::Tuple(params.to_vec());
+    match value.serialize(TokenSerializer::new(&param_type))? {
+        TokenValue::Tuple(tokens) => Ok(tokens),
+        _ => bail!(AbiErrorKind::WrongParameterType)
+    }
+}
+
+/// Converts tokens into Rust value
+pub fn from_tokens<T: DeserializeOwned>(tokens: &[Token]) -> AbiResult<T> {
+    let value = TokenValue::Tuple(tokens.to_vec());
+    T::deserialize(TokenDeserializer::new(&value))
+}
+
+fn unsupported(what: &str, param_type: &ParamType) -> AbiError {
+    AbiErrorKind::InvalidData(
+        format!("{} can not be serialized as {}", what, param_type.type_signature())).into()
+}
+
+fn parse_number<T: FromStr>(string: &str) -> AbiResult<T> {
+    string.parse().map_err(|_| AbiErrorKind::InvalidData(format!("{} is not a valid number", string)).into())
+}
+
+/// Serializes Rust value into `TokenValue` of provided type
+pub struct TokenSerializer<'a> {
+    param_type: &'a ParamType,
+}
+
+impl<'a> TokenSerializer<'a> {
+    pub fn new(param_type: &'a ParamType) -> Self {
+        Self { param_type }
+    }
+
+    fn tokenize(self, value: Value) -> AbiResult<TokenValue> {
+        Tokenizer::tokenize_parameter(self.param_type, &value)
+    }
+}
+
+impl<'a> Serializer for TokenSerializer<'a> {
+    type Ok = TokenValue;
+    type Error = AbiError;
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = SeqSerializer<'a>;
+    type SerializeTupleStruct = SeqSerializer<'a>;
+    type SerializeTupleVariant = Impossible<TokenValue, AbiError>;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = StructSerializer<'a>;
+    type SerializeStructVariant = Impossible<TokenValue, AbiError>;
+
+    fn serialize_bool(self, v: bool) -> AbiResult<TokenValue> {
+        self.tokenize(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> AbiResult<TokenValue> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> AbiResult<TokenValue> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> AbiResult<TokenValue> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> AbiResult<TokenValue> {
+        self.tokenize(Value::from(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> AbiResult<TokenValue> {
+        self.tokenize(Value::String(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> AbiResult<TokenValue> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> AbiResult<TokenValue> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> AbiResult<TokenValue> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> AbiResult<TokenValue> {
+        self.tokenize(Value::from(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> AbiResult<TokenValue> {
+        self.tokenize(Value::String(v.to_string()))
+    }
+
+    fn serialize_f32(self, _v: f32) -> AbiResult<TokenValue> {
+        Err(unsupported("float", self.param_type))
+    }
+
+    fn serialize_f64(self, _v: f64) -> AbiResult<TokenValue> {
+        Err(unsupported("float", self.param_type))
+    }
+
+    fn serialize_char(self, v: char) -> AbiResult<TokenValue> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> AbiResult<TokenValue> {
+        self.tokenize(Value::String(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> AbiResult<TokenValue> {
+        match self.param_type {
+            ParamType::Bytes => Ok(TokenValue::Bytes(v.to_vec())),
+            ParamType::FixedBytes(size) if *size == v.len() => Ok(TokenValue::FixedBytes(v.to_vec())),
+            _ => Err(unsupported("byte array", self.param_type))
+        }
+    }
+
+    fn serialize_none(self) -> AbiResult<TokenValue> {
+        match self.param_type {
+            ParamType::Optional(inner_type) => Ok(TokenValue::Optional((**inner_type).clone(), None)),
+            _ => Err(unsupported("none", self.param_type))
+        }
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> AbiResult<TokenValue> {
+        match self.param_type {
+            ParamType::Optional(inner_type) => Ok(TokenValue::Optional(
+                (**inner_type).clone(),
+                Some(Box::new(value.serialize(TokenSerializer::new(inner_type))?)))),
+            _ => value.serialize(self)
+        }
+    }
+
+    fn serialize_unit(self) -> AbiResult<TokenValue> {
+        Err(unsupported("unit", self.param_type))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> AbiResult<TokenValue> {
+        Err(unsupported(name, self.param_type))
+    }
+
+    /// Unit enum variants are represented by their indexes
+    fn serialize_unit_variant(
+        self, _name: &'static str, variant_index: u32, _variant: &'static str
+    ) -> AbiResult<TokenValue> {
+        self.serialize_u32(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self, _name: &'static str, value: &T
+    ) -> AbiResult<TokenValue> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self, name: &'static str, _variant_index: u32, _variant: &'static str, _value: &T
+    ) -> AbiResult<TokenValue> {
+        Err(unsupported(name, self.param_type))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> AbiResult<SeqSerializer<'a>> {
+        SeqSerializer::new(self.param_type)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> AbiResult<SeqSerializer<'a>> {
+        SeqSerializer::new(self.param_type)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> AbiResult<SeqSerializer<'a>> {
+        SeqSerializer::new(self.param_type)
+    }
+
+    fn serialize_tuple_variant(
+        self, name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize
+    ) -> AbiResult<Self::SerializeTupleVariant> {
+        Err(unsupported(name, self.param_type))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> AbiResult<MapSerializer<'a>> {
+        MapSerializer::new(self.param_type)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> AbiResult<StructSerializer<'a>> {
+        StructSerializer::new(self.param_type)
+    }
+
+    fn serialize_struct_variant(
+        self, name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize
+    ) -> AbiResult<Self::SerializeStructVariant> {
+        Err(unsupported(name, self.param_type))
+    }
+}
+
+/// Serializes sequences into arrays, byte arrays and tuples (by position)
+pub struct SeqSerializer<'a> {
+    param_type: &'a ParamType,
+    byte_type: ParamType,
+    values: Vec<TokenValue>,
+}
+
+impl<'a> SeqSerializer<'a> {
+    fn new(param_type: &'a ParamType) -> AbiResult<Self> {
+        match param_type {
+            ParamType::Array(_) | ParamType::FixedArray(_, _) | ParamType::Bytes |
+            ParamType::FixedBytes(_) | ParamType::Tuple(_) => Ok(Self {
+                param_type,
+                byte_type: ParamType::Uint(8),
+                values: vec![],
+            }),
+            _ => Err(unsupported("sequence", param_type))
+        }
+    }
+
+    fn item_type(&self) -> AbiResult<&ParamType> {
+        match self.param_type {
+            ParamType::Array(item_type) | ParamType::FixedArray(item_type, _) => Ok(&**item_type),
+            ParamType::Tuple(params) => params
+                .get(self.values.len())
+                .map(|param| &param.kind)
+                .ok_or_else(|| AbiErrorKind::WrongParametersCount(params.len(), self.values.len() + 1).into()),
+            _ => Ok(&self.byte_type)
+        }
+    }
+
+    fn collect_bytes(values: Vec<TokenValue>) -> AbiResult<Vec<u8>> {
+        values
+            .into_iter()
+            .map(|value| match value {
+                TokenValue::Uint(uint) => Ok(uint.number.to_bytes_le()[0]),
+                _ => Err(AbiErrorKind::WrongParameterType.into())
+            })
+            .collect()
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> AbiResult<()> {
+        let value = value.serialize(TokenSerializer::new(self.item_type()?))?;
+        self.values.push(value);
+        Ok(())
+    }
+
+    fn finish(self) -> AbiResult<TokenValue> {
+        match self.param_type {
+            ParamType::Array(_) => Ok(TokenValue::Array(self.values)),
+            ParamType::FixedArray(_, size) => {
+                if *size != self.values.len() {
+                    bail!(AbiErrorKind::InvalidData(
+                        format!("{} array items expected, {} provided", size, self.values.len())))
+                }
+                Ok(TokenValue::FixedArray(self.values))
+            }
+            ParamType::Bytes => Ok(TokenValue::Bytes(Self::collect_bytes(self.values)?)),
+            ParamType::FixedBytes(size) => {
+                if *size != self.values.len() {
+                    bail!(AbiErrorKind::InvalidData(
+                        format!("{} bytes expected, {} provided", size, self.values.len())))
+                }
+                Ok(TokenValue::FixedBytes(Self::collect_bytes(self.values)?))
+            }
+            ParamType::Tuple(params) => {
+                if params.len() != self.values.len() {
+                    bail!(AbiErrorKind::WrongParametersCount(params.len(), self.values.len()))
+                }
+                Ok(TokenValue::Tuple(params
+                    .iter()
+                    .zip(self.values.into_iter())
+                    .map(|(param, value)| Token { name: param.name.clone(), value })
+                    .collect()))
+            }
+            _ => bail!(AbiErrorKind::WrongParameterType)
+        }
+    }
+}
+
+impl<'a> ser::SerializeSeq for SeqSerializer<'a> {
+    type Ok = TokenValue;
+    type Error = AbiError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> AbiResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> AbiResult<TokenValue> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTuple for SeqSerializer<'a> {
+    type Ok = TokenValue;
+    type Error = AbiError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> AbiResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> AbiResult<TokenValue> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SeqSerializer<'a> {
+    type Ok = TokenValue;
+    type Error = AbiError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> AbiResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> AbiResult<TokenValue> {
+        self.finish()
+    }
+}
+
+/// Serializes maps into ABI dictionaries
+pub struct MapSerializer<'a> {
+    key_type: &'a ParamType,
+    value_type: &'a ParamType,
+    map: HashMap<String, TokenValue>,
+    key: Option<String>,
+}
+
+impl<'a> MapSerializer<'a> {
+    fn new(param_type: &'a ParamType) -> AbiResult<Self> {
+        match param_type {
+            ParamType::Map(key_type, value_type) => Ok(Self {
+                key_type: &**key_type,
+                value_type: &**value_type,
+                map: HashMap::new(),
+                key: None,
+            }),
+            _ => Err(unsupported("map", param_type))
+        }
+    }
+}
+
+impl<'a> ser::SerializeMap for MapSerializer<'a> {
+    type Ok = TokenValue;
+    type Error = AbiError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> AbiResult<()> {
+        let key = key.serialize(TokenSerializer::new(self.key_type))?;
+        self.key = Some(Token::detokenize_map_key(&key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> AbiResult<()> {
+        let key = self.key.take()
+            .ok_or_else(|| AbiError::from(AbiErrorKind::InvalidData("map value without key".to_owned())))?;
+        let value = value.serialize(TokenSerializer::new(self.value_type))?;
+        self.map.insert(key, value);
+        Ok(())
+    }
+
+    fn end(self) -> AbiResult<TokenValue> {
+        Ok(TokenValue::Map(self.key_type.clone(), self.map))
+    }
+}
+
+/// Serializes structs into tuples matching fields by names
+pub struct StructSerializer<'a> {
+    params: &'a [Param],
+    values: HashMap<&'static str, TokenValue>,
+}
+
+impl<'a> StructSerializer<'a> {
+    fn new(param_type: &'a ParamType) -> AbiResult<Self> {
+        match param_type {
+            ParamType::Tuple(params) => Ok(Self { params, values: HashMap::new() }),
+            _ => Err(unsupported("struct", param_type))
+        }
+    }
+}
+
+impl<'a> ser::SerializeStruct for StructSerializer<'a> {
+    type Ok = TokenValue;
+    type Error = AbiError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> AbiResult<()> {
+        let param = self.params
+            .iter()
+            .find(|param| param.name == key)
+            .ok_or_else(|| AbiError::from(AbiErrorKind::InvalidName(key.to_owned())))?;
+        let value = value.serialize(TokenSerializer::new(&param.kind))?;
+        self.values.insert(key, value);
+        Ok(())
+    }
+
+    fn end(mut self) -> AbiResult<TokenValue> {
+        let mut tokens = Vec::with_capacity(self.params.len());
+        for param in self.params {
+            let value = match (self.values.remove(param.name.as_str()), &param.kind) {
+                (Some(value), _) => value,
+                // skipped optional fields are treated as empty values
+                (None, ParamType::Optional(inner_type)) => TokenValue::Optional((**inner_type).clone(), None),
+                (None, _) => bail!(AbiErrorKind::InvalidData(format!("field `{}` is missing", param.name))),
+            };
+            tokens.push(Token { name: param.name.clone(), value });
+        }
+        Ok(TokenValue::Tuple(tokens))
+    }
+}
+
+/// Deserializes Rust value from `TokenValue`
+pub struct TokenDeserializer<'a> {
+    value: &'a TokenValue,
+}
+
+impl<'a> TokenDeserializer<'a> {
+    pub fn new(value: &'a TokenValue) -> Self {
+        Self { value }
+    }
+
+    fn visit_uint<'de, V: Visitor<'de>>(number: &BigUint, visitor: V) -> AbiResult<V::Value> {
+        let bits = number.bits();
+        if bits <= 64 {
+            visitor.visit_u64(parse_number(&number.to_str_radix(10))?)
+        } else if bits <= 128 {
+            visitor.visit_u128(parse_number(&number.to_str_radix(10))?)
+        } else {
+            visitor.visit_string(number.to_str_radix(10))
+        }
+    }
+
+    fn visit_int<'de, V: Visitor<'de>>(number: &BigInt, visitor: V) -> AbiResult<V::Value> {
+        let bits = number.bits();
+        if bits < 64 {
+            visitor.visit_i64(parse_number(&number.to_str_radix(10))?)
+        } else if bits < 128 {
+            visitor.visit_i128(parse_number(&number.to_str_radix(10))?)
+        } else {
+            visitor.visit_string(number.to_str_radix(10))
+        }
+    }
+
+    fn number_string(&self) -> Option<String> {
+        match self.value {
+            TokenValue::Uint(uint) => Some(uint.number.to_str_radix(10)),
+            TokenValue::Int(int) => Some(int.number.to_str_radix(10)),
+            TokenValue::Gram(gram) => Some(gram.value().to_str_radix(10)),
+            _ => None
+        }
+    }
+}
+
+impl<'de, 'a> Deserializer<'de> for TokenDeserializer<'a> {
+    type Error = AbiError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> AbiResult<V::Value> {
+        match self.value {
+            TokenValue::Uint(uint) => Self::visit_uint(&uint.number, visitor),
+            TokenValue::Int(int) => Self::visit_int(&int.number, visitor),
+            TokenValue::Gram(gram) => Self::visit_int(gram.value(), visitor),
+            TokenValue::Bool(value) => visitor.visit_bool(*value),
+            TokenValue::Tuple(tokens) => visitor.visit_map(TupleAccess { tokens: tokens.iter(), value: None }),
+            TokenValue::Array(values) | TokenValue::FixedArray(values) =>
+                visitor.visit_seq(ValuesAccess { values: values.iter() }),
+            TokenValue::Bytes(bytes) | TokenValue::FixedBytes(bytes) =>
+                visitor.visit_seq(de::value::SeqDeserializer::<_, AbiError>::new(bytes.iter().cloned())),
+            TokenValue::Map(key_type, map) => visitor.visit_map(DictionaryAccess {
+                entries: TokenValue::sorted_map_entries(key_type, map)?.into_iter(),
+                value: None,
+            }),
+            TokenValue::Address(address) => visitor.visit_string(address.to_string()),
+            TokenValue::Cell(cell) => {
+                let mut data = vec![];
+                serialize_tree_of_cells(cell, &mut data)?;
+                visitor.visit_string(base64::encode(&data))
+            }
+            TokenValue::String(string) => visitor.visit_string(string.clone()),
+            TokenValue::Optional(_, Some(value)) => TokenDeserializer::new(value).deserialize_any(visitor),
+            TokenValue::Optional(_, None) => visitor.visit_none(),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> AbiResult<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    /// Numbers are deserialized into strings as decimals, byte arrays - as hex
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> AbiResult<V::Value> {
+        if let Some(number) = self.number_string() {
+            return visitor.visit_string(number);
+        }
+        match self.value {
+            TokenValue::Bytes(bytes) | TokenValue::FixedBytes(bytes) => visitor.visit_string(hex::encode(bytes)),
+            TokenValue::Optional(_, Some(value)) => TokenDeserializer::new(value).deserialize_string(visitor),
+            _ => self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> AbiResult<V::Value> {
+        match self.value {
+            TokenValue::Bytes(bytes) | TokenValue::FixedBytes(bytes) => visitor.visit_bytes(bytes),
+            _ => self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> AbiResult<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> AbiResult<V::Value> {
+        match self.value {
+            TokenValue::Optional(_, Some(value)) => visitor.visit_some(TokenDeserializer::new(value)),
+            TokenValue::Optional(_, None) => visitor.visit_none(),
+            _ => visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> AbiResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> AbiResult<V::Value> {
+        match self.value {
+            TokenValue::Tuple(tokens) => visitor.visit_seq(TupleValuesAccess { tokens: tokens.iter() }),
+            _ => self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self, _name: &'static str, len: usize, visitor: V
+    ) -> AbiResult<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    /// Unit enum variants are represented by their indexes
+    fn deserialize_enum<V: Visitor<'de>>(
+        self, _name: &'static str, _variants: &'static [&'static str], visitor: V
+    ) -> AbiResult<V::Value> {
+        let index: u32 = match self.number_string() {
+            Some(number) => parse_number(&number)?,
+            None => bail!(AbiErrorKind::WrongParameterType)
+        };
+        visitor.visit_enum(index.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        unit unit_struct seq map struct identifier ignored_any
+    }
+}
+
+/// Provides tuple tokens as struct fields
+struct TupleAccess<'a> {
+    tokens: std::slice::Iter<'a, Token>,
+    value: Option<&'a TokenValue>,
+}
+
+impl<'de, 'a> MapAccess<'de> for TupleAccess<'a> {
+    type Error = AbiError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> AbiResult<Option<K::Value>> {
+        match self.tokens.next() {
+            Some(token) => {
+                self.value = Some(&token.value);
+                seed.deserialize(token.name.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None)
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> AbiResult<V::Value> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(TokenDeserializer::new(value)),
+            None => bail!(AbiErrorKind::InvalidData("value without key".to_owned()))
+        }
+    }
+}
+
+/// Provides tuple tokens as sequence of values
+struct TupleValuesAccess<'a> {
+    tokens: std::slice::Iter<'a, Token>,
+}
+
+impl<'de, 'a> SeqAccess<'de> for TupleValuesAccess<'a> {
+    type Error = AbiError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> AbiResult<Option<T::Value>> {
+        match self.tokens.next() {
+            Some(token) => seed.deserialize(TokenDeserializer::new(&token.value)).map(Some),
+            None => Ok(None)
+        }
+    }
+}
+
+/// Provides array items
+struct ValuesAccess<'a> {
+    values: std::slice::Iter<'a, TokenValue>,
+}
+
+impl<'de, 'a> SeqAccess<'de> for ValuesAccess<'a> {
+    type Error = AbiError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> AbiResult<Option<T::Value>> {
+        match self.values.next() {
+            Some(value) => seed.deserialize(TokenDeserializer::new(value)).map(Some),
+            None => Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// Provides dictionary entries in canonical key order
+struct DictionaryAccess<'a> {
+    entries: std::vec::IntoIter<(&'a String, &'a TokenValue)>,
+    value: Option<&'a TokenValue>,
+}
+
+impl<'de, 'a> MapAccess<'de> for DictionaryAccess<'a> {
+    type Error = AbiError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> AbiResult<Option<K::Value>> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(MapKeyDeserializer { key }).map(Some)
+            }
+            None => Ok(None)
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> AbiResult<V::Value> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(TokenDeserializer::new(value)),
+            None => bail!(AbiErrorKind::InvalidData("value without key".to_owned()))
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// Deserializes canonical map key string either as string or as number
+struct MapKeyDeserializer<'a> {
+    key: &'a str,
+}
+
+macro_rules! deserialize_key_number {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> AbiResult<V::Value> {
+                visitor.$visit(parse_number(self.key)?)
+            }
+        )*
+    }
+}
+
+impl<'de, 'a> Deserializer<'de> for MapKeyDeserializer<'a> {
+    type Error = AbiError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> AbiResult<V::Value> {
+        visitor.visit_str(self.key)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> AbiResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    deserialize_key_number! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+    }
+
+    forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf option unit unit_struct
+        seq tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Serde helpers for types represented by their string form (e.g. `MsgAddressInt`)
+pub mod serde_address {
+    use super::*;
+
+    pub fn serialize<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        T::from_str(&string).map_err(de::Error::custom)
+    }
+}
+
+/// Serde helpers for `Cell` represented by base64 encoded BOC
+pub mod serde_cell {
+    use super::*;
+    use std::io::Cursor;
+    use ton_types::Cell;
+    use ton_types::cells_serialization::deserialize_tree_of_cells;
+
+    pub fn serialize<S: Serializer>(cell: &Cell, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut data = vec![];
+        serialize_tree_of_cells(cell, &mut data).map_err(ser::Error::custom)?;
+        serializer.serialize_str(&base64::encode(&data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Cell, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        let data = base64::decode(&string).map_err(de::Error::custom)?;
+        deserialize_tree_of_cells(&mut Cursor::new(data)).map_err(de::Error::custom)
+    }
+}
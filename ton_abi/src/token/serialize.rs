@@ -15,27 +15,27 @@
 use super::*;
 use crate::error::*;
 
-use ton_types::{BuilderData, IBitstring};
+use ton_types::{BuilderData, SliceData, IBitstring};
 use ton_block::Serializable;
 use ton_types::dictionary::HashmapE;
 
 use num_bigint::{BigInt, Sign};
 
 impl TokenValue {
-    pub fn pack_values_into_chain(tokens: &[Token], mut cells: Vec<BuilderData>) -> AbiResult<BuilderData> {
+    pub fn pack_values_into_chain(tokens: &[Token], mut cells: Vec<BuilderData>, layout: AbiLayout) -> AbiResult<BuilderData> {
         for token in tokens {
-            cells.append(&mut token.value.write_to_cells()?);
+            cells.append(&mut token.value.write_to_cells(layout)?);
         }
-        Self::pack_cells_into_chain(cells)
+        Self::pack_cells_into_chain(cells, layout)
     }
 
-    pub fn pack_into_chain(&self) -> AbiResult<BuilderData> {
-        Self::pack_cells_into_chain(self.write_to_cells()?)
+    pub fn pack_into_chain(&self, layout: AbiLayout) -> AbiResult<BuilderData> {
+        Self::pack_cells_into_chain(self.write_to_cells(layout)?, layout)
     }
 
     // first cell is resulting builder
     // every next cell: put data to root
-    fn pack_cells_into_chain(mut cells: Vec<BuilderData>) -> AbiResult<BuilderData> {
+    fn pack_cells_into_chain(mut cells: Vec<BuilderData>, layout: AbiLayout) -> AbiResult<BuilderData> {
         cells.reverse();
         let mut packed_cells = match cells.pop() {
             Some(cell) => vec![cell],
@@ -43,10 +43,10 @@ impl TokenValue {
         };
         while let Some(cell) = cells.pop() {
             let builder = packed_cells.last_mut().unwrap();
-            if builder.bits_free() < cell.bits_used() || builder.references_free() <= cell.references_used() {
-                packed_cells.push(cell);
-            } else {
+            if layout.fits(builder.bits_free(), builder.references_free(), cell.bits_used(), cell.references_used()) {
                 builder.append_builder(&cell)?;
+            } else {
+                packed_cells.push(cell);
             }
         }
         while let Some(cell) = packed_cells.pop() {
@@ -59,7 +59,7 @@ impl TokenValue {
     }
 
 
-    fn write_to_cells(&self) -> AbiResult<Vec<BuilderData>> {
+    fn write_to_cells(&self, layout: AbiLayout) -> AbiResult<Vec<BuilderData>> {
         match self {
             TokenValue::Uint(uint) => Self::write_uint(uint),
             TokenValue::Int(int) => Self::write_int(int),
@@ -67,20 +67,33 @@ impl TokenValue {
             TokenValue::Tuple(ref tokens) => {
                 let mut vec = vec![];
                 for token in tokens.iter() {
-                    vec.append(&mut token.value.write_to_cells()?);
+                    vec.append(&mut token.value.write_to_cells(layout)?);
                 }
                 Ok(vec)
             }
-            TokenValue::Array(ref tokens) => Self::write_array(tokens),
-            TokenValue::FixedArray(ref tokens) => Self::write_fixed_array(tokens),
+            TokenValue::Array(ref tokens) => Self::write_array(tokens, layout),
+            TokenValue::FixedArray(ref tokens) => Self::write_fixed_array(tokens, layout),
             TokenValue::Cell(cell) => Self::write_cell(cell),
-            TokenValue::Map(key_type, value) => Self::write_map(key_type, value),
+            TokenValue::Map(key_type, value) => Self::write_map(key_type, value, layout),
             TokenValue::Address(address) => Ok(vec![address.write_to_new_cell()?]),
             TokenValue::Bytes(ref arr) | TokenValue::FixedBytes(ref arr) => Self::write_bytes(arr.to_vec()),
             TokenValue::Gram(gram) => Ok(vec![gram.write_to_new_cell()?]),
+            TokenValue::String(ref string) => Self::write_bytes(string.as_bytes().to_vec()),
+            TokenValue::Optional(_, ref value) => Self::write_optional(value, layout),
         }
     }
 
+    fn write_optional(value: &Option<Box<TokenValue>>, layout: AbiLayout) -> AbiResult<Vec<BuilderData>> {
+        let mut builder = BuilderData::new();
+        builder.append_bit_bool(value.is_some())?;
+
+        let mut cells = vec![builder];
+        if let Some(value) = value {
+            cells.append(&mut value.write_to_cells(layout)?);
+        }
+        Ok(cells)
+    }
+
     fn write_int(value: &Int) -> AbiResult<Vec<BuilderData>> {
         let vec = value.number.to_signed_bytes_be();
         let vec_bits_length = vec.len() * 8;
@@ -134,13 +147,13 @@ impl TokenValue {
 
     // creates dictionary with indexes of an array items as keys and items as values
     // and prepends dictionary to cell
-    fn put_array_into_dictionary(array: &[TokenValue]) -> AbiResult<HashmapE> {
+    fn put_array_into_dictionary(array: &[TokenValue], layout: AbiLayout) -> AbiResult<HashmapE> {
         let mut map = HashmapE::with_bit_len(32);
 
         for i in 0..array.len() {
             let index = (i as u32).write_to_new_cell()?;
 
-            let data = Self::pack_cells_into_chain(array[i].write_to_cells()?)?;
+            let data = Self::pack_cells_into_chain(array[i].write_to_cells(layout)?, layout)?;
 
             map.set(index.into(), &data.into())?;
         }
@@ -148,8 +161,8 @@ impl TokenValue {
         Ok(map)
     }
 
-    fn write_array(value: &Vec<TokenValue>) -> AbiResult<Vec<BuilderData>> {
-        let map = Self::put_array_into_dictionary(value)?;
+    fn write_array(value: &Vec<TokenValue>, layout: AbiLayout) -> AbiResult<Vec<BuilderData>> {
+        let map = Self::put_array_into_dictionary(value, layout)?;
 
         let mut builder = BuilderData::new();
         builder.append_u32(value.len() as u32)?;
@@ -159,8 +172,8 @@ impl TokenValue {
         Ok(vec![builder])
     }
 
-    fn write_fixed_array(value: &Vec<TokenValue>) -> AbiResult<Vec<BuilderData>> {
-        let map = Self::put_array_into_dictionary(value)?;
+    fn write_fixed_array(value: &Vec<TokenValue>, layout: AbiLayout) -> AbiResult<Vec<BuilderData>> {
+        let map = Self::put_array_into_dictionary(value, layout)?;
 
         Ok(vec![map.write_to_new_cell()?])
     }
@@ -183,24 +196,56 @@ impl TokenValue {
         Ok(vec![builder])
     }
 
-    fn write_map(key_type: &ParamType, value: &HashMap<String, TokenValue>) -> AbiResult<Vec<BuilderData>> {
-        let bit_len = match key_type {
-            ParamType::Int(size) | ParamType::Uint(size) => *size,
-            _ => bail!(AbiErrorKind::InvalidData("Only int and uint types can be map keys".to_owned()))
+    /// Serializes map key into bits which are used as dictionary key
+    pub fn write_map_key(&self) -> AbiResult<BuilderData> {
+        let key_type = self.get_param_type();
+        let builder = match self {
+            TokenValue::Uint(_) | TokenValue::Int(_) | TokenValue::Address(_) => {
+                // keys are single cells, so layout doesn't matter
+                let mut cells = self.write_to_cells(AbiLayout::default())?;
+                if cells.len() != 1 {
+                    bail!(AbiErrorKind::InvalidData("Map key must 1-cell length".to_owned()))
+                };
+                cells.pop().unwrap()
+            }
+            TokenValue::FixedBytes(ref arr) => BuilderData::with_raw(arr.clone(), arr.len() * 8)?,
+            _ => bail!(AbiErrorKind::InvalidData(format!("Type {} can not be a map key", key_type)))
         };
+
+        if key_type.bit_len() == 0 || builder.bits_used() != key_type.bit_len() || builder.references_used() != 0 {
+            bail!(AbiErrorKind::InvalidData(format!("Invalid map key {}: {} bits expected, {} provided",
+                self, key_type.bit_len(), builder.bits_used())))
+        }
+
+        Ok(builder)
+    }
+
+    /// Returns map entries in canonical order, i.e. in the order of dictionary keys
+    pub fn sorted_map_entries<'a>(key_type: &ParamType, map: &'a HashMap<String, TokenValue>)
+    -> AbiResult<Vec<(&'a String, &'a TokenValue)>> {
+        let mut entries = Vec::new();
+        for (key, value) in map.iter() {
+            let key_builder = Tokenizer::tokenize_map_key(key_type, key)?.write_map_key()?;
+            entries.push((SliceData::from(key_builder).get_bytestring(0), key, value));
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(entries.into_iter().map(|(_, key, value)| (key, value)).collect())
+    }
+
+    fn write_map(key_type: &ParamType, value: &HashMap<String, TokenValue>, layout: AbiLayout) -> AbiResult<Vec<BuilderData>> {
+        let bit_len = key_type.bit_len();
+        if bit_len == 0 {
+            bail!(AbiErrorKind::InvalidData(format!("Type {} can not be a map key", key_type)))
+        }
         let mut hashmap = HashmapE::with_bit_len(bit_len);
 
         for (key, value) in value.iter() {
-            let key = Tokenizer::tokenize_parameter(key_type, &serde_json::from_str(key)?)?;
-
-            let mut key_vec = key.write_to_cells()?;
-            if key_vec.len() != 1 {
-                bail!(AbiErrorKind::InvalidData("Map key must 1-cell length".to_owned()))
-            };
+            let key = Tokenizer::tokenize_map_key(key_type, key)?.write_map_key()?;
 
-            let data = Self::pack_cells_into_chain(value.write_to_cells()?)?;
+            let data = Self::pack_cells_into_chain(value.write_to_cells(layout)?, layout)?;
 
-            hashmap.set(key_vec.pop().unwrap().into(), &data.into())?;
+            hashmap.set(key.into(), &data.into())?;
         }
 
         let mut builder = BuilderData::new();
@@ -217,7 +262,7 @@ fn test_pack_cells() {
         BuilderData::with_bitstring(vec![3, 4, 0x80]).unwrap(),
     ];
     let builder = BuilderData::with_bitstring(vec![1, 2, 3, 4, 0x80]).unwrap();
-    assert_eq!(TokenValue::pack_cells_into_chain(cells).unwrap(), builder);
+    assert_eq!(TokenValue::pack_cells_into_chain(cells, AbiLayout::V1Greedy).unwrap(), builder);
 
     let cells = vec![
         BuilderData::with_raw(vec![0x55; 100], 100 * 8).unwrap(),
@@ -228,6 +273,6 @@ fn test_pack_cells() {
     let builder = BuilderData::with_raw(vec![0x55; 127], 127 * 8).unwrap();
     let builder = BuilderData::with_raw_and_refs(vec![0x55; 127], 127 * 8, vec![builder.into()]).unwrap();
     let builder = BuilderData::with_raw_and_refs(vec![0x55; 100], 100 * 8, vec![builder.into()]).unwrap();
-    let tree = TokenValue::pack_cells_into_chain(cells).unwrap();
+    let tree = TokenValue::pack_cells_into_chain(cells, AbiLayout::V1Greedy).unwrap();
     assert_eq!(tree, builder);
 }
\ No newline at end of file
@@ -13,7 +13,7 @@
 */
 
 mod tokenize_tests {
-    use crate::{Int, Param, ParamType, Token, TokenValue, Uint};
+    use crate::{AbiLayout, Int, Param, ParamType, Token, TokenValue, Uint};
     // use serde::Serialize;
     use std::collections::HashMap;
     use token::{Detokenizer, Tokenizer};
@@ -496,6 +496,61 @@ mod tokenize_tests {
         );
     }
 
+    #[test]
+    fn test_tokenize_hashmap_keys() {
+        let input = r#"{
+            "a": {
+                "0x10": 1,
+                "2": 2
+            },
+            "b": {
+                "0:1111111111111111111111111111111111111111111111111111111111111111": true
+            },
+            "c": {
+                "0a0b": 3
+            }
+        }"#;
+
+        let params = vec![
+            Param::new("a", ParamType::Map(Box::new(ParamType::Uint(256)), Box::new(ParamType::Uint(8)))),
+            Param::new("b", ParamType::Map(Box::new(ParamType::Address), Box::new(ParamType::Bool))),
+            Param::new("c", ParamType::Map(Box::new(ParamType::FixedBytes(2)), Box::new(ParamType::Uint(8)))),
+        ];
+
+        let mut expected_tokens = vec![];
+        let mut map = HashMap::<String, TokenValue>::new();
+        map.insert("16".to_owned(), TokenValue::Uint(Uint::new(1, 8)));
+        map.insert("2".to_owned(), TokenValue::Uint(Uint::new(2, 8)));
+        expected_tokens.push(Token::new("a", TokenValue::Map(ParamType::Uint(256), map)));
+
+        let mut map = HashMap::<String, TokenValue>::new();
+        map.insert(
+            "0:1111111111111111111111111111111111111111111111111111111111111111".to_owned(),
+            TokenValue::Bool(true));
+        expected_tokens.push(Token::new("b", TokenValue::Map(ParamType::Address, map)));
+
+        let mut map = HashMap::<String, TokenValue>::new();
+        map.insert("0a0b".to_owned(), TokenValue::Uint(Uint::new(3, 8)));
+        expected_tokens.push(Token::new("c", TokenValue::Map(ParamType::FixedBytes(2), map)));
+
+        assert_eq!(
+            Tokenizer::tokenize_all(&params, &serde_json::from_str(input).unwrap()).unwrap(),
+            expected_tokens
+        );
+
+        // keys are printed in canonical dictionary order
+        assert_eq!(
+            Detokenizer::detokenize(&params, &expected_tokens).unwrap(),
+            r#"{"a":{"2":"0x2","16":"0x1"},"b":{"0:1111111111111111111111111111111111111111111111111111111111111111":true},"c":{"0a0b":"0x3"}}"#
+        );
+
+        // keys of wrong type are rejected
+        let params = vec![
+            Param::new("a", ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::Uint(8)))),
+        ];
+        assert!(Tokenizer::tokenize_all(&params, &serde_json::from_str(r#"{"a":{"256":1}}"#).unwrap()).is_err());
+    }
+
     #[test]
     fn test_tokenize_address() {
         let input = r#"{
@@ -568,6 +623,144 @@ mod tokenize_tests {
             expected_tokens
         );
     }
+
+    #[test]
+    fn test_tokenize_string_and_optional() {
+        let input = r#"{
+            "name": "Крипто token",
+            "symbol": "TON",
+            "decimals": null,
+            "meta": {"1": "one", "2": null}
+        }"#;
+
+        let params = vec![
+            Param::new("name", ParamType::String),
+            Param::new("symbol", ParamType::Optional(Box::new(ParamType::String))),
+            Param::new("decimals", ParamType::Optional(Box::new(ParamType::Uint(8)))),
+            Param::new("meta", ParamType::Map(
+                Box::new(ParamType::Uint(8)),
+                Box::new(ParamType::Optional(Box::new(ParamType::String))))),
+        ];
+
+        let mut meta = HashMap::new();
+        meta.insert("1".to_owned(), TokenValue::Optional(
+            ParamType::String, Some(Box::new(TokenValue::String("one".to_owned())))));
+        meta.insert("2".to_owned(), TokenValue::Optional(ParamType::String, None));
+
+        let expected_tokens = vec![
+            Token::new("name", TokenValue::String("Крипто token".to_owned())),
+            Token::new("symbol", TokenValue::Optional(
+                ParamType::String, Some(Box::new(TokenValue::String("TON".to_owned()))))),
+            Token::new("decimals", TokenValue::Optional(ParamType::Uint(8), None)),
+            Token::new("meta", TokenValue::Map(ParamType::Uint(8), meta)),
+        ];
+
+        assert_eq!(
+            Tokenizer::tokenize_all(&params, &serde_json::from_str(input).unwrap()).unwrap(),
+            expected_tokens
+        );
+
+        // check that detokenizer gives the same result
+        let input = Detokenizer::detokenize(&params, &expected_tokens).unwrap();
+        assert_eq!(
+            input,
+            r#"{"name":"Крипто token","symbol":"TON","decimals":null,"meta":{"1":"one","2":null}}"#
+        );
+        assert_eq!(
+            Tokenizer::tokenize_all(&params, &serde_json::from_str(&input).unwrap()).unwrap(),
+            expected_tokens
+        );
+
+        // check cells encoding
+        let value = TokenValue::Tuple(expected_tokens.clone());
+        let cell = value.pack_into_chain(AbiLayout::V1Greedy).unwrap();
+        let (decoded, _) = TokenValue::read_from(
+            &ParamType::Tuple(params.clone()), cell.into(), AbiLayout::V1Greedy).unwrap();
+        assert_eq!(decoded, value);
+
+        assert!(Tokenizer::tokenize_all(&params, &serde_json::json!({
+            "name": 1, "symbol": null, "decimals": null, "meta": {}
+        })).is_err());
+        assert!(Tokenizer::tokenize_all(&params, &serde_json::json!({
+            "name": "", "symbol": null, "decimals": 256, "meta": {}
+        })).is_err());
+    }
+
+    #[test]
+    fn test_tokenize_errors() {
+        use crate::error::AbiErrorKind;
+
+        let item = ParamType::Tuple(vec![
+            Param::new("dest", ParamType::Address),
+            Param::new("amount", ParamType::Uint(8)),
+        ]);
+        let params = vec![
+            Param::new("recipients", ParamType::Array(Box::new(item))),
+            Param::new("flags", ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::Int(8)))),
+        ];
+        let recipient = serde_json::json!({"dest": "0:0000000000000000000000000000000000000000000000000000000000000000", "amount": 1});
+
+        let check_error = |input: serde_json::Value, path: &str, param_type: ParamType, reason: &str| {
+            let error = Tokenizer::tokenize_all_with_path(&params, &input, "input").unwrap_err();
+            match error.kind() {
+                AbiErrorKind::InvalidParameter(error_path, expected, _, error_reason) => {
+                    assert_eq!(error_path, path);
+                    assert_eq!(expected, &param_type);
+                    assert_eq!(error_reason, reason);
+                }
+                kind => panic!("unexpected error: {}", kind),
+            }
+        };
+
+        let mut bad_recipient = recipient.clone();
+        bad_recipient["amount"] = serde_json::json!(300);
+        check_error(
+            serde_json::json!({"recipients": [recipient, recipient, recipient, bad_recipient], "flags": {}}),
+            "input.recipients[3].amount",
+            ParamType::Uint(8),
+            "value 300 does not fit uint8");
+
+        bad_recipient["amount"] = serde_json::json!(-1);
+        check_error(
+            serde_json::json!({"recipients": [bad_recipient], "flags": {}}),
+            "input.recipients[0].amount",
+            ParamType::Uint(8),
+            "negative value -1 can not be unsigned");
+
+        check_error(
+            serde_json::json!({"recipients": [recipient], "flags": {"1": 128}}),
+            "input.flags[1]",
+            ParamType::Int(8),
+            "value 128 does not fit int8");
+
+        check_error(
+            serde_json::json!({"recipients": [recipient], "flags": {"256": 1}}),
+            "input.flags[256]",
+            ParamType::Uint(8),
+            "value 256 does not fit uint8");
+
+        check_error(
+            serde_json::json!({"recipients": [{"dest": true, "amount": 1}], "flags": {}}),
+            "input.recipients[0].dest",
+            ParamType::Address,
+            "string expected");
+
+        check_error(
+            serde_json::json!({"recipients": [{"dest": recipient["dest"]}], "flags": {}}),
+            "input.recipients[0].amount",
+            ParamType::Uint(8),
+            "parameter is missing");
+
+        let error = Tokenizer::tokenize_all(&params, &serde_json::json!({"recipients": 1, "flags": {}}))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid parameter `recipients`: array expected. Expected type: (address,uint8)[], received: 1");
+
+        let error = Tokenizer::tokenize_all(&params, &serde_json::json!({"recipients": [], "flags": {}, "other": 1}))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Invalid data: Unknown parameter `other`");
+    }
 }
 
 mod types_check_tests {
@@ -777,4 +970,777 @@ mod types_check_tests {
         };
         assert_not_type_check(&tokens_wrong_tuple_type, &params);
     }
+
+    #[test]
+    fn test_check_types() {
+        use crate::error::AbiErrorKind;
+
+        let params = vec![
+            Param::new("a", ParamType::Bool),
+            Param::new("b", ParamType::Array(Box::new(ParamType::Tuple(vec![
+                Param::new("c", ParamType::Uint(8)),
+            ])))),
+        ];
+        let item = |value: TokenValue| TokenValue::Tuple(vec![Token::new("c", value)]);
+
+        let tokens = vec![
+            Token::new("a", TokenValue::Bool(true)),
+            Token::new("b", TokenValue::Array(vec![
+                item(TokenValue::Uint(Uint::new(1, 8))),
+                item(TokenValue::Uint(Uint::new(2, 8))),
+            ])),
+        ];
+        Token::check_types(&tokens, &params).unwrap();
+
+        let tokens = vec![
+            Token::new("a", TokenValue::Bool(true)),
+            Token::new("b", TokenValue::Array(vec![
+                item(TokenValue::Uint(Uint::new(1, 8))),
+                item(TokenValue::Int(Int::new(2, 8))),
+            ])),
+        ];
+        match Token::check_types(&tokens, &params).unwrap_err().kind() {
+            AbiErrorKind::ParameterTypeMismatch(path, expected, provided) => {
+                assert_eq!(path, "b[1].c");
+                assert_eq!(expected, &ParamType::Uint(8));
+                assert_eq!(provided, &ParamType::Int(8));
+            }
+            kind => panic!("unexpected error: {}", kind),
+        }
+    }
+}
+
+mod serde_tokens_tests {
+    use crate::{Param, ParamType, Token, TokenValue, Uint};
+    use std::collections::{BTreeMap, HashMap};
+    use std::str::FromStr;
+    use token::{from_tokens, serde_address, to_tokens};
+    use ton_block::{MsgAddress, MsgAddressInt};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Transfer {
+        value: u64,
+        payload: Vec<u8>,
+        #[serde(with = "serde_address")]
+        dest: MsgAddressInt,
+        limits: BTreeMap<u32, u64>,
+        flags: Vec<bool>,
+        info: Info,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Info {
+        id: u8,
+        delta: i32,
+    }
+
+    fn params() -> Vec<Param> {
+        vec![
+            Param::new("value", ParamType::Uint(64)),
+            Param::new("payload", ParamType::Bytes),
+            Param::new("dest", ParamType::Address),
+            Param::new("limits", ParamType::Map(Box::new(ParamType::Uint(32)), Box::new(ParamType::Uint(64)))),
+            Param::new("flags", ParamType::Array(Box::new(ParamType::Bool))),
+            Param::new("info", ParamType::Tuple(vec![
+                Param::new("id", ParamType::Uint(8)),
+                Param::new("delta", ParamType::Int(16)),
+            ])),
+        ]
+    }
+
+    #[test]
+    fn test_serde_tokens() {
+        let address = "0:1111111111111111111111111111111111111111111111111111111111111111";
+        let mut limits = BTreeMap::new();
+        limits.insert(10, 1000);
+        limits.insert(2, 20);
+
+        let transfer = Transfer {
+            value: 0xFFFF_FFFF_FFFF_FFFF,
+            payload: vec![1, 2, 3],
+            dest: MsgAddressInt::from_str(address).unwrap(),
+            limits,
+            flags: vec![true, false],
+            info: Info { id: 7, delta: -300 },
+        };
+
+        let tokens = to_tokens(&transfer, &params()).unwrap();
+
+        let mut expected_map = HashMap::new();
+        expected_map.insert("2".to_owned(), TokenValue::Uint(Uint::new(20, 64)));
+        expected_map.insert("10".to_owned(), TokenValue::Uint(Uint::new(1000, 64)));
+
+        assert_eq!(tokens[0], Token::new("value", TokenValue::Uint(Uint::new(0xFFFF_FFFF_FFFF_FFFF, 64))));
+        assert_eq!(tokens[1], Token::new("payload", TokenValue::Bytes(vec![1, 2, 3])));
+        assert_eq!(tokens[2], Token::new("dest", TokenValue::Address(MsgAddress::from_str(address).unwrap())));
+        assert_eq!(tokens[3], Token::new("limits", TokenValue::Map(ParamType::Uint(32), expected_map)));
+        assert_eq!(tokens[4], Token::new("flags", TokenValue::Array(vec![TokenValue::Bool(true), TokenValue::Bool(false)])));
+        assert!(Token::types_check(&tokens, &params()));
+
+        let decoded: Transfer = from_tokens(&tokens).unwrap();
+        assert_eq!(decoded, transfer);
+    }
+
+    #[test]
+    fn test_serde_string_and_optional() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct TokenInfo {
+            name: String,
+            symbol: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            decimals: Option<u8>,
+        }
+
+        let params = vec![
+            Param::new("name", ParamType::String),
+            Param::new("symbol", ParamType::Optional(Box::new(ParamType::String))),
+            Param::new("decimals", ParamType::Optional(Box::new(ParamType::Uint(8)))),
+        ];
+
+        let info = TokenInfo { name: "Crystal".to_owned(), symbol: Some("TON".to_owned()), decimals: None };
+        let tokens = to_tokens(&info, &params).unwrap();
+
+        assert_eq!(tokens[0].value, TokenValue::String("Crystal".to_owned()));
+        assert_eq!(tokens[1].value, TokenValue::Optional(
+            ParamType::String, Some(Box::new(TokenValue::String("TON".to_owned())))));
+        assert_eq!(tokens[2].value, TokenValue::Optional(ParamType::Uint(8), None));
+        assert!(Token::types_check(&tokens, &params));
+
+        let decoded: TokenInfo = from_tokens(&tokens).unwrap();
+        assert_eq!(decoded, info);
+    }
+
+    #[test]
+    fn test_serde_tokens_errors() {
+        #[derive(Serialize)]
+        struct Small {
+            value: u16,
+        }
+        #[derive(Serialize)]
+        struct Unknown {
+            other: u8,
+        }
+
+        let params = vec![Param::new("value", ParamType::Uint(8))];
+
+        assert!(to_tokens(&Small { value: 255 }, &params).is_ok());
+        assert!(to_tokens(&Small { value: 256 }, &params).is_err());
+        assert!(to_tokens(&Unknown { other: 1 }, &params).is_err());
+    }
+}
+
+mod lazy_tests {
+    use crate::{AbiLayout, Contract, Function, Int, IntFormat, ParamType, Token, TokenValue, Uint};
+    use crate::error::AbiResult;
+    use json_abi::decode_function_response_paginated;
+    use std::collections::HashMap;
+    use token::{LazyValue, Pagination};
+    use ton_types::{BuilderData, IBitstring, SliceData};
+    use ton_types::dictionary::{HashmapE, HashmapType};
+    use ton_block::Serializable;
+
+    const REGISTRY_ABI: &str = r#"{
+        "ABI version": 1,
+        "functions": [{
+            "name": "getRegistry",
+            "inputs": [],
+            "outputs": [
+                {"name": "items", "type": "uint32[]"},
+                {"name": "owners", "type": "map(uint32,uint64)"},
+                {"name": "done", "type": "bool"}
+            ]
+        }],
+        "events": [],
+        "data": []
+    }"#;
+
+    fn registry_output(function: &Function, count: u32) -> (SliceData, Vec<Token>) {
+        let items = (0..count)
+            .map(|i| TokenValue::Uint(Uint::new(i as u128 * 10, 32)))
+            .collect();
+        let owners = (0..count)
+            .map(|i| (i.to_string(), TokenValue::Uint(Uint::new(i as u128 + 1000, 64))))
+            .collect::<HashMap<_, _>>();
+        let tokens = vec![
+            Token::new("items", TokenValue::Array(items)),
+            Token::new("owners", TokenValue::Map(ParamType::Uint(32), owners)),
+            Token::new("done", TokenValue::Bool(true)),
+        ];
+
+        let mut builder = BuilderData::new();
+        builder.append_u32(function.get_output_id()).unwrap();
+        let builder = TokenValue::pack_values_into_chain(&tokens, vec![builder], function.layout).unwrap();
+
+        (builder.into(), tokens)
+    }
+
+    #[test]
+    fn test_lazy_decoding() {
+        let contract = Contract::load(REGISTRY_ABI.as_bytes()).unwrap();
+        let function = contract.function("getRegistry").unwrap();
+        let (body, tokens) = registry_output(function, 100);
+
+        let lazy = function.decode_output_lazy(body.clone()).unwrap();
+        assert_eq!(lazy.len(), 3);
+
+        match &lazy[0].value {
+            LazyValue::Array(array) => {
+                assert_eq!(array.len(), 100);
+                assert_eq!(array.get(42).unwrap(), TokenValue::Uint(Uint::new(420, 32)));
+                assert!(array.get(100).is_err());
+
+                let page = array.range(95, 10).collect::<AbiResult<Vec<_>>>().unwrap();
+                assert_eq!(page.len(), 5);
+                assert_eq!(page[0], TokenValue::Uint(Uint::new(950, 32)));
+                assert_eq!(array.range(200, 10).count(), 0);
+            }
+            _ => panic!("array expected"),
+        }
+
+        match &lazy[1].value {
+            LazyValue::Map(map) => {
+                assert_eq!(map.get("7").unwrap(), Some(TokenValue::Uint(Uint::new(1007, 64))));
+                assert_eq!(map.get("100").unwrap(), None);
+
+                let page = map.range(10, 3).unwrap().collect::<AbiResult<Vec<_>>>().unwrap();
+                assert_eq!(page, vec![
+                    ("10".to_owned(), TokenValue::Uint(Uint::new(1010, 64))),
+                    ("11".to_owned(), TokenValue::Uint(Uint::new(1011, 64))),
+                    ("12".to_owned(), TokenValue::Uint(Uint::new(1012, 64))),
+                ]);
+                assert_eq!(map.iter().unwrap().count(), 100);
+            }
+            _ => panic!("map expected"),
+        }
+
+        // complete decoding gives the same result as eager decoding
+        let decoded: Vec<Token> = lazy
+            .into_iter()
+            .map(|token| Token::new(&token.name, token.value.into_token_value().unwrap()))
+            .collect();
+        assert_eq!(decoded, tokens);
+        assert_eq!(decoded, function.decode_output(body, false).unwrap());
+    }
+
+    #[test]
+    fn test_paginated_json_decoding() {
+        let contract = Contract::load(REGISTRY_ABI.as_bytes()).unwrap();
+        let (body, _) = registry_output(contract.function("getRegistry").unwrap(), 20);
+
+        let output = decode_function_response_paginated(
+            REGISTRY_ABI.to_owned(),
+            "getRegistry".to_owned(),
+            body,
+            Some(Pagination { offset: 18, limit: 5 }),
+            IntFormat::Number.into(),
+        ).unwrap();
+
+        assert_eq!(
+            output,
+            r#"{"items":[180,190],"owners":{"18":1018,"19":1019},"done":true}"#);
+    }
+
+    #[test]
+    fn test_lazy_map_matches_eager() {
+        let map_type = ParamType::Map(Box::new(ParamType::Int(16)), Box::new(ParamType::Uint(64)));
+        let keys = [-300i16, -1, 0, 1, 7, 255, 20000];
+
+        // values have trailing bits which are ignored by map decoding
+        let mut hashmap = HashmapE::with_bit_len(16);
+        for key in keys.iter() {
+            let mut value = BuilderData::new();
+            value.append_u64((*key as i64 + 1000) as u64).unwrap();
+            value.append_u8(0xff).unwrap();
+            hashmap.set(
+                TokenValue::Int(Int::new(*key as i128, 16)).write_map_key().unwrap().into(),
+                &value.into(),
+            ).unwrap();
+        }
+        let mut builder = BuilderData::new();
+        hashmap.write_to(&mut builder).unwrap();
+        let cursor: SliceData = builder.into();
+
+        let (eager, _) = TokenValue::read_from(&map_type, cursor.clone(), AbiLayout::V1Greedy).unwrap();
+        let (lazy, _) = TokenValue::read_lazy(&map_type, cursor, AbiLayout::V1Greedy).unwrap();
+
+        let map = match &lazy {
+            LazyValue::Map(map) => map,
+            _ => panic!("map expected"),
+        };
+        let entries = map.iter().unwrap().collect::<AbiResult<Vec<_>>>().unwrap();
+        assert_eq!(entries.len(), keys.len());
+        assert_eq!(map.get("-300").unwrap(), Some(TokenValue::Uint(Uint::new(700, 64))));
+        assert_eq!(
+            map.range(keys.len() - 1, 10).unwrap().collect::<AbiResult<Vec<_>>>().unwrap(),
+            entries[keys.len() - 1..].to_vec());
+        assert_eq!(map.range(keys.len(), 10).unwrap().count(), 0);
+        assert_eq!(map.range(0, 0).unwrap().count(), 0);
+
+        assert_eq!(lazy.into_token_value().unwrap(), eager);
+    }
+}
+
+mod layout_tests {
+    use crate::{AbiLayout, Int, Param, ParamType, Token, TokenValue, Uint};
+    use std::collections::HashMap;
+    use ton_block::MsgAddress;
+    use ton_types::{BuilderData, Cell, SliceData};
+    use ton_vm::types::AccountId;
+
+    fn cell(byte: u8) -> Cell {
+        BuilderData::with_raw(vec![byte; 8], 64).unwrap().into()
+    }
+
+    fn check_round_trip(params: Vec<Param>, tokens: Vec<Token>) {
+        let param_type = ParamType::Tuple(params);
+        let value = TokenValue::Tuple(tokens);
+        for (_, layout) in AbiLayout::VERSIONS.iter() {
+            let builder = value.pack_into_chain(*layout).unwrap();
+            let (decoded, cursor) = TokenValue::read_from(&param_type, builder.into(), *layout).unwrap();
+            assert_eq!(decoded, value, "{:?}", layout);
+            assert_eq!(cursor.remaining_bits(), 0);
+            assert_eq!(cursor.remaining_references(), 0);
+        }
+    }
+
+    #[test]
+    fn test_last_reference_usage() {
+        let param_type = ParamType::Tuple((0..4)
+            .map(|i| Param::new(&format!("c{}", i), ParamType::Cell))
+            .collect());
+        let value = TokenValue::Tuple((0..4)
+            .map(|i| Token::new(&format!("c{}", i), TokenValue::Cell(cell(i as u8))))
+            .collect());
+
+        // V1 reserves the last reference for the chain continuation
+        let v1 = value.pack_into_chain(AbiLayout::V1Greedy).unwrap();
+        assert_eq!(v1.references_used(), 4);
+        let chain = SliceData::from(SliceData::from(v1.clone()).reference(3).unwrap());
+        assert_eq!(chain.remaining_references(), 1);
+        assert_eq!(chain.reference(0).unwrap(), cell(3));
+
+        assert_eq!(TokenValue::read_from(&param_type, v1.into(), AbiLayout::V1Greedy).unwrap().0, value);
+
+        // the cell in the last reference is always taken for the chain continuation
+        let body = BuilderData::with_raw_and_refs(vec![], 0, (0..4).map(cell).collect()).unwrap();
+        assert!(TokenValue::read_from(&param_type, body.into(), AbiLayout::V1Greedy).is_err());
+    }
+
+    #[test]
+    fn test_deep_tuples() {
+        let params = vec![
+            Param::new("owner", ParamType::Address),
+            Param::new("info", ParamType::Tuple(vec![
+                Param::new("name", ParamType::String),
+                Param::new("code", ParamType::Cell),
+                Param::new("inner", ParamType::Tuple(vec![
+                    Param::new("data", ParamType::Bytes),
+                    Param::new("balance", ParamType::Uint(256)),
+                    Param::new("deep", ParamType::Tuple(vec![
+                        Param::new("flag", ParamType::Bool),
+                        Param::new("note", ParamType::Optional(Box::new(ParamType::String))),
+                        Param::new("salt", ParamType::Cell),
+                    ])),
+                ])),
+            ])),
+            Param::new("delta", ParamType::Int(256)),
+            Param::new("payload", ParamType::Cell),
+            Param::new("limit", ParamType::Uint(256)),
+        ];
+        let address = MsgAddress::with_standart(None, 0, AccountId::from([0x33; 32])).unwrap();
+        let tokens = vec![
+            Token::new("owner", TokenValue::Address(address)),
+            Token::new("info", TokenValue::Tuple(vec![
+                Token::new("name", TokenValue::String("deep tuple".to_owned())),
+                Token::new("code", TokenValue::Cell(cell(1))),
+                Token::new("inner", TokenValue::Tuple(vec![
+                    Token::new("data", TokenValue::Bytes(vec![0x55; 200])),
+                    Token::new("balance", TokenValue::Uint(Uint::new(u128::max_value(), 256))),
+                    Token::new("deep", TokenValue::Tuple(vec![
+                        Token::new("flag", TokenValue::Bool(true)),
+                        Token::new("note", TokenValue::Optional(
+                            ParamType::String, Some(Box::new(TokenValue::String("note".to_owned()))))),
+                        Token::new("salt", TokenValue::Cell(cell(2))),
+                    ])),
+                ])),
+            ])),
+            Token::new("delta", TokenValue::Int(Int::new(-1, 256))),
+            Token::new("payload", TokenValue::Cell(cell(3))),
+            Token::new("limit", TokenValue::Uint(Uint::new(42, 256))),
+        ];
+
+        check_round_trip(params, tokens);
+    }
+
+    #[test]
+    fn test_array_of_tuples() {
+        let item = vec![
+            Param::new("id", ParamType::Uint(32)),
+            Param::new("payload", ParamType::Cell),
+            Param::new("value", ParamType::Int(128)),
+            Param::new("comment", ParamType::Bytes),
+            Param::new("hash", ParamType::Uint(256)),
+            Param::new("proof", ParamType::Cell),
+            Param::new("code", ParamType::Cell),
+        ];
+        let params = vec![
+            Param::new("items", ParamType::Array(Box::new(ParamType::Tuple(item.clone())))),
+            Param::new("pair", ParamType::FixedArray(Box::new(ParamType::Tuple(item)), 2)),
+        ];
+        let item = |i: u8| TokenValue::Tuple(vec![
+            Token::new("id", TokenValue::Uint(Uint::new(i as u128, 32))),
+            Token::new("payload", TokenValue::Cell(cell(i))),
+            Token::new("value", TokenValue::Int(Int::new(-(i as i128), 128))),
+            Token::new("comment", TokenValue::Bytes(vec![i; 10])),
+            Token::new("hash", TokenValue::Uint(Uint::new(i as u128, 256))),
+            Token::new("proof", TokenValue::Cell(cell(i + 1))),
+            Token::new("code", TokenValue::Cell(cell(i + 2))),
+        ]);
+        let tokens = vec![
+            Token::new("items", TokenValue::Array((0..5).map(item).collect())),
+            Token::new("pair", TokenValue::FixedArray(vec![item(10), item(20)])),
+        ];
+
+        check_round_trip(params, tokens);
+    }
+
+    #[test]
+    fn test_map_inside_tuple() {
+        let value_type = ParamType::Tuple(vec![
+            Param::new("amount", ParamType::Uint(128)),
+            Param::new("data", ParamType::Cell),
+            Param::new("code", ParamType::Cell),
+            Param::new("extra", ParamType::Cell),
+        ]);
+        let params = vec![
+            Param::new("state", ParamType::Tuple(vec![
+                Param::new("balances", ParamType::Map(Box::new(ParamType::Uint(32)), Box::new(value_type))),
+                Param::new("flags", ParamType::Map(Box::new(ParamType::Uint(8)), Box::new(ParamType::Bool))),
+                Param::new("limit", ParamType::Uint(256)),
+                Param::new("extra", ParamType::Cell),
+                Param::new("owner", ParamType::Cell),
+            ])),
+            Param::new("code", ParamType::Cell),
+        ];
+
+        let mut balances = HashMap::new();
+        for i in 0..10u8 {
+            balances.insert(i.to_string(), TokenValue::Tuple(vec![
+                Token::new("amount", TokenValue::Uint(Uint::new(i as u128 * 1000, 128))),
+                Token::new("data", TokenValue::Cell(cell(i))),
+                Token::new("code", TokenValue::Cell(cell(i + 1))),
+                Token::new("extra", TokenValue::Cell(cell(i + 2))),
+            ]));
+        }
+        let mut flags = HashMap::new();
+        flags.insert("1".to_owned(), TokenValue::Bool(true));
+        flags.insert("2".to_owned(), TokenValue::Bool(false));
+
+        let tokens = vec![
+            Token::new("state", TokenValue::Tuple(vec![
+                Token::new("balances", TokenValue::Map(ParamType::Uint(32), balances)),
+                Token::new("flags", TokenValue::Map(ParamType::Uint(8), flags)),
+                Token::new("limit", TokenValue::Uint(Uint::new(1, 256))),
+                Token::new("extra", TokenValue::Cell(cell(100))),
+                Token::new("owner", TokenValue::Cell(cell(101))),
+            ])),
+            Token::new("code", TokenValue::Cell(cell(102))),
+        ];
+
+        check_round_trip(params, tokens);
+    }
+}
+
+mod int_format_tests {
+    use crate::{Int, IntFormat, Param, ParamType, Token, TokenValue, Uint};
+    use num_bigint::{BigInt, BigUint};
+    use token::{Detokenizer, Tokenizer, MAX_SAFE_INTEGER};
+
+    const MAX_UINT256: &str = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    const MIN_INT256: &str = "-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+
+    #[test]
+    fn test_parse_big_ints() {
+        let max_uint256 = BigUint::parse_bytes(MAX_UINT256.as_bytes(), 10).unwrap();
+        assert_eq!(Uint::parse(MAX_UINT256, 256).unwrap(), Uint::from_big_uint(max_uint256.clone(), 256));
+        assert_eq!(Uint::parse(&format!("0x{}", "f".repeat(64)), 256).unwrap().number, max_uint256);
+        assert_eq!(Uint::parse("0XFF", 8).unwrap(), Uint::new(255, 8));
+        assert!(Uint::parse("0x1", 256).is_ok());
+        assert!(Uint::parse(&format!("0x1{}", "0".repeat(64)), 256).is_err());
+        assert!(Uint::parse("256", 8).is_err());
+        assert!(Uint::parse("-1", 8).is_err());
+        assert!(Uint::parse("0x", 8).is_err());
+        assert!(Uint::parse("+1", 8).is_err());
+        assert!(Uint::parse(" 1", 8).is_err());
+
+        let min_int256 = BigInt::parse_bytes(MIN_INT256.as_bytes(), 10).unwrap();
+        assert_eq!(Int::parse(MIN_INT256, 256).unwrap(), Int::from_big_int(min_int256.clone(), 256));
+        assert_eq!(Int::parse(&format!("-0x8{}", "0".repeat(63)), 256).unwrap().number, min_int256);
+        assert!(Int::parse(&format!("0x8{}", "0".repeat(63)), 256).is_err());
+        assert_eq!(Int::parse("-0x80", 8).unwrap(), Int::new(-128, 8));
+        assert!(Int::parse("0x80", 8).is_err());
+        assert!(Int::parse("0x-1", 8).is_err());
+        assert!(Int::parse("--1", 8).is_err());
+    }
+
+    #[test]
+    fn test_tokenize_big_ints() {
+        let params = vec![
+            Param::new("max", ParamType::Uint(256)),
+            Param::new("min", ParamType::Int(256)),
+            Param::new("big", ParamType::Uint(64)),
+            Param::new("amounts", ParamType::Array(Box::new(ParamType::Uint(256)))),
+        ];
+        let input = serde_json::json!({
+            "max": format!("0x{}", "F".repeat(64)),
+            "min": MIN_INT256,
+            "big": u64::max_value(),
+            "amounts": [MAX_UINT256, "0x0", 1],
+        });
+
+        let tokens = Tokenizer::tokenize_all(&params, &input).unwrap();
+        let max_uint256 = Uint::parse(MAX_UINT256, 256).unwrap();
+        assert_eq!(tokens, vec![
+            Token::new("max", TokenValue::Uint(max_uint256.clone())),
+            Token::new("min", TokenValue::Int(Int::parse(MIN_INT256, 256).unwrap())),
+            Token::new("big", TokenValue::Uint(Uint::new(u64::max_value() as u128, 64))),
+            Token::new("amounts", TokenValue::Array(vec![
+                TokenValue::Uint(max_uint256),
+                TokenValue::Uint(Uint::new(0, 256)),
+                TokenValue::Uint(Uint::new(1, 256)),
+            ])),
+        ]);
+
+        // JSON numbers can not hold values beyond 64 bits
+        let error = Tokenizer::tokenize_parameter(&ParamType::Uint(256), &serde_json::json!(1e30))
+            .unwrap_err().to_string();
+        assert!(error.contains("use string for big numbers"), "{}", error);
+        let error = Tokenizer::tokenize_parameter(&ParamType::Uint(256), &serde_json::json!("-0x1"))
+            .unwrap_err().to_string();
+        assert!(error.contains("negative value -0x1 can not be unsigned"), "{}", error);
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Int(256), &serde_json::json!(MAX_UINT256)).is_err());
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Int(64), &serde_json::json!(u64::max_value())).is_err());
+    }
+
+    #[test]
+    fn test_detokenize_int_formats() {
+        let params = vec![
+            Param::new("max", ParamType::Uint(256)),
+            Param::new("delta", ParamType::Int(64)),
+            Param::new("big", ParamType::Uint(64)),
+            Param::new("nested", ParamType::Tuple(vec![
+                Param::new("values", ParamType::Array(Box::new(ParamType::Int(128)))),
+                Param::new("fee", ParamType::Gram),
+                Param::new("limit", ParamType::Optional(Box::new(ParamType::Uint(32)))),
+            ])),
+            Param::new("balances", ParamType::Map(Box::new(ParamType::Uint(32)), Box::new(ParamType::Uint(256)))),
+        ];
+        let input = serde_json::json!({
+            "max": MAX_UINT256,
+            "delta": -255,
+            "big": u64::max_value(),
+            "nested": { "values": [-1, "170141183460469231731687303715884105727"], "fee": 1000, "limit": 16 },
+            "balances": { "10": MAX_UINT256 },
+        });
+        let tokens = Tokenizer::tokenize_all(&params, &input).unwrap();
+
+        let detokenize = |format: IntFormat| {
+            let output = Detokenizer::detokenize_with_format(&params, &tokens, format.into()).unwrap();
+            serde_json::from_str::<serde_json::Value>(&output).unwrap()
+        };
+
+        let max_hex = format!("0x{}", "f".repeat(64));
+        assert_eq!(detokenize(IntFormat::Hex), serde_json::json!({
+            "max": max_hex,
+            "delta": "-0xff",
+            "big": "0xffffffffffffffff",
+            "nested": { "values": ["-0x1", "0x7fffffffffffffffffffffffffffffff"], "fee": "0x3e8", "limit": "0x10" },
+            "balances": { "10": max_hex },
+        }));
+        assert_eq!(
+            detokenize(IntFormat::Hex),
+            serde_json::from_str::<serde_json::Value>(&Detokenizer::detokenize(&params, &tokens).unwrap()).unwrap());
+
+        assert_eq!(detokenize(IntFormat::Decimal), serde_json::json!({
+            "max": MAX_UINT256,
+            "delta": "-255",
+            "big": "18446744073709551615",
+            "nested": { "values": ["-1", "170141183460469231731687303715884105727"], "fee": "1000", "limit": "16" },
+            "balances": { "10": MAX_UINT256 },
+        }));
+
+        assert_eq!(detokenize(IntFormat::Number), serde_json::json!({
+            "max": MAX_UINT256,
+            "delta": -255,
+            "big": "18446744073709551615",
+            "nested": { "values": [-1, "170141183460469231731687303715884105727"], "fee": 1000, "limit": 16 },
+            "balances": { "10": MAX_UINT256 },
+        }));
+
+        // all formats are accepted back by tokenizer
+        for format in [IntFormat::Hex, IntFormat::Decimal, IntFormat::Number].iter() {
+            assert_eq!(Tokenizer::tokenize_all(&params, &detokenize(*format)).unwrap(), tokens);
+        }
+    }
+
+    #[test]
+    fn test_number_format_bounds() {
+        let detokenize = |value: i128| {
+            let params = vec![Param::new("value", ParamType::Int(128))];
+            let tokens = vec![Token::new("value", TokenValue::Int(Int::new(value, 128)))];
+            let output = Detokenizer::detokenize_with_format(&params, &tokens, IntFormat::Number.into()).unwrap();
+            serde_json::from_str::<serde_json::Value>(&output).unwrap()["value"].clone()
+        };
+
+        let max = MAX_SAFE_INTEGER as i128;
+        assert_eq!(detokenize(max), serde_json::json!(9007199254740991i64));
+        assert_eq!(detokenize(-max), serde_json::json!(-9007199254740991i64));
+        assert_eq!(detokenize(max + 1), serde_json::json!("9007199254740992"));
+        assert_eq!(detokenize(-max - 1), serde_json::json!("-9007199254740992"));
+        assert_eq!(detokenize(i64::min_value() as i128), serde_json::json!("-9223372036854775808"));
+    }
+}
+
+mod value_format_tests {
+    use crate::{AddressFormat, BytesFormat, CellFormat, Param, ParamType, Token, TokenValue, ValueFormat};
+    use token::{Detokenizer, Tokenizer};
+    use ton_block::MsgAddress;
+    use ton_types::{BuilderData, Cell, IBitstring};
+
+    const RAW_ADDRESS: &str = "-1:fcb91a3a3816d0f7b8c2c76108b8a9bc5a6b7a55bd79f8ab101c52db29232260";
+    const BASE64_ADDRESS: &str = "kf/8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYIny";
+    const URL_ADDRESS: &str = "kf_8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15-KsQHFLbKSMiYIny";
+
+    fn address() -> MsgAddress {
+        MsgAddress::with_standart(None, -1, hex::decode(&RAW_ADDRESS[3..]).unwrap().into()).unwrap()
+    }
+
+    fn cell() -> Cell {
+        let mut child = BuilderData::new();
+        child.append_u8(1).unwrap();
+        let mut builder = BuilderData::with_raw(vec![0xb4], 6).unwrap();
+        builder.append_reference_cell(child.into());
+        builder.into()
+    }
+
+    #[test]
+    fn test_tokenize_address_forms() {
+        for string in &[RAW_ADDRESS, BASE64_ADDRESS, URL_ADDRESS] {
+            let token = Tokenizer::tokenize_parameter(&ParamType::Address, &serde_json::json!(string)).unwrap();
+            assert_eq!(token, TokenValue::Address(address()), "{}", string);
+        }
+        for string in &["", "addr_none"] {
+            let token = Tokenizer::tokenize_parameter(&ParamType::Address, &serde_json::json!(string)).unwrap();
+            assert_eq!(token, TokenValue::Address(MsgAddress::AddrNone));
+        }
+
+        let broken_crc = format!("{}Iz", &BASE64_ADDRESS[..46]);
+        let error = Tokenizer::tokenize_parameter(&ParamType::Address, &serde_json::json!(broken_crc))
+            .unwrap_err().to_string();
+        assert!(error.contains("CRC16 mismatch"), "{}", error);
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Address, &serde_json::json!("-1:xyz")).is_err());
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Address, &serde_json::json!("kf8u")).is_err());
+    }
+
+    #[test]
+    fn test_tokenize_cell_forms() {
+        let tree = serde_json::json!({
+            "bits": "b4",
+            "bitLength": 6,
+            "refs": [{"cellType": "Ordinary", "bits": "01", "bitLength": 8}]
+        });
+        assert_eq!(Tokenizer::tokenize_parameter(&ParamType::Cell, &tree).unwrap(), TokenValue::Cell(cell()));
+
+        let too_short = serde_json::json!({"bits": "b4", "bitLength": 10});
+        let error = Tokenizer::tokenize_parameter(&ParamType::Cell, &too_short).unwrap_err().to_string();
+        assert!(error.contains("invalid cell tree"), "{}", error);
+    }
+
+    #[test]
+    fn test_tokenize_bytes_forms() {
+        let expected = TokenValue::Bytes(b"hello".to_vec());
+        for value in &[
+            serde_json::json!("68656c6c6f"),
+            serde_json::json!({"hex": "68656c6c6f"}),
+            serde_json::json!({"base64": "aGVsbG8="}),
+            serde_json::json!({"utf8": "hello"}),
+        ] {
+            assert_eq!(Tokenizer::tokenize_parameter(&ParamType::Bytes, value).unwrap(), expected, "{}", value);
+        }
+        assert_eq!(
+            Tokenizer::tokenize_parameter(&ParamType::FixedBytes(2), &serde_json::json!({"utf8": "hello"})).unwrap(),
+            TokenValue::FixedBytes(b"he".to_vec()));
+
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Bytes, &serde_json::json!({"utf16": "hello"})).is_err());
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Bytes, &serde_json::json!({"hex": "00", "utf8": "a"})).is_err());
+        assert!(Tokenizer::tokenize_parameter(&ParamType::Bytes, &serde_json::json!({"base64": "!"})).is_err());
+    }
+
+    #[test]
+    fn test_detokenize_value_formats() {
+        let params = vec![
+            Param::new("address", ParamType::Address),
+            Param::new("none", ParamType::Address),
+            Param::new("cell", ParamType::Cell),
+            Param::new("text", ParamType::Bytes),
+            Param::new("binary", ParamType::Bytes),
+        ];
+        let tokens = vec![
+            Token::new("address", TokenValue::Address(address())),
+            Token::new("none", TokenValue::Address(MsgAddress::AddrNone)),
+            Token::new("cell", TokenValue::Cell(cell())),
+            Token::new("text", TokenValue::Bytes(b"hello".to_vec())),
+            Token::new("binary", TokenValue::Bytes(vec![0xff, 0x00])),
+        ];
+
+        let check = |format: ValueFormat, expected: serde_json::Value| {
+            let output = Detokenizer::detokenize_with_format(&params, &tokens, format).unwrap();
+            let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+            for (name, value) in expected.as_object().unwrap() {
+                assert_eq!(&output[name], value, "{:?}", format);
+            }
+            // output is accepted back by tokenizer
+            assert_eq!(Tokenizer::tokenize_all(&params, &output).unwrap(), tokens, "{:?}", format);
+        };
+
+        check(ValueFormat::default(), serde_json::json!({
+            "address": RAW_ADDRESS,
+            "text": "68656c6c6f",
+            "binary": "ff00",
+        }));
+        check(ValueFormat {
+            address_format: AddressFormat::Base64 { bounceable: true, testnet: true, url: false },
+            cell_format: CellFormat::Tree,
+            bytes_format: BytesFormat::Utf8,
+            ..ValueFormat::default()
+        }, serde_json::json!({
+            "address": BASE64_ADDRESS,
+            "cell": {
+                "cellType": "Ordinary",
+                "bits": "b4",
+                "bitLength": 6,
+                "refs": [{"cellType": "Ordinary", "bits": "01", "bitLength": 8, "refs": []}]
+            },
+            "text": {"utf8": "hello"},
+            "binary": {"hex": "ff00"},
+        }));
+        check(ValueFormat {
+            address_format: AddressFormat::Base64 { bounceable: true, testnet: true, url: true },
+            cell_format: CellFormat::Hex,
+            bytes_format: BytesFormat::Base64,
+            ..ValueFormat::default()
+        }, serde_json::json!({
+            "address": URL_ADDRESS,
+            "text": {"base64": "aGVsbG8="},
+        }));
+
+        let format: ValueFormat = serde_json::from_value(serde_json::json!({
+            "intFormat": "decimal",
+            "addressFormat": {"base64": {"url": true}},
+            "cellFormat": "tree",
+        })).unwrap();
+        assert_eq!(format.address_format, AddressFormat::Base64 { bounceable: true, testnet: false, url: true });
+        assert_eq!(format.cell_format, CellFormat::Tree);
+        assert_eq!(format.bytes_format, BytesFormat::Hex);
+    }
 }
@@ -14,10 +14,13 @@
 
 //! ABI param and parsing for it.
 use {ParamType, Param, Uint, Int, Token, TokenValue};
+use super::{field_path, item_path};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::io::Cursor;
-use num_bigint::{Sign, BigInt, BigUint};
+use num_bigint::{BigInt, BigUint};
+use int::{parse_big_int, parse_big_uint};
+use format::{ADDR_NONE, CellTree, decode_user_friendly_address};
 use ton_block::{Grams, MsgAddress};
 use ton_types::BuilderData;
 use ton_types::cells_serialization::deserialize_tree_of_cells;
@@ -25,153 +28,159 @@ use crate::error::*;
 use std::str::FromStr;
 
 /// This struct should be used to parse string values as tokens.
+///
+/// Errors are reported as `AbiErrorKind::InvalidParameter` holding path to the offending
+/// value (like `input.recipients[3].amount`), expected type and received JSON.
 pub struct Tokenizer;
 
 impl Tokenizer {
     /// Tries to parse a JSON value as a token of given type.
     pub fn tokenize_parameter(param: &ParamType, value: &Value) -> AbiResult<TokenValue> {
-        match param {
-            ParamType::Unknown => bail!(AbiErrorKind::NotImplemented),
-            ParamType::Uint(size) => Self::tokenize_uint(*size, value),
-            ParamType::Int(size) => Self::tokenize_int(*size, value),
-            ParamType::Bool => Self::tokenize_bool(value),
-            ParamType::Tuple(tuple_params) => Self::tokenize_tuple(tuple_params, value),
-            ParamType::Array(param_type) => Self::tokenize_array(&param_type, value),
-            ParamType::FixedArray(param_type, size) => Self::tokenize_fixed_array(&param_type, *size, value),
-            ParamType::Cell => Self::tokenize_cell(value),
-            ParamType::Map(key_type, value_type) => Self::tokenize_hashmap(key_type, value_type, value),
-            ParamType::Address => {
-                let address = MsgAddress::from_str(
-                    &value.as_str()
-                        .ok_or(AbiErrorKind::WrongDataFormat(value.clone()))?)
-                    .map_err(|_| AbiErrorKind::WrongDataFormat(value.clone()))?;
-                Ok(TokenValue::Address(address))
-            }
-            ParamType::Bytes => Self::tokenize_bytes(value, None),
-            ParamType::FixedBytes(size) => Self::tokenize_bytes(value, Some(*size)),
-            ParamType::Gram => Self::tokenize_gram(value),
-        }
+        Self::tokenize_value(param, value, "value")
     }
 
     /// Tries to parse parameters from JSON values to tokens.
     pub fn tokenize_all(params: &[Param], values: &Value) -> AbiResult<Vec<Token>> {
-        if let Value::Object(map) = values {
-            if map.len() != params.len() {
-                bail!(AbiErrorKind::WrongParametersCount(params.len(), map.len()))
-            }
+        Self::tokenize_all_with_path(params, values, "")
+    }
 
+    /// Tries to parse parameters from JSON values to tokens. `path` is prepended to parameter
+    /// names in error messages, e.g. `input` or `data`
+    pub fn tokenize_all_with_path(params: &[Param], values: &Value, path: &str) -> AbiResult<Vec<Token>> {
+        if let Value::Object(map) = values {
             let mut tokens = Vec::new();
             for param in params {
-                let token_value = Self::tokenize_parameter(&param.kind, &values[&param.name])?;
+                let param_path = field_path(path, &param.name);
+                let value = map.get(&param.name).ok_or_else(|| Self::error(
+                    &param_path, &param.kind, &Value::Null, "parameter is missing".to_owned()))?;
+                let token_value = Self::tokenize_value(&param.kind, value, &param_path)?;
                 tokens.push(Token { name: param.name.clone(), value: token_value});
             }
 
+            if map.len() != params.len() {
+                match map.keys().find(|key| params.iter().all(|param| &param.name != *key)) {
+                    Some(key) => bail!(AbiErrorKind::InvalidData(
+                        format!("Unknown parameter `{}`", field_path(path, key)))),
+                    None => bail!(AbiErrorKind::WrongParametersCount(params.len(), map.len())),
+                }
+            }
+
             Ok(tokens)
         } else {
-            bail!(AbiErrorKind::WrongDataFormat(values.clone()))
+            Err(Self::error(path, &ParamType::Tuple(params.to_vec()), values, "object expected".to_owned()))
+        }
+    }
+
+    fn error(path: &str, expected: &ParamType, value: &Value, reason: String) -> AbiError {
+        AbiErrorKind::InvalidParameter(path.to_owned(), expected.clone(), value.clone(), reason).into()
+    }
+
+    fn tokenize_value(param: &ParamType, value: &Value, path: &str) -> AbiResult<TokenValue> {
+        match param {
+            ParamType::Unknown => bail!(AbiErrorKind::NotImplemented),
+            ParamType::Uint(size) => Self::tokenize_uint(*size, value, path),
+            ParamType::Int(size) => Self::tokenize_int(*size, value, path),
+            ParamType::Bool => Self::tokenize_bool(value, path),
+            ParamType::Tuple(tuple_params) => Self::tokenize_tuple(tuple_params, value, path),
+            ParamType::Array(param_type) => Self::tokenize_array(&param_type, value, path),
+            ParamType::FixedArray(param_type, size) => Self::tokenize_fixed_array(&param_type, *size, value, path),
+            ParamType::Cell => Self::tokenize_cell(value, path),
+            ParamType::Map(key_type, value_type) => Self::tokenize_hashmap(key_type, value_type, value, path),
+            ParamType::Address => Self::tokenize_address(value, path),
+            ParamType::Bytes => Self::tokenize_bytes(value, None, path),
+            ParamType::FixedBytes(size) => Self::tokenize_bytes(value, Some(*size), path),
+            ParamType::Gram => Self::tokenize_gram(value, path),
+            ParamType::String => Self::tokenize_string(value, path),
+            ParamType::Optional(inner_type) => Self::tokenize_optional(inner_type, value, path),
         }
     }
 
     /// Tries to read tokens array from `Value`
-    fn read_array(param: &ParamType, value: &Value) -> AbiResult<Vec<TokenValue>> {
+    fn read_array(param: &ParamType, value: &Value, path: &str) -> AbiResult<Vec<TokenValue>> {
         if let Value::Array(array) = value {
             let mut tokens = Vec::new();
-            for value in array {
-                tokens.push(Self::tokenize_parameter(param, value)?);
+            for (i, value) in array.iter().enumerate() {
+                tokens.push(Self::tokenize_value(param, value, &item_path(path, i))?);
             }
             
             Ok(tokens)
         } else {
-            bail!(AbiErrorKind::WrongDataFormat(value.clone()))
+            Err(Self::error(path, &ParamType::Array(Box::new(param.clone())), value, "array expected".to_owned()))
         }
     }
 
     /// Tries to parse a value as a vector of tokens of fixed size.
     fn tokenize_fixed_array(
         param: &ParamType,
-        size: usize, value: &Value
+        size: usize, value: &Value,
+        path: &str,
     ) -> AbiResult<TokenValue> {
-        let vec = Self::read_array(param, value)?;
+        let vec = Self::read_array(param, value, path)?;
         match vec.len() == size {
             true => Ok(TokenValue::FixedArray(vec)),
-            false => bail!(AbiErrorKind::InvalidParameterLength(value.clone())),
+            false => Err(Self::error(
+                path,
+                &ParamType::FixedArray(Box::new(param.clone()), size),
+                value,
+                format!("array of {} elements expected, received {} elements", size, vec.len()))),
         }
     }
 
     /// Tries to parse a value as a vector of tokens.
-    fn tokenize_array(param: &ParamType, value: &Value) -> AbiResult<TokenValue> {
-        let vec = Self::read_array(param, value)?;
+    fn tokenize_array(param: &ParamType, value: &Value, path: &str) -> AbiResult<TokenValue> {
+        let vec = Self::read_array(param, value, path)?;
 
         Ok(TokenValue::Array(vec))
     }
 
     /// Tries to parse a value as a bool.
-    fn tokenize_bool(value: &Value) -> AbiResult<TokenValue> {
+    fn tokenize_bool(value: &Value, path: &str) -> AbiResult<TokenValue> {
         match value {
             Value::Bool(value) => Ok(TokenValue::Bool(value.to_owned())),
             Value::String(string) => {
                 match string.as_str() {
                     "true" => Ok(TokenValue::Bool(true)),
                     "false" => Ok(TokenValue::Bool(false)),
-                    _ => bail!(AbiErrorKind::InvalidParameterValue(value.clone())),
+                    _ => Err(Self::error(path, &ParamType::Bool, value, "`true` or `false` expected".to_owned())),
                 }
             }
-            _ => bail!(AbiErrorKind::InvalidParameterValue(value.clone())),
+            _ => Err(Self::error(path, &ParamType::Bool, value, "boolean value expected".to_owned())),
         }
     }
 
-    /// Tries to read integer number from `Value`
-    fn read_int(value: &Value) -> AbiResult<BigInt> {
+    /// Tries to read integer number from `Value`. Numbers out of 64-bit range must be passed
+    /// as decimal or `0x` prefixed hex strings. Returns reason of failure on error
+    fn read_int(value: &Value) -> Result<BigInt, String> {
         if let Some(number) = value.as_i64() {
             Ok(BigInt::from(number))
+        } else if let Some(number) = value.as_u64() {
+            Ok(BigInt::from(number))
         } else if let Some(string) = value.as_str() {
-            let result = if string.starts_with("-0x") {
-                BigInt::parse_bytes(&string.as_bytes()[3..], 16)
-                .map(|number| -number)
-            } else if string.starts_with("0x") {
-                BigInt::parse_bytes(&string.as_bytes()[2..], 16)
-            } else {
-                BigInt::parse_bytes(string.as_bytes(), 10)
-            };
-            match result {
-                Some(number) => Ok(number),
-                None => bail!(AbiErrorKind::InvalidParameterValue(value.clone()))
-            }
+            parse_big_int(string).ok_or_else(|| format!("`{}` is not a valid number", string))
+        } else if value.is_number() {
+            Err(format!("{} is not an integer or exceeds 64 bits, use string for big numbers", value))
         } else {
-            bail!(AbiErrorKind::WrongDataFormat(value.clone()))
+            Err("number or numeric string expected".to_owned())
         }
     }
 
-    /// Tries to read integer number from `Value`
-    fn read_uint(value: &Value) -> AbiResult<BigUint> {
+    /// Tries to read integer number from `Value`. Numbers out of 64-bit range must be passed
+    /// as decimal or `0x` prefixed hex strings. Returns reason of failure on error
+    fn read_uint(value: &Value) -> Result<BigUint, String> {
         if let Some(number) = value.as_u64() {
             Ok(BigUint::from(number))
+        } else if let Some(number) = value.as_i64() {
+            Err(format!("negative value {} can not be unsigned", number))
         } else if let Some(string) = value.as_str() {
-            let result = if string.starts_with("0x") {
-                BigUint::parse_bytes(&string.as_bytes()[2..], 16)
-            } else {
-                BigUint::parse_bytes(string.as_bytes(), 10)
-            };
-            match result {
+            match parse_big_uint(string) {
                 Some(number) => Ok(number),
-                None => bail!(AbiErrorKind::InvalidParameterValue(value.clone()))
+                None if parse_big_int(string).is_some() => Err(format!("negative value {} can not be unsigned", string)),
+                None => Err(format!("`{}` is not a valid unsigned number", string)),
             }
+        } else if value.is_number() {
+            Err(format!("{} is not an integer or exceeds 64 bits, use string for big numbers", value))
         } else {
-            bail!(AbiErrorKind::WrongDataFormat(value.clone()))
-        }
-    }
-
-    /// Checks if given number can be fit into given bits count
-    fn check_int_size(number: &BigInt, size: usize) -> bool {
-        // `BigInt::bits` returns fewest bits necessary to express the number, not including
-        // the sign and it works well for all values except `-2^n`. Such values can be encoded 
-        // using `n` bits, but `bits` function returns `n` (and plus one bit for sign) so we 
-        // have to explicitly check such situation by comparing bits sizes of given number 
-        // and increased number
-        if number.sign() == Sign::Minus && number.bits() != (number + BigInt::from(1)).bits() {
-            number.bits() <= size
-        } else {
-            number.bits() < size
+            Err("number or numeric string expected".to_owned())
         }
     }
 
@@ -181,87 +190,186 @@ impl Tokenizer {
     }
 
     /// Tries to parse a value as grams.
-    fn tokenize_gram(value: &Value) -> AbiResult<TokenValue> {
-        let number = Self::read_uint(value)?;
+    fn tokenize_gram(value: &Value, path: &str) -> AbiResult<TokenValue> {
+        let number = Self::read_uint(value)
+            .map_err(|reason| Self::error(path, &ParamType::Gram, value, reason))?;
 
         if !Self::check_uint_size(&number, 120) {
-            bail!(AbiErrorKind::InvalidParameterValue(value.clone()))
+            Err(Self::error(path, &ParamType::Gram, value,
+                format!("value {} does not fit {} (120 bits)", number, ParamType::Gram)))
         } else {
             Ok(TokenValue::Gram(Grams::from(number)))
         }
     }
 
     /// Tries to parse a value as unsigned integer.
-    fn tokenize_uint(size: usize, value: &Value) -> AbiResult<TokenValue> {
-        let number = Self::read_uint(value)?;
+    fn tokenize_uint(size: usize, value: &Value, path: &str) -> AbiResult<TokenValue> {
+        let param_type = ParamType::Uint(size);
+        let number = Self::read_uint(value)
+            .map_err(|reason| Self::error(path, &param_type, value, reason))?;
 
-        if !Self::check_uint_size(&number, size + 1) {
-            bail!(AbiErrorKind::InvalidParameterValue(value.clone()))
+        if !Uint::fits(&number, size) {
+            Err(Self::error(path, &param_type, value,
+                format!("value {} does not fit {}", number, param_type)))
         } else {
             Ok(TokenValue::Uint(Uint{number, size}))
         }
     }
 
     /// Tries to parse a value as signed integer.
-    fn tokenize_int(size: usize, value: &Value) -> AbiResult<TokenValue> {
-        let number = Self::read_int(value)?;
+    fn tokenize_int(size: usize, value: &Value, path: &str) -> AbiResult<TokenValue> {
+        let param_type = ParamType::Int(size);
+        let number = Self::read_int(value)
+            .map_err(|reason| Self::error(path, &param_type, value, reason))?;
 
-        if !Self::check_int_size(&number, size) {
-            bail!(AbiErrorKind::InvalidParameterValue(value.clone()))
+        if !Int::fits(&number, size) {
+            Err(Self::error(path, &param_type, value,
+                format!("value {} does not fit {}", number, param_type)))
         } else {
             Ok(TokenValue::Int(Int{number, size}))
         }
     }
 
-    fn tokenize_cell(value: &Value) -> AbiResult<TokenValue> {
+    /// Tries to parse a value as address in raw or user-friendly form or as `addr_none`
+    fn tokenize_address(value: &Value, path: &str) -> AbiResult<TokenValue> {
         let string = value
             .as_str()
-            .ok_or(AbiErrorKind::WrongDataFormat(value.clone()))?;
+            .ok_or_else(|| Self::error(path, &ParamType::Address, value, "string expected".to_owned()))?;
+
+        if string.is_empty() || string == ADDR_NONE {
+            return Ok(TokenValue::Address(MsgAddress::AddrNone));
+        }
+
+        let address = match MsgAddress::from_str(string) {
+            Ok(address) => address,
+            Err(_) if !string.contains(':') => decode_user_friendly_address(string)
+                .map_err(|reason| Self::error(path, &ParamType::Address, value, reason))?
+                .address,
+            Err(_) => return Err(Self::error(path, &ParamType::Address, value, "invalid address format".to_owned())),
+        };
+        Ok(TokenValue::Address(address))
+    }
+
+    /// Tries to parse a value as cell from base64 or hex bag of cells or from `CellTree` object
+    fn tokenize_cell(value: &Value, path: &str) -> AbiResult<TokenValue> {
+        if value.is_object() {
+            let cell = serde_json::from_value::<CellTree>(value.clone())
+                .map_err(|err| err.to_string())
+                .and_then(|tree| tree.to_cell().map_err(|err| err.to_string()))
+                .map_err(|reason| Self::error(path, &ParamType::Cell, value, format!("invalid cell tree: {}", reason)))?;
+            return Ok(TokenValue::Cell(cell));
+        }
+
+        let string = value
+            .as_str()
+            .ok_or_else(|| Self::error(path, &ParamType::Cell, value, "bag of cells string or cell tree expected".to_owned()))?;
 
         if string.len() == 0 {
             return Ok(TokenValue::Cell(BuilderData::new().into()));
         }
 
-        let data = base64::decode(string)
-            .map_err(|_| AbiErrorKind::InvalidParameterValue(value.clone()))?;
+        // base64 of bag of cells starts with non-hex characters for any bag of cells magic
+        let data = if string.chars().all(|c| c.is_ascii_hexdigit()) {
+            hex::decode(string)
+                .map_err(|err| Self::error(path, &ParamType::Cell, value, format!("invalid hex: {}", err)))?
+        } else {
+            base64::decode(string)
+                .map_err(|err| Self::error(path, &ParamType::Cell, value, format!("invalid base64: {}", err)))?
+        };
         let cell = deserialize_tree_of_cells(&mut Cursor::new(data))
-            .map_err(|_| AbiErrorKind::InvalidParameterValue(value.clone()))?;
+            .map_err(|err| Self::error(path, &ParamType::Cell, value, format!("invalid bag of cells: {}", err)))?;
         Ok(TokenValue::Cell(cell))
     }
 
-    fn tokenize_hashmap(key_type: &ParamType, value_type: &ParamType, map_value: &Value) -> AbiResult<TokenValue> {
+    /// Tries to parse a map key from its JSON string representation. Returned token
+    /// has declared key type
+    pub fn tokenize_map_key(key_type: &ParamType, key: &str) -> AbiResult<TokenValue> {
+        Self::tokenize_key(key_type, key, "key")
+    }
+
+    fn tokenize_key(key_type: &ParamType, key: &str, path: &str) -> AbiResult<TokenValue> {
+        if key_type.bit_len() == 0 {
+            bail!(AbiErrorKind::InvalidData(format!("Type {} can not be a map key", key_type)))
+        }
+        Self::tokenize_value(key_type, &Value::String(key.to_owned()), path)
+    }
+
+    fn tokenize_hashmap(key_type: &ParamType, value_type: &ParamType, map_value: &Value, path: &str) -> AbiResult<TokenValue> {
         if let Value::Object(map) = map_value {
             let mut new_map = HashMap::<String, TokenValue>::new();
             for (key, value) in map.iter() {
-                let value = Self::tokenize_parameter(value_type, value)?;
-                new_map.insert(key.to_string(), value);
+                let value_path = item_path(path, key);
+                let key = Token::detokenize_map_key(&Self::tokenize_key(key_type, key, &value_path)?)?;
+                let value = Self::tokenize_value(value_type, value, &value_path)?;
+                new_map.insert(key, value);
             }
             Ok(TokenValue::Map(key_type.clone(), new_map))
         } else {
-            bail!(AbiErrorKind::WrongDataFormat(map_value.clone()))
+            Err(Self::error(
+                path,
+                &ParamType::Map(Box::new(key_type.clone()), Box::new(value_type.clone())),
+                map_value,
+                "object expected".to_owned()))
         }
     }
 
-    fn tokenize_bytes(value: &Value, size: Option<usize>) -> AbiResult<TokenValue> {
-        let string = value
-            .as_str()
-            .ok_or(AbiErrorKind::WrongDataFormat(value.clone()))?;
-        let mut data = hex::decode(string)
-            .map_err(|_| AbiErrorKind::InvalidParameterValue(value.clone()))?;
+    /// Reads bytes from hex string or from object with single encoding marker field:
+    /// `hex`, `base64` or `utf8`. Returns reason of failure on error
+    fn read_bytes(value: &Value) -> Result<Vec<u8>, String> {
+        let (encoding, string) = match value {
+            Value::String(string) => ("hex", string),
+            Value::Object(map) if map.len() == 1 => match map.iter().next() {
+                Some((encoding, Value::String(string))) => (encoding.as_str(), string),
+                _ => return Err("encoded string expected".to_owned()),
+            },
+            _ => return Err("hex string or object with `hex`, `base64` or `utf8` field expected".to_owned()),
+        };
+
+        match encoding {
+            "hex" => hex::decode(string).map_err(|err| format!("invalid hex: {}", err)),
+            "base64" => base64::decode(string).map_err(|err| format!("invalid base64: {}", err)),
+            "utf8" => Ok(string.as_bytes().to_vec()),
+            _ => Err(format!("unknown bytes encoding `{}`", encoding)),
+        }
+    }
+
+    fn tokenize_bytes(value: &Value, size: Option<usize>, path: &str) -> AbiResult<TokenValue> {
+        let param_type = size.map(ParamType::FixedBytes).unwrap_or(ParamType::Bytes);
+        let mut data = Self::read_bytes(value)
+            .map_err(|reason| Self::error(path, &param_type, value, reason))?;
         match size {
             Some(size) => if data.len() >= size {
                 data.split_off(size);
                 Ok(TokenValue::FixedBytes(data))
             } else {
-                bail!(AbiErrorKind::InvalidParameterValue(value.clone()))
+                Err(Self::error(path, &param_type, value,
+                    format!("{} bytes expected, received {} bytes", size, data.len())))
             }
             None => Ok(TokenValue::Bytes(data))
         }
     }
 
+    fn tokenize_string(value: &Value, path: &str) -> AbiResult<TokenValue> {
+        let string = value
+            .as_str()
+            .ok_or_else(|| Self::error(path, &ParamType::String, value, "string expected".to_owned()))?;
+
+        Ok(TokenValue::String(string.to_owned()))
+    }
+
+    /// Tries to parse a value as optional value, `null` is treated as no value
+    fn tokenize_optional(inner_type: &ParamType, value: &Value, path: &str) -> AbiResult<TokenValue> {
+        let value = match value {
+            Value::Null => None,
+            value => Some(Box::new(Self::tokenize_value(inner_type, value, path)?)),
+        };
+
+        Ok(TokenValue::Optional(inner_type.clone(), value))
+    }
+
     /// Tries to parse a value as tuple.
-    fn tokenize_tuple(params: &Vec<Param>, value: &Value) -> AbiResult<TokenValue> {
-        let tokens = Self::tokenize_all(params, value)?;
+    fn tokenize_tuple(params: &Vec<Param>, value: &Value, path: &str) -> AbiResult<TokenValue> {
+        let tokens = Self::tokenize_all_with_path(params, value, path)?;
 
         Ok(TokenValue::Tuple(tokens))
     }
@@ -0,0 +1,408 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! ABI JSON validation.
+
+use std::collections::HashMap;
+use std::fmt;
+use serde_json::Value;
+use {AbiLayout, Event, Function, ParamType};
+use param_type::Reader;
+
+#[cfg(test)]
+#[path = "tests/test_validator.rs"]
+mod tests;
+
+/// Problem found in ABI JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationError {
+    /// Path to the offending field, e.g. `functions[1].inputs[0].type`
+    pub path: String,
+    /// Line of the offending field in ABI JSON, starting from 1
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{} (line {}): {}", self.path, line, self.message),
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+/// Checks ABI JSON and returns all found problems. Empty result means ABI is valid
+pub fn validate(abi_json: &str) -> Vec<ValidationError> {
+    let value: Value = match serde_json::from_str(abi_json) {
+        Ok(value) => value,
+        Err(err) => return vec![ValidationError {
+            path: String::new(),
+            line: Some(err.line()),
+            message: format!("invalid JSON: {}", err),
+        }],
+    };
+
+    let mut validator = Validator {
+        lines: Locator::locate(abi_json),
+        errors: Vec::new(),
+    };
+    validator.check_contract(&value);
+    validator.errors
+}
+
+/// Checks that type sizes are valid and map keys have allowed types
+pub(crate) fn check_type(kind: &ParamType) -> Result<(), String> {
+    match kind {
+        ParamType::Unknown => Err("unknown type".to_owned()),
+        ParamType::Uint(size) | ParamType::Int(size) if *size == 0 || *size > 256 =>
+            Err(format!("invalid size of `{}`: must be from 1 to 256", kind)),
+        ParamType::FixedBytes(0) =>
+            Err(format!("invalid size of `{}`: must be positive", kind)),
+        ParamType::FixedArray(_, 0) =>
+            Err(format!("invalid size of `{}`: must be positive", kind)),
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) |
+        ParamType::Optional(inner) => check_type(inner),
+        ParamType::Tuple(params) => {
+            for param in params {
+                check_type(&param.kind)?;
+            }
+            Ok(())
+        }
+        ParamType::Map(key, value) => {
+            check_type(key)?;
+            if key.bit_len() == 0 {
+                return Err(format!(
+                    "`{}` can not be a map key: only int, uint, address and fixedbytes types are allowed", key));
+            }
+            check_type(value)
+        }
+        _ => Ok(())
+    }
+}
+
+// Returns true if type contains tuple which components are described in `components` field
+fn has_tuple(kind: &ParamType) -> bool {
+    match kind {
+        ParamType::Tuple(_) => true,
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) |
+        ParamType::Optional(inner) => has_tuple(inner),
+        ParamType::Map(_, value) => has_tuple(value),
+        _ => false
+    }
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_owned()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+struct Validator {
+    lines: HashMap<String, usize>,
+    errors: Vec<ValidationError>,
+}
+
+impl Validator {
+    fn error(&mut self, path: String, message: String) {
+        // field may be absent, so fall back to the line of the closest parent
+        let mut line = None;
+        let mut parent = path.as_str();
+        loop {
+            if let Some(found) = self.lines.get(parent) {
+                line = Some(*found);
+                break;
+            }
+            match parent.rfind(|c: char| c == '.' || c == '[') {
+                Some(pos) => parent = &parent[..pos],
+                None if !parent.is_empty() => parent = "",
+                None => break,
+            }
+        }
+        self.errors.push(ValidationError { path, line, message });
+    }
+
+    fn array<'a>(&mut self, value: &'a Value, path: &str, field: &str, required: bool) -> &'a [Value] {
+        match value.get(field) {
+            Some(Value::Array(items)) => items.as_slice(),
+            None if !required => &[],
+            Some(_) => { self.error(join(path, field), "array expected".to_owned()); &[] }
+            None => { self.error(join(path, field), "field is missing".to_owned()); &[] }
+        }
+    }
+
+    fn name(&mut self, value: &Value, path: &str) -> Option<String> {
+        match value.get("name") {
+            Some(Value::String(name)) if !name.is_empty() => Some(name.clone()),
+            Some(_) => { self.error(join(path, "name"), "non-empty string expected".to_owned()); None }
+            None => { self.error(join(path, "name"), "field is missing".to_owned()); None }
+        }
+    }
+
+    fn check_contract(&mut self, value: &Value) {
+        if !value.is_object() {
+            self.error(String::new(), "object expected".to_owned());
+            return;
+        }
+
+        let layout = match value.get("ABI version") {
+            Some(version) => {
+                let layout = version.as_u64()
+                    .filter(|version| *version <= u8::max_value() as u64)
+                    .and_then(|version| AbiLayout::from_version(version as u8).ok());
+                if layout.is_none() {
+                    self.error("ABI version".to_owned(), format!("unsupported ABI version {}, expected {}", version, AbiLayout::versions_text()));
+                }
+                layout.unwrap_or_default()
+            }
+            None => {
+                self.error("ABI version".to_owned(), "field is missing".to_owned());
+                AbiLayout::default()
+            }
+        };
+
+        let set_time = match value.get("setTime") {
+            Some(Value::Bool(set_time)) => *set_time,
+            Some(_) => { self.error("setTime".to_owned(), "boolean expected".to_owned()); true }
+            None => true,
+        };
+
+        let mut names = HashMap::new();
+        let mut ids = HashMap::new();
+        for (i, function) in self.array(value, "", "functions", true).iter().enumerate() {
+            let path = format!("functions[{}]", i);
+            let valid = self.check_params(function, &path, "inputs") &
+                self.check_params(function, &path, "outputs");
+            let name = self.name(function, &path);
+            if let Some(ref name) = name {
+                if let Some(other) = names.insert(name.clone(), path.clone()) {
+                    self.error(join(&path, "name"), format!("function `{}` is already defined at {}", name, other));
+                }
+            }
+            if !valid || name.is_none() {
+                continue;
+            }
+            match serde_json::from_value::<Function>(function.clone()) {
+                Ok(mut function) => {
+                    function.set_time = set_time;
+                    function.layout = layout;
+                    let id = function.get_input_id();
+                    if let Some(other) = ids.insert(id, path.clone()) {
+                        self.error(path, format!("function id 0x{:08x} duplicates id of {}", id, other));
+                    }
+                }
+                Err(err) => self.error(path, err.to_string()),
+            }
+        }
+
+        let mut names = HashMap::new();
+        let mut ids = HashMap::new();
+        for (i, event) in self.array(value, "", "events", false).iter().enumerate() {
+            let path = format!("events[{}]", i);
+            let valid = self.check_params(event, &path, "inputs");
+            let name = self.name(event, &path);
+            if let Some(ref name) = name {
+                if let Some(other) = names.insert(name.clone(), path.clone()) {
+                    self.error(join(&path, "name"), format!("event `{}` is already defined at {}", name, other));
+                }
+            }
+            if !valid || name.is_none() {
+                continue;
+            }
+            match serde_json::from_value::<Event>(event.clone()) {
+                Ok(mut event) => {
+                    event.layout = layout;
+                    let id = event.get_id();
+                    if let Some(other) = ids.insert(id, path.clone()) {
+                        self.error(path, format!("event id 0x{:08x} duplicates id of {}", id, other));
+                    }
+                }
+                Err(err) => self.error(path, err.to_string()),
+            }
+        }
+
+        let mut keys = HashMap::new();
+        for (i, item) in self.array(value, "", "data", false).iter().enumerate() {
+            let path = format!("data[{}]", i);
+            self.check_param(item, &path);
+            match item.get("key").and_then(|key| key.as_u64()) {
+                Some(key) => if let Some(other) = keys.insert(key, path.clone()) {
+                    self.error(join(&path, "key"), format!("data key {} is already used by {}", key, other));
+                },
+                None => self.error(join(&path, "key"), "unsigned integer expected".to_owned()),
+            }
+        }
+    }
+
+    fn check_params(&mut self, value: &Value, path: &str, field: &str) -> bool {
+        let params = self.array(value, path, field, false);
+        let path = join(path, field);
+        let mut valid = true;
+        for (i, param) in params.iter().enumerate() {
+            valid &= self.check_param(param, &format!("{}[{}]", path, i));
+        }
+        valid
+    }
+
+    fn check_param(&mut self, value: &Value, path: &str) -> bool {
+        if !value.is_object() {
+            self.error(path.to_owned(), "object expected".to_owned());
+            return false;
+        }
+
+        let mut valid = self.name(value, path).is_some();
+
+        let type_path = join(path, "type");
+        let type_name = match value.get("type") {
+            Some(Value::String(type_name)) => type_name,
+            Some(_) => { self.error(type_path, "string expected".to_owned()); return false; }
+            None => { self.error(type_path, "field is missing".to_owned()); return false; }
+        };
+
+        let kind = match Reader::read(type_name) {
+            Ok(kind) => kind,
+            Err(err) => {
+                self.error(type_path, format!("invalid type `{}`: {}", type_name, err));
+                return false;
+            }
+        };
+
+        if let Err(err) = check_type(&kind) {
+            self.error(type_path, err);
+            valid = false;
+        }
+
+        if has_tuple(&kind) {
+            let components = self.array(value, path, "components", true);
+            let components_path = join(path, "components");
+            if components.is_empty() {
+                if value.get("components").is_some() {
+                    self.error(components_path.clone(), "tuple must have at least one component".to_owned());
+                }
+                valid = false;
+            }
+            for (i, component) in components.iter().enumerate() {
+                valid &= self.check_param(component, &format!("{}[{}]", components_path, i));
+            }
+        }
+
+        valid
+    }
+}
+
+// Maps JSON value paths to the lines they start at
+struct Locator<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    line: usize,
+    lines: HashMap<String, usize>,
+}
+
+impl<'a> Locator<'a> {
+    fn locate(json: &'a str) -> HashMap<String, usize> {
+        let mut locator = Locator { bytes: json.as_bytes(), pos: 0, line: 1, lines: HashMap::new() };
+        locator.value(String::new());
+        locator.lines
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.bytes.get(self.pos) {
+            match c {
+                b'\n' => self.line += 1,
+                b' ' | b'\t' | b'\r' => {},
+                _ => return,
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(c) = self.bytes.get(self.pos) {
+            self.pos += 1;
+            match c {
+                b'\\' => self.pos += 1,
+                b'"' => break,
+                _ => {},
+            }
+        }
+        let end = std::cmp::min(self.pos, self.bytes.len());
+        serde_json::from_slice(&self.bytes[start..end]).unwrap_or_default()
+    }
+
+    fn value(&mut self, path: String) {
+        self.skip_whitespace();
+        self.lines.insert(path.clone(), self.line);
+        match self.bytes.get(self.pos) {
+            Some(b'{') => {
+                self.pos += 1;
+                if self.eat(b'}') {
+                    return;
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.bytes.get(self.pos) != Some(&b'"') {
+                        return;
+                    }
+                    let key = self.string();
+                    if !self.eat(b':') {
+                        return;
+                    }
+                    self.value(join(&path, &key));
+                    if !self.eat(b',') {
+                        self.eat(b'}');
+                        return;
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                if self.eat(b']') {
+                    return;
+                }
+                let mut index = 0;
+                loop {
+                    self.value(format!("{}[{}]", path, index));
+                    index += 1;
+                    if !self.eat(b',') {
+                        self.eat(b']');
+                        return;
+                    }
+                }
+            }
+            Some(b'"') => { self.string(); }
+            _ => {
+                while let Some(c) = self.bytes.get(self.pos) {
+                    match c {
+                        b',' | b']' | b'}' | b' ' | b'\t' | b'\r' | b'\n' => return,
+                        _ => self.pos += 1,
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// Generated by ton_abi::codegen. Do not edit.
+
+#[allow(unused_imports)]
+use ton_abi::{AbiResult, Contract, Param, ParamType, Token, TokenValue, Int, Uint};
+#[allow(unused_imports)]
+use ton_abi::codegen::{self, BuilderData, Cell, Grams, Keypair, MsgAddress, SliceData};
+#[allow(unused_imports)]
+use std::collections::HashMap;
+
+pub const ABI: &str = r###"{
+    "ABI version": 1,
+    "functions": [{
+        "name": "transfer",
+        "inputs": [
+            {"name": "dest", "type": "address"},
+            {"name": "value", "type": "uint128"},
+            {"name": "memo", "type": "fixedbytes4"}
+        ],
+        "outputs": [
+            {"name": "codes", "type": "int32[]"},
+            {"name": "limits", "type": "uint128[2]"}
+        ]
+    }],
+    "events": [{
+        "name": "Done",
+        "inputs": [{"name": "value", "type": "uint128"}]
+    }],
+    "data": []
+}"###;
+
+codegen::lazy_static! {
+    static ref CONTRACT: Result<Contract, String> =
+        Contract::load(ABI.as_bytes()).map_err(|err| err.to_string());
+}
+
+/// Returns contract ABI parsed once on first call
+pub fn contract() -> AbiResult<&'static Contract> {
+    codegen::loaded(&CONTRACT)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct TransferInput {
+    pub dest: MsgAddress,
+    pub value: Uint128,
+    pub memo: [u8; 4],
+}
+
+impl TransferInput {
+    pub fn into_tokens(self) -> Vec<Token> {
+        vec![
+            Token::new("dest", TokenValue::Address(self.dest)),
+            Token::new("value", TokenValue::Uint(self.value.into_value())),
+            Token::new("memo", TokenValue::FixedBytes(self.memo.to_vec())),
+        ]
+    }
+
+    #[allow(unused_mut, unused_variables)]
+    pub fn from_tokens(tokens: Vec<Token>) -> AbiResult<Self> {
+        codegen::check_count(&tokens, 3)?;
+        let mut tokens = tokens.into_iter();
+        Ok(Self {
+            dest: codegen::address(codegen::next_value(&mut tokens, "dest")?)?,
+            value: Uint128::from_value(codegen::uint(codegen::next_value(&mut tokens, "value")?)?)?,
+            memo: codegen::fixed(codegen::bytes(codegen::next_value(&mut tokens, "memo")?)?)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct TransferOutput {
+    pub codes: Vec<Int32>,
+    pub limits: [Uint128; 2],
+}
+
+impl TransferOutput {
+    pub fn into_tokens(self) -> Vec<Token> {
+        vec![
+            Token::new("codes", TokenValue::Array(self.codes.into_iter().map(|v| TokenValue::Int(v.into_value())).collect())),
+            Token::new("limits", TokenValue::FixedArray(self.limits.to_vec().into_iter().map(|v| TokenValue::Uint(v.into_value())).collect())),
+        ]
+    }
+
+    #[allow(unused_mut, unused_variables)]
+    pub fn from_tokens(tokens: Vec<Token>) -> AbiResult<Self> {
+        codegen::check_count(&tokens, 2)?;
+        let mut tokens = tokens.into_iter();
+        Ok(Self {
+            codes: codegen::array(codegen::next_value(&mut tokens, "codes")?)?.into_iter().map(|v| -> AbiResult<_> { Ok(Int32::from_value(codegen::int(v)?)?) }).collect::<AbiResult<Vec<_>>>()?,
+            limits: codegen::fixed(codegen::array(codegen::next_value(&mut tokens, "limits")?)?.into_iter().map(|v| -> AbiResult<_> { Ok(Uint128::from_value(codegen::uint(v)?)?) }).collect::<AbiResult<Vec<_>>>()?)?,
+        })
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn encode_transfer(input: TransferInput, internal: bool, pair: Option<&Keypair>) -> AbiResult<BuilderData> {
+    contract()?.function("transfer")?.encode_input(&input.into_tokens(), internal, pair)
+}
+
+#[allow(non_snake_case)]
+pub fn decode_transfer_input(body: SliceData, internal: bool) -> AbiResult<TransferInput> {
+    TransferInput::from_tokens(contract()?.function("transfer")?.decode_input(body, internal)?)
+}
+
+#[allow(non_snake_case)]
+pub fn decode_transfer_output(body: SliceData, internal: bool) -> AbiResult<TransferOutput> {
+    TransferOutput::from_tokens(contract()?.function("transfer")?.decode_output(body, internal)?)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct DoneEvent {
+    pub value: Uint128,
+}
+
+impl DoneEvent {
+    pub fn into_tokens(self) -> Vec<Token> {
+        vec![
+            Token::new("value", TokenValue::Uint(self.value.into_value())),
+        ]
+    }
+
+    #[allow(unused_mut, unused_variables)]
+    pub fn from_tokens(tokens: Vec<Token>) -> AbiResult<Self> {
+        codegen::check_count(&tokens, 1)?;
+        let mut tokens = tokens.into_iter();
+        Ok(Self {
+            value: Uint128::from_value(codegen::uint(codegen::next_value(&mut tokens, "value")?)?)?,
+        })
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_Done_event(body: SliceData) -> AbiResult<DoneEvent> {
+    let event = codegen::event(contract()?, "Done")?;
+    DoneEvent::from_tokens(event.decode_input(body)?)
+}
+
+/// `uint128` value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Uint128(Uint);
+
+impl Uint128 {
+    pub const SIZE: usize = 128;
+
+    /// Creates value checking that it fits 128 bits
+    pub fn new(number: u128) -> AbiResult<Self> {
+        Self::from_value(Uint::new(number, 128))
+    }
+
+    /// Checks that value has 128 bits width and fits it
+    pub fn from_value(value: Uint) -> AbiResult<Self> {
+        codegen::check_uint(&value, 128)?;
+        Ok(Uint128(value))
+    }
+
+    pub fn value(&self) -> &Uint {
+        &self.0
+    }
+
+    pub fn into_value(self) -> Uint {
+        self.0
+    }
+}
+
+/// `int32` value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Int32(Int);
+
+impl Int32 {
+    pub const SIZE: usize = 32;
+
+    /// Creates value checking that it fits 32 bits
+    pub fn new(number: i128) -> AbiResult<Self> {
+        Self::from_value(Int::new(number, 32))
+    }
+
+    /// Checks that value has 32 bits width and fits it
+    pub fn from_value(value: Int) -> AbiResult<Self> {
+        codegen::check_int(&value, 32)?;
+        Ok(Int32(value))
+    }
+
+    pub fn value(&self) -> &Int {
+        &self.0
+    }
+
+    pub fn into_value(self) -> Int {
+        self.0
+    }
+}
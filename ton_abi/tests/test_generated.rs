@@ -0,0 +1,85 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Compiles bindings generated by `ton_abi::codegen` for `generated/Transfer.abi.json`.
+//! `ton_abi` unit tests check that the checked-in bindings match generator output.
+
+extern crate ton_abi;
+extern crate ton_types;
+
+#[allow(dead_code)]
+mod transfer {
+    include!("generated/transfer.rs");
+}
+
+use ton_abi::{Token, TokenValue, Uint};
+use ton_abi::codegen::{BuilderData, MsgAddress};
+use ton_types::IBitstring;
+use transfer::*;
+
+#[test]
+fn test_generated_bindings_round_trip() {
+    let input = TransferInput {
+        dest: MsgAddress::with_standart(None, 0, vec![0x11; 32].into()).unwrap(),
+        value: Uint128::new(1_000_000_000).unwrap(),
+        memo: *b"memo",
+    };
+    let body = encode_transfer(input.clone(), true, None).unwrap();
+    assert_eq!(decode_transfer_input(body.into(), true).unwrap(), input);
+
+    let output = TransferOutput {
+        codes: vec![Int32::new(-1).unwrap(), Int32::new(i32::max_value() as i128).unwrap()],
+        limits: [Uint128::new(0).unwrap(), Uint128::new(u128::max_value()).unwrap()],
+    };
+    let mut builder = BuilderData::new();
+    builder.append_u32(contract().unwrap().function("transfer").unwrap().get_output_id()).unwrap();
+    let body = TokenValue::pack_values_into_chain(&output.clone().into_tokens(), vec![builder], Default::default())
+        .unwrap();
+    assert_eq!(decode_transfer_output(body.into(), true).unwrap(), output);
+
+    // ABI is parsed once
+    assert!(std::ptr::eq(contract().unwrap(), contract().unwrap()));
+}
+
+#[test]
+fn test_generated_integers_are_checked() {
+    assert!(Uint128::new(u128::max_value()).is_ok());
+    assert!(Int32::new(i32::min_value() as i128).is_ok());
+    assert!(Int32::new(i32::max_value() as i128 + 1).is_err());
+    assert!(Uint128::from_value(Uint::new(1, 64)).is_err());
+
+    // decoding checks widths of received tokens as well
+    let tokens = vec![Token::new("value", TokenValue::Uint(Uint::new(1, 64)))];
+    assert!(DoneEvent::from_tokens(tokens).is_err());
+}
+
+#[test]
+fn test_generated_fixed_sizes_are_checked() {
+    let tokens = |memo: Vec<u8>| vec![
+        Token::new("dest", TokenValue::Address(MsgAddress::AddrNone)),
+        Token::new("value", TokenValue::Uint(Uint::new(1, 128))),
+        Token::new("memo", TokenValue::FixedBytes(memo)),
+    ];
+    assert_eq!(TransferInput::from_tokens(tokens(b"memo".to_vec())).unwrap().memo, *b"memo");
+    assert!(TransferInput::from_tokens(tokens(b"mem".to_vec())).is_err());
+    assert!(TransferInput::from_tokens(tokens(b"memos".to_vec())).is_err());
+
+    let limits = |count: usize| vec![
+        Token::new("codes", TokenValue::Array(vec![])),
+        Token::new("limits", TokenValue::FixedArray(vec![TokenValue::Uint(Uint::new(1, 128)); count])),
+    ];
+    assert!(TransferOutput::from_tokens(limits(2)).is_ok());
+    assert!(TransferOutput::from_tokens(limits(1)).is_err());
+    assert!(TransferOutput::from_tokens(limits(3)).is_err());
+}
@@ -0,0 +1,165 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use types::{ApiResult, ApiError, base64_decode};
+use dispatch::DispatchTable;
+use client::ClientContext;
+use std::io::Cursor;
+use ton_sdk::AbiCellTree as CellTree;
+use ton_types::Cell;
+use ton_types::cells_serialization::{BagOfCells, BocSerialiseMode, deserialize_tree_of_cells};
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[allow(non_snake_case)]
+pub(crate) struct BocSerializationOptions {
+    #[serde(default)]
+    pub index: bool,
+    #[serde(default)]
+    pub crc32: bool,
+    #[serde(default)]
+    pub cacheBits: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfBoc {
+    pub bocBase64: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfFromTree {
+    pub tree: CellTree,
+    pub options: Option<BocSerializationOptions>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfSerialize {
+    pub bocBase64: String,
+    pub options: Option<BocSerializationOptions>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfGetHash {
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfGetDepth {
+    pub depth: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfSerialize {
+    pub bocBase64: String,
+    pub hash: String,
+}
+
+pub(crate) fn deserialize_boc(boc_base64: &String) -> ApiResult<Cell> {
+    let bytes = base64_decode(boc_base64)?;
+    deserialize_tree_of_cells(&mut Cursor::new(bytes))
+        .map_err(|err| ApiError::boc_invalid_boc(err))
+}
+
+pub(crate) fn serialize_boc(cell: &Cell, options: &BocSerializationOptions) -> ApiResult<Vec<u8>> {
+    let mode = BocSerialiseMode::Generic {
+        index: options.index,
+        crc: options.crc32,
+        cache_bits: options.cacheBits,
+        flags: 0,
+    };
+    let mut data = Vec::new();
+    BagOfCells::with_root(cell).write_to_ex(&mut data, mode, None, None)
+        .map_err(|err| ApiError::boc_serialization_failed(err))?;
+    Ok(data)
+}
+
+fn cell_hash(cell: &Cell) -> String {
+    hex::encode(cell.repr_hash().as_slice())
+}
+
+pub(crate) fn cell_to_tree(cell: &Cell) -> ApiResult<CellTree> {
+    CellTree::from_cell(cell)
+        .map_err(|err| ApiError::boc_invalid_boc(err))
+}
+
+pub(crate) fn tree_to_cell(tree: &CellTree) -> ApiResult<Cell> {
+    tree.to_cell()
+        .map_err(|err| ApiError::boc_invalid_tree(err))
+}
+
+pub(crate) fn get_hash(_context: &mut ClientContext, params: ParamsOfBoc) -> ApiResult<ResultOfGetHash> {
+    debug!("-> boc.get_hash()");
+    let cell = deserialize_boc(&params.bocBase64)?;
+    debug!("<-");
+    Ok(ResultOfGetHash {
+        hash: cell_hash(&cell),
+    })
+}
+
+pub(crate) fn get_depth(_context: &mut ClientContext, params: ParamsOfBoc) -> ApiResult<ResultOfGetDepth> {
+    debug!("-> boc.get_depth()");
+    let cell = deserialize_boc(&params.bocBase64)?;
+    debug!("<-");
+    Ok(ResultOfGetDepth {
+        depth: cell.repr_depth(),
+    })
+}
+
+pub(crate) fn to_tree(_context: &mut ClientContext, params: ParamsOfBoc) -> ApiResult<CellTree> {
+    debug!("-> boc.to_tree()");
+    let cell = deserialize_boc(&params.bocBase64)?;
+    let tree = cell_to_tree(&cell)?;
+    debug!("<-");
+    Ok(tree)
+}
+
+pub(crate) fn from_tree(_context: &mut ClientContext, params: ParamsOfFromTree) -> ApiResult<ResultOfSerialize> {
+    debug!("-> boc.from_tree({:?})", params.options);
+    let cell = tree_to_cell(&params.tree)?;
+    let data = serialize_boc(&cell, &params.options.unwrap_or_default())?;
+    debug!("<-");
+    Ok(ResultOfSerialize {
+        bocBase64: base64::encode(&data),
+        hash: cell_hash(&cell),
+    })
+}
+
+pub(crate) fn serialize(_context: &mut ClientContext, params: ParamsOfSerialize) -> ApiResult<ResultOfSerialize> {
+    debug!("-> boc.serialize({:?})", params.options);
+    let cell = deserialize_boc(&params.bocBase64)?;
+    let data = serialize_boc(&cell, &params.options.unwrap_or_default())?;
+    debug!("<-");
+    Ok(ResultOfSerialize {
+        bocBase64: base64::encode(&data),
+        hash: cell_hash(&cell),
+    })
+}
+
+pub(crate) fn register(handlers: &mut DispatchTable) {
+    handlers.spawn("boc.get_hash",
+        get_hash);
+    handlers.spawn("boc.get_depth",
+        get_depth);
+    handlers.spawn("boc.to_tree",
+        to_tree);
+    handlers.spawn("boc.from_tree",
+        from_tree);
+    handlers.spawn("boc.serialize",
+        serialize);
+}
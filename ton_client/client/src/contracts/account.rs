@@ -0,0 +1,102 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use ton_sdk::{Contract, ContractImage, AccountInfo, CodeComparison};
+use crypto::keys::account_decode;
+use types::{ApiResult, ApiError, hex_decode};
+use client::ClientContext;
+
+#[cfg(feature = "node_interaction")]
+use futures::Stream;
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfGetAccountInfo {
+    pub address: String,
+    pub account: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfVerifyCode {
+    pub address: String,
+    pub account: Option<serde_json::Value>,
+    pub imageBase64: Option<String>,
+    pub codeHash: Option<String>,
+}
+
+pub(crate) fn get_info(_context: &mut ClientContext, params: ParamsOfGetAccountInfo) -> ApiResult<AccountInfo> {
+    debug!("-> contracts.account.info({})", params.address);
+
+    let contract = load_account(&params.address, params.account)?;
+
+    debug!("<-");
+    Ok(contract.info())
+}
+
+pub(crate) fn verify_code(_context: &mut ClientContext, params: ParamsOfVerifyCode) -> ApiResult<CodeComparison> {
+    debug!("-> contracts.account.verify_code({})", params.address);
+
+    let expected = match (params.imageBase64, params.codeHash) {
+        (Some(image), _) => decode_image(&image)?.code_hash()
+            .map_err(|err| ApiError::contracts_image_creation_failed(err))?,
+        (None, Some(hash)) => hex_decode(&hash)?,
+        (None, None) => return Err(ApiError::invalid_params("", "Either image or code hash must be provided")),
+    };
+
+    let contract = load_account(&params.address, params.account)?;
+
+    debug!("<-");
+    Ok(contract.compare_code_hash(&expected))
+}
+
+// Internals
+
+pub(crate) fn decode_image(image_base64: &str) -> ApiResult<ContractImage> {
+    let bytes = base64::decode(image_base64)
+        .map_err(|err| ApiError::contracts_invalid_image(err))?;
+    ContractImage::from_state_init(&mut bytes.as_slice())
+        .map_err(|err| ApiError::contracts_image_creation_failed(err))
+}
+
+// Uses provided account JSON or loads account from the node
+pub(crate) fn load_account(address: &String, account: Option<serde_json::Value>) -> ApiResult<Contract> {
+    let decoded = account_decode(address)?;
+
+    match account {
+        #[cfg(feature = "node_interaction")]
+        None => {
+            let loaded = Contract::load(&decoded)
+                .map_err(|err| ApiError::contracts_load_failed(err, address))?
+                .wait()
+                .next();
+            match loaded {
+                Some(Ok(Some(contract))) => Ok(contract),
+                Some(Err(err)) => Err(ApiError::contracts_load_failed(err, address)),
+                _ => Err(ApiError::contracts_load_failed("Account not found", address)),
+            }
+        }
+        #[cfg(not(feature = "node_interaction"))]
+        None => {
+            let _address = decoded;
+            Err(ApiError::invalid_params("", "No account provided"))
+        }
+        Some(account) => {
+            let mut contract = Contract::from_json(&account.to_string())
+                .map_err(|err| ApiError::invalid_params(&account.to_string(), err))?;
+            contract.id = decoded;
+            Ok(contract)
+        }
+    }
+}
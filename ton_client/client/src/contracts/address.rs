@@ -0,0 +1,235 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use types::{ApiResult, ApiError, base64_decode};
+use crypto::keys::decode_public_key;
+use ton_sdk::{Address, AddressFormat, ContractImage};
+use ton_block::MsgAddressInt;
+use std::io::Cursor;
+use client::ClientContext;
+
+fn default_bounce() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct AddressFlags {
+    #[serde(default = "default_bounce")]
+    pub bounce: bool,
+    #[serde(default)]
+    pub test: bool,
+}
+
+impl Default for AddressFlags {
+    fn default() -> Self {
+        Self { bounce: default_bounce(), test: false }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfAddressFromStateInit {
+    pub stateInitBase64: String,
+    #[serde(default)]
+    pub workchainId: i32,
+    #[serde(default)]
+    pub flags: AddressFlags,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfAddressFromCodeAndData {
+    pub codeBase64: String,
+    pub dataBase64: Option<String>,
+    pub libraryBase64: Option<String>,
+    #[serde(default)]
+    pub workchainId: i32,
+    #[serde(default)]
+    pub flags: AddressFlags,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfAddressFromImage {
+    pub abi: serde_json::Value,
+    pub imageBase64: String,
+    pub initParams: Option<serde_json::Value>,
+    pub publicKey: Option<String>,
+    #[serde(default)]
+    pub workchainId: i32,
+    #[serde(default)]
+    pub flags: AddressFlags,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfCalcAddress {
+    /// Raw form `workchain:hex`
+    pub address: String,
+    pub accountId: String,
+    /// User-friendly base64 form, not set for non-std addresses
+    pub base64: Option<String>,
+    /// Url-safe base64 form, not set for non-std addresses
+    pub base64Url: Option<String>,
+    pub bounce: bool,
+    pub test: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfParseAddress {
+    pub address: String,
+    /// Workchain of bare account id, account id without workchain is invalid if not set
+    pub defaultWorkchainId: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfParseAddress {
+    pub valid: bool,
+    pub format: Option<AddressFormat>,
+    /// Flags found in user-friendly address
+    pub bounceable: Option<bool>,
+    pub testnet: Option<bool>,
+    pub workchainId: Option<i32>,
+    pub anycast: Option<String>,
+    pub accountId: Option<String>,
+    pub raw: Option<String>,
+    /// User-friendly forms keep parsed flags, bounceable mainnet address is used by default
+    pub base64: Option<String>,
+    pub base64Url: Option<String>,
+    /// Reasons the address or some of its forms are invalid
+    pub errors: Vec<String>,
+}
+
+pub(crate) fn from_state_init(_context: &mut ClientContext, params: ParamsOfAddressFromStateInit) -> ApiResult<ResultOfCalcAddress> {
+    debug!("-> contracts.address.from_state_init({})", params.workchainId);
+
+    let bytes = base64_decode(&params.stateInitBase64)?;
+    let image = ContractImage::from_state_init(&mut bytes.as_slice())
+        .map_err(|err| ApiError::contracts_image_creation_failed(err))?;
+
+    debug!("<-");
+    encode_address(&image.msg_address(params.workchainId), &params.flags)
+}
+
+pub(crate) fn from_code_and_data(_context: &mut ClientContext, params: ParamsOfAddressFromCodeAndData) -> ApiResult<ResultOfCalcAddress> {
+    debug!("-> contracts.address.from_code_and_data({})", params.workchainId);
+
+    let code = base64_decode(&params.codeBase64)?;
+    let mut data = match params.dataBase64 {
+        Some(ref data) => Some(Cursor::new(base64_decode(data)?)),
+        None => None,
+    };
+    let mut library = match params.libraryBase64 {
+        Some(ref library) => Some(Cursor::new(base64_decode(library)?)),
+        None => None,
+    };
+
+    let image = ContractImage::from_code_data_and_library(
+        &mut Cursor::new(code),
+        data.as_mut(),
+        library.as_mut())
+        .map_err(|err| ApiError::contracts_image_creation_failed(err))?;
+
+    debug!("<-");
+    encode_address(&image.msg_address(params.workchainId), &params.flags)
+}
+
+pub(crate) fn from_image(_context: &mut ClientContext, params: ParamsOfAddressFromImage) -> ApiResult<ResultOfCalcAddress> {
+    debug!("-> contracts.address.from_image({})", params.workchainId);
+
+    let bytes = base64::decode(&params.imageBase64)
+        .map_err(|err| ApiError::contracts_invalid_image(err))?;
+    let mut image = ContractImage::from_state_init(&mut bytes.as_slice())
+        .map_err(|err| ApiError::contracts_image_creation_failed(err))?;
+
+    if let Some(ref public_key) = params.publicKey {
+        image.set_public_key(&decode_public_key(public_key)?)
+            .map_err(|err| ApiError::contracts_image_creation_failed(err))?;
+    }
+
+    if let Some(ref init_params) = params.initParams {
+        image.update_data(&init_params.to_string(), &params.abi.to_string())
+            .map_err(|err| ApiError::contracts_image_creation_failed(
+                format!("Failed to set initial data: {}", err)))?;
+    }
+
+    debug!("<-");
+    encode_address(&image.msg_address(params.workchainId), &params.flags)
+}
+
+fn encode_address(address: &MsgAddressInt, flags: &AddressFlags) -> ApiResult<ResultOfCalcAddress> {
+    let (base64, base64_url) = match address {
+        MsgAddressInt::AddrStd(_) => (
+            Some(ton_sdk::encode_base64(address, flags.bounce, flags.test, false)
+                .map_err(|err| ApiError::contracts_address_conversion_failed(err))?),
+            Some(ton_sdk::encode_base64(address, flags.bounce, flags.test, true)
+                .map_err(|err| ApiError::contracts_address_conversion_failed(err))?),
+        ),
+        _ => (None, None),
+    };
+
+    Ok(ResultOfCalcAddress {
+        address: address.to_string(),
+        accountId: address.get_address().to_hex_string(),
+        base64,
+        base64Url: base64_url,
+        bounce: flags.bounce,
+        test: flags.test,
+    })
+}
+
+pub(crate) fn parse(_context: &mut ClientContext, params: ParamsOfParseAddress) -> ApiResult<ResultOfParseAddress> {
+    debug!("-> contracts.address.parse({})", params.address);
+
+    let parsed = match params.defaultWorkchainId {
+        Some(workchain_id) => Address::parse_with_workchain(&params.address, workchain_id),
+        None => Address::parse(&params.address),
+    };
+    let address = match parsed {
+        Ok(address) => address,
+        Err(err) => return Ok(ResultOfParseAddress {
+            errors: vec![err.to_string()],
+            ..ResultOfParseAddress::default()
+        })
+    };
+
+    let mut errors = Vec::new();
+    let bounceable = address.bounceable.unwrap_or(true);
+    let testnet = address.testnet.unwrap_or(false);
+    let (base64, base64_url) = match address.to_base64(bounceable, testnet, false) {
+        Ok(base64) => (Some(base64), address.to_base64(bounceable, testnet, true).ok()),
+        Err(err) => {
+            errors.push(err.to_string());
+            (None, None)
+        }
+    };
+
+    debug!("<-");
+    Ok(ResultOfParseAddress {
+        valid: true,
+        format: Some(address.format),
+        bounceable: address.bounceable,
+        testnet: address.testnet,
+        workchainId: Some(address.workchain_id()),
+        anycast: address.anycast(),
+        accountId: Some(address.account_id()),
+        raw: Some(address.to_raw()),
+        base64,
+        base64Url: base64_url,
+        errors,
+    })
+}
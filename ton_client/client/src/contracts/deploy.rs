@@ -13,6 +13,8 @@
 */
 
 use crypto::keys::{KeyPair, decode_public_key, account_encode};
+use crypto::keystore::KeyStore;
+use crypto::signing_box::SigningBoxHandle;
 use ton_sdk::{Contract, ContractImage};
 
 use contracts::EncodedUnsignedMessage;
@@ -29,7 +31,9 @@ pub(crate) struct ParamsOfDeploy {
     pub constructorParams: serde_json::Value,
     pub initParams: Option<serde_json::Value>,
     pub imageBase64: String,
-    pub keyPair: KeyPair,
+    pub keyPair: Option<KeyPair>,
+    pub keystoreHandle: Option<String>,
+    pub signingBox: Option<SigningBoxHandle>,
     #[serde(default)]
     pub workchainId: i32,
 }
@@ -59,7 +63,9 @@ pub(crate) struct ParamsOfGetDeployAddress {
     pub abi: serde_json::Value,
     pub initParams: Option<serde_json::Value>,
     pub imageBase64: String,
-    pub keyPair: KeyPair,
+    pub keyPair: Option<KeyPair>,
+    pub keystoreHandle: Option<String>,
+    pub signingBox: Option<SigningBoxHandle>,
     #[serde(default)]
     pub workchainId: i32,
 }
@@ -100,14 +106,15 @@ pub(crate) struct ResultOfGetDeployData {
 pub(crate) fn deploy(_context: &mut ClientContext, params: ParamsOfDeploy) -> ApiResult<ResultOfDeploy> {
     debug!("-> contracts.deploy({})", params.constructorParams.to_string());
 
-    let key_pair = params.keyPair.decode()?;
+    let key_pair = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
+    let public = super::signer_public_key(key_pair.as_ref(), params.signingBox)?;
 
-    let contract_image = create_image(&params.abi, params.initParams.as_ref(), &params.imageBase64, &key_pair.public)?;
+    let contract_image = create_image(&params.abi, params.initParams.as_ref(), &params.imageBase64, &public)?;
     let account_id = contract_image.msg_address(params.workchainId);
     debug!("-> -> image prepared with address: {}", account_id);
 
     debug!("-> -> deploy");
-    let tr = deploy_contract(&params, contract_image, &key_pair)?;
+    let tr = deploy_contract(&params, contract_image, key_pair.as_ref())?;
     debug!("-> -> deploy transaction: {}", tr. id());
 
     debug!("<-");
@@ -116,8 +123,9 @@ pub(crate) fn deploy(_context: &mut ClientContext, params: ParamsOfDeploy) -> Ap
 }
 
 pub(crate) fn get_address(_context: &mut ClientContext, params: ParamsOfGetDeployAddress) -> ApiResult<String> {
-    let key_pair = params.keyPair.decode()?;
-    let contract_image = create_image(&params.abi, params.initParams.as_ref(), &params.imageBase64, &key_pair.public)?;
+    let key_pair = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
+    let public = super::signer_public_key(key_pair.as_ref(), params.signingBox)?;
+    let contract_image = create_image(&params.abi, params.initParams.as_ref(), &params.imageBase64, &public)?;
     let account_id = contract_image.msg_address(params.workchainId);
     Ok(account_encode(&account_id))
 }
@@ -125,17 +133,13 @@ pub(crate) fn get_address(_context: &mut ClientContext, params: ParamsOfGetDeplo
 pub(crate) fn encode_message(_context: &mut ClientContext, params: ParamsOfDeploy) -> ApiResult<ResultOfEncodeDeployMessage> {
     debug!("-> contracts.deploy.message({})", params.constructorParams.to_string());
 
-    let keys = params.keyPair.decode()?;
+    let keys = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
+    let public = super::signer_public_key(keys.as_ref(), params.signingBox)?;
 
-    let contract_image = create_image(&params.abi, params.initParams.as_ref(), &params.imageBase64, &keys.public)?;
+    let contract_image = create_image(&params.abi, params.initParams.as_ref(), &params.imageBase64, &public)?;
     let account_id = contract_image.msg_address(params.workchainId);
     debug!("image prepared with address: {}", account_encode(&account_id));
-    let (message_body, message_id) = Contract::construct_deploy_message_json(
-        "constructor".to_owned(),
-        params.constructorParams.to_string().to_owned(),
-        params.abi.to_string().to_owned(),
-        contract_image,
-        Some(&keys), params.workchainId).map_err(|err| ApiError::contracts_create_deploy_message_failed(err))?;
+    let (message_body, message_id) = encode_deploy_message(&params, contract_image, keys.as_ref())?;
 
     debug!("<-");
     Ok(ResultOfEncodeDeployMessage {
@@ -228,13 +232,33 @@ use ed25519_dalek::PublicKey;
 use types::{ApiResult, ApiError};
 
 use client::ClientContext;
-
-#[cfg(feature = "node_interaction")]
 use ed25519_dalek::Keypair;
+use ton_sdk::MessageId;
 
 #[cfg(feature = "node_interaction")]
 use ton_block::TransactionProcessingStatus;
 
+fn encode_deploy_message(params: &ParamsOfDeploy, image: ContractImage, keys: Option<&Keypair>)
+    -> ApiResult<(Vec<u8>, MessageId)>
+{
+    if let Some(signing_box) = params.signingBox {
+        let message = Contract::get_deploy_message_bytes_for_signing(
+            "constructor".to_owned(),
+            params.constructorParams.to_string(),
+            params.abi.to_string(),
+            image,
+            params.workchainId).map_err(|err| ApiError::contracts_create_deploy_message_failed(err))?;
+        super::sign_message(message, signing_box)
+    } else {
+        Contract::construct_deploy_message_json(
+            "constructor".to_owned(),
+            params.constructorParams.to_string(),
+            params.abi.to_string(),
+            image,
+            keys, params.workchainId).map_err(|err| ApiError::contracts_create_deploy_message_failed(err))
+    }
+}
+
 fn create_image(abi: &serde_json::Value, init_params: Option<&serde_json::Value>, image_base64: &String, public_key: &PublicKey) -> ApiResult<ContractImage> {
     let bytes = base64::decode(image_base64)
         .map_err(|err| ApiError::contracts_invalid_image(err))?;
@@ -251,18 +275,23 @@ fn create_image(abi: &serde_json::Value, init_params: Option<&serde_json::Value>
 }
 
 #[cfg(feature = "node_interaction")]
-fn deploy_contract(params: &ParamsOfDeploy, image: ContractImage, keys: &Keypair) -> ApiResult<Transaction> {
-    let changes_stream = Contract::deploy_json(
-        "constructor".to_owned(),
-        params.constructorParams.to_string().to_owned(),
-        params.abi.to_string().to_owned(),
-        image, Some(keys), params.workchainId)
-        .expect("Error deploying contract");
+fn deploy_contract(params: &ParamsOfDeploy, image: ContractImage, keys: Option<&Keypair>) -> ApiResult<Transaction> {
+    let changes_stream = if params.signingBox.is_some() {
+        let (message, id) = encode_deploy_message(params, image, keys)?;
+        super::send_message(&id, &message)?
+    } else {
+        Contract::deploy_json(
+            "constructor".to_owned(),
+            params.constructorParams.to_string().to_owned(),
+            params.abi.to_string().to_owned(),
+            image, keys, params.workchainId)
+            .map_err(|err| ApiError::contracts_send_message_failed(err))?
+    };
 
     let mut tr = None;
     for transaction in changes_stream.wait() {
-        if let Err(e) = transaction {
-            panic!("error next state getting: {}", e);
+        if let Err(err) = transaction {
+            return Err(ApiError::contracts_send_message_failed(err));
         }
         if let Ok(transaction) = transaction {
             debug!("-> -> deploy: {:?}", transaction.status);
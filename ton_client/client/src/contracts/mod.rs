@@ -13,13 +13,26 @@
 */
 
 use types::{ApiResult, hex_decode, base64_decode, ApiError};
-use ton_sdk::{AbiContract, ContractImage};
+use ton_sdk::{AbiContract, AbiValidationError, ContractImage};
 use std::io::Cursor;
 use crypto::keys::{account_decode, account_encode_ex, AccountAddressType, Base64AddressParams};
+use crypto::signing_box::{SigningBoxes, SigningBoxHandle};
+use ed25519_dalek::{Keypair, PublicKey};
+use ton_sdk::{MessageId, MessageToSign};
+
+#[cfg(feature = "node_interaction")]
+use ton_sdk::{SdkError, Transaction};
+#[cfg(feature = "node_interaction")]
+use futures::Stream;
 
 pub(crate) mod types;
 pub(crate) mod deploy;
 pub(crate) mod run;
+pub(crate) mod multisig;
+pub(crate) mod address;
+pub(crate) mod upgrade;
+pub(crate) mod account;
+pub(crate) mod registry;
 
 #[cfg(feature = "node_interaction")]
 pub(crate) mod load;
@@ -75,6 +88,27 @@ pub(crate) struct ParamsOfGetCodeFromImage {
     pub imageBase64: String,
 }
 
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfValidateAbi {
+    /// ABI JSON text or object. Error lines refer to the text only
+    pub abi: serde_json::Value,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ResultOfValidateAbi {
+    pub valid: bool,
+    pub errors: Vec<AbiValidationError>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfGetAbiSchema {
+    /// ABI JSON text or object
+    pub abi: serde_json::Value,
+}
+
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize)]
 pub(crate) struct ResultOfGetCodeFromImage {
@@ -87,6 +121,20 @@ pub(crate) struct ResultOfConvertAddress {
     pub address: String,
 }
 
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfDecodeContractData {
+    pub abi: serde_json::Value,
+    pub dataBase64: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ResultOfDecodeContractData {
+    pub data: serde_json::Value,
+    pub publicKey: Option<String>,
+}
+
 use ton_sdk;
 use dispatch::DispatchTable;
 use client::ClientContext;
@@ -104,6 +152,37 @@ pub(crate) fn encode_message_with_sign(_context: &mut ClientContext, params: Par
     })
 }
 
+/// Returns public key of the signer. Signing box takes precedence over the key pair.
+pub(crate) fn signer_public_key(key_pair: Option<&Keypair>, signing_box: Option<SigningBoxHandle>) -> ApiResult<PublicKey> {
+    match (signing_box, key_pair) {
+        (Some(handle), _) => {
+            let bytes = SigningBoxes::get_public_key(handle)?;
+            PublicKey::from_bytes(&bytes)
+                .map_err(|err| ApiError::crypto_invalid_public_key(err, &hex::encode(&bytes)))
+        }
+        (None, Some(keys)) => Ok(keys.public),
+        (None, None) => Err(ApiError::crypto_missing_key_source())
+    }
+}
+
+/// Signs message prepared for signing with the signing box.
+/// Returns serialized message and its identifier.
+pub(crate) fn sign_message(message: MessageToSign, signing_box: SigningBoxHandle) -> ApiResult<(Vec<u8>, MessageId)> {
+    let (public_key, signature) = SigningBoxes::sign(signing_box, &message.data_to_sign)?;
+    ton_sdk::Contract::add_sign_to_message(&signature, &public_key, &message.message)
+        .map_err(|err| ApiError::contracts_encode_message_with_sign_failed(err))
+}
+
+#[cfg(feature = "node_interaction")]
+pub(crate) fn send_message(id: &MessageId, message: &[u8])
+    -> ApiResult<Box<dyn Stream<Item = Transaction, Error = SdkError>>>
+{
+    ton_sdk::Contract::send_serialized_message(id, message)
+        .map_err(|err| ApiError::contracts_send_message_failed(err))?;
+    ton_sdk::Contract::subscribe_transaction_processing(id)
+        .map_err(|err| ApiError::contracts_send_message_failed(err))
+}
+
 pub(crate) fn get_function_id(_context: &mut ClientContext, params: ParamsOfGetFunctionId) -> ApiResult<ResultOfGetFunctionId> {
     let contract = AbiContract::load(params.abi.to_string().as_bytes())
         .map_err(|err|ApiError::contracts_get_function_id_failed(err))?;
@@ -116,6 +195,35 @@ pub(crate) fn get_function_id(_context: &mut ClientContext, params: ParamsOfGetF
     })
 }
 
+pub(crate) fn validate_abi(_context: &mut ClientContext, params: ParamsOfValidateAbi) -> ApiResult<ResultOfValidateAbi> {
+    debug!("-> contracts.abi.validate()");
+
+    let errors = match params.abi {
+        serde_json::Value::String(text) => ton_sdk::validate_abi(&text),
+        abi => ton_sdk::validate_abi(&abi.to_string()),
+    };
+
+    debug!("<-");
+    Ok(ResultOfValidateAbi {
+        valid: errors.is_empty(),
+        errors,
+    })
+}
+
+pub(crate) fn get_abi_schema(_context: &mut ClientContext, params: ParamsOfGetAbiSchema) -> ApiResult<serde_json::Value> {
+    debug!("-> contracts.abi.schema()");
+
+    let abi = match params.abi {
+        serde_json::Value::String(text) => text,
+        abi => abi.to_string(),
+    };
+    let contract = AbiContract::load(abi.as_bytes())
+        .map_err(|err| ApiError::contracts_abi_schema_failed(err))?;
+
+    debug!("<-");
+    Ok(contract.json_schema())
+}
+
 pub(crate) fn get_code_from_image(_context: &mut ClientContext, params: ParamsOfGetCodeFromImage) -> ApiResult<ResultOfGetCodeFromImage> {
     debug!("-> contracts.image.code()");
 
@@ -140,6 +248,23 @@ pub(crate) fn convert_address(_context: &mut ClientContext, params: ParamsOfConv
     })
 }
 
+pub(crate) fn decode_contract_data(_context: &mut ClientContext, params: ParamsOfDecodeContractData) -> ApiResult<ResultOfDecodeContractData> {
+    debug!("-> contracts.data.decode()");
+
+    let data = base64_decode(&params.dataBase64)?;
+    let decoded = ton_sdk::Contract::decode_data_from_bytes_json(params.abi.to_string(), &data)
+        .map_err(|err| ApiError::contracts_decode_data_failed(err))?;
+    let public_key = ton_sdk::Contract::get_public_key_from_data(&data)
+        .map_err(|err| ApiError::contracts_decode_data_failed(err))?;
+
+    debug!("<-");
+    Ok(ResultOfDecodeContractData {
+        data: serde_json::from_str(&decoded)
+            .map_err(|err| ApiError::contracts_decode_data_failed(err))?,
+        publicKey: public_key.map(|key| hex::encode(&key)),
+    })
+}
+
 pub(crate) fn register(handlers: &mut DispatchTable) {
     // Load
     #[cfg(feature = "node_interaction")]
@@ -178,16 +303,81 @@ pub(crate) fn register(handlers: &mut DispatchTable) {
         run::get_run_body);
     handlers.spawn("contracts.run.local",
         run::local_run);
+    handlers.spawn("contracts.run.verify_signature",
+        run::verify_signature);
 
     // Contracts
     handlers.spawn("contracts.encode_message_with_sign",
         encode_message_with_sign);
     handlers.spawn("contracts.function.id",
         get_function_id);
+    handlers.spawn("contracts.abi.validate",
+        validate_abi);
+    handlers.spawn("contracts.abi.schema",
+        get_abi_schema);
     handlers.spawn("contracts.image.code",
         get_code_from_image);
+    handlers.spawn("contracts.data.decode",
+        decode_contract_data);
+    handlers.spawn("contracts.decode.any",
+        registry::decode_any);
+
+    // ABI registries
+    handlers.call_no_args("contracts.registry.create",
+        registry::create);
+    handlers.call("contracts.registry.add",
+        registry::add);
+    handlers.spawn("contracts.registry.decode",
+        registry::decode);
+    handlers.call("contracts.registry.drop",
+        registry::remove);
 
     // Addresses
     handlers.spawn("contracts.address.convert",
         convert_address);
+    handlers.spawn("contracts.address.from_state_init",
+        address::from_state_init);
+    handlers.spawn("contracts.address.from_code_and_data",
+        address::from_code_and_data);
+    handlers.spawn("contracts.address.from_image",
+        address::from_image);
+    handlers.spawn("contracts.address.parse",
+        address::parse);
+
+    // Account
+    handlers.spawn("contracts.account.info",
+        account::get_info);
+    handlers.spawn("contracts.account.verify_code",
+        account::verify_code);
+
+    // Multisig
+    handlers.spawn("contracts.multisig.submit",
+        multisig::submit_transaction);
+    handlers.spawn("contracts.multisig.confirm",
+        multisig::confirm_transaction);
+    handlers.spawn("contracts.multisig.sign",
+        multisig::sign);
+    handlers.spawn("contracts.multisig.custodian_call",
+        multisig::custodian_call);
+    handlers.spawn("contracts.multisig.add_signature",
+        multisig::add_signature);
+    handlers.spawn("contracts.multisig.verify",
+        multisig::verify);
+    handlers.spawn("contracts.multisig.messages",
+        multisig::get_messages);
+
+    // Upgrade
+    #[cfg(feature = "node_interaction")]
+    handlers.spawn("contracts.upgrade",
+        upgrade::upgrade);
+    #[cfg(feature = "node_interaction")]
+    handlers.spawn("contracts.upgrade.verify",
+        upgrade::verify);
+
+    handlers.spawn("contracts.upgrade.message",
+        upgrade::encode_message);
+    handlers.spawn("contracts.upgrade.encode_unsigned_message",
+        upgrade::encode_unsigned_message);
+    handlers.spawn("contracts.upgrade.compare",
+        upgrade::compare_code);
 }
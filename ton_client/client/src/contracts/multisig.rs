@@ -0,0 +1,200 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use ton_sdk::{MultisigPayload, CustodianCall, SignatureCheck};
+use crypto::keys::{KeyPair, account_decode};
+use crypto::keystore::KeyStore;
+use crypto::signing_box::{SigningBoxes, SigningBoxHandle};
+use types::{ApiResult, ApiError, hex_decode};
+
+use contracts::EncodedMessage;
+use client::ClientContext;
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfSubmitTransaction {
+    pub address: String,
+    pub dest: String,
+    pub value: serde_json::Value,
+    pub bounce: bool,
+    #[serde(default)]
+    pub allBalance: bool,
+    pub payloadBase64: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfConfirmTransaction {
+    pub address: String,
+    pub transactionId: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfSignPayload {
+    pub payload: MultisigPayload,
+    pub keyPair: Option<KeyPair>,
+    pub keystoreHandle: Option<String>,
+    pub signingBox: Option<SigningBoxHandle>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfAddSignature {
+    pub payload: MultisigPayload,
+    /// Message returned by `contracts.multisig.custodian_call` which is signed
+    pub call: CustodianCall,
+    pub publicKeyHex: String,
+    pub signatureHex: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfPayload {
+    pub payload: MultisigPayload,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfVerifySignatures {
+    pub signatures: Vec<SignatureCheck>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfGetMessages {
+    pub messages: Vec<EncodedMessage>,
+}
+
+// Accepts JSON number, decimal or `0x` prefixed hex string
+fn parse_number(name: &str, value: &serde_json::Value) -> ApiResult<u128> {
+    let result = match value {
+        serde_json::Value::Number(number) => number.as_u64().map(|number| number as u128),
+        serde_json::Value::String(string) => if string.starts_with("0x") {
+            u128::from_str_radix(&string[2..], 16).ok()
+        } else {
+            u128::from_str_radix(string, 10).ok()
+        },
+        _ => None
+    };
+    result.ok_or(ApiError::contracts_multisig_failed(
+        format!("invalid {}: {}", name, value)))
+}
+
+pub(crate) fn submit_transaction(_context: &mut ClientContext, params: ParamsOfSubmitTransaction) -> ApiResult<MultisigPayload> {
+    debug!("-> contracts.multisig.submit({}, {}, {})", params.address, params.dest, params.value);
+
+    let wallet = account_decode(&params.address)?;
+    let dest = account_decode(&params.dest)?;
+    let value = parse_number("value", &params.value)?;
+
+    let payload = MultisigPayload::submit_transaction(
+        &wallet, &dest, value, params.bounce, params.allBalance, params.payloadBase64.as_ref().map(|s| s.as_str()))
+        .map_err(|err| ApiError::contracts_multisig_failed(err))?;
+
+    debug!("<-");
+    Ok(payload)
+}
+
+pub(crate) fn confirm_transaction(_context: &mut ClientContext, params: ParamsOfConfirmTransaction) -> ApiResult<MultisigPayload> {
+    debug!("-> contracts.multisig.confirm({}, {})", params.address, params.transactionId);
+
+    let wallet = account_decode(&params.address)?;
+    let transaction_id = parse_number("transactionId", &params.transactionId)?;
+    if transaction_id > u64::max_value() as u128 {
+        return Err(ApiError::contracts_multisig_failed(
+            format!("invalid transactionId: {}", params.transactionId)));
+    }
+
+    let payload = MultisigPayload::confirm_transaction(&wallet, transaction_id as u64)
+        .map_err(|err| ApiError::contracts_multisig_failed(err))?;
+
+    debug!("<-");
+    Ok(payload)
+}
+
+pub(crate) fn sign(_context: &mut ClientContext, params: ParamsOfSignPayload) -> ApiResult<MultisigPayload> {
+    debug!("-> contracts.multisig.sign({})", params.payload.function);
+
+    let mut payload = params.payload;
+    if let Some(signing_box) = params.signingBox {
+        let call = payload.custodian_call()
+            .map_err(|err| ApiError::contracts_multisig_failed(err))?;
+        let data_to_sign = call.unsigned_hash()
+            .map_err(|err| ApiError::contracts_multisig_failed(err))?;
+        let (public_key, signature) = SigningBoxes::sign(signing_box, &data_to_sign)?;
+        payload.add_signature(call, &public_key, &signature)
+            .map_err(|err| ApiError::contracts_multisig_invalid_signature(err))?;
+    } else {
+        let keys = KeyStore::require_key_pair(&params.keyPair, &params.keystoreHandle)?;
+        payload.sign(&keys)
+            .map_err(|err| ApiError::contracts_multisig_failed(err))?;
+    }
+
+    debug!("<-");
+    Ok(payload)
+}
+
+pub(crate) fn custodian_call(_context: &mut ClientContext, params: ParamsOfPayload) -> ApiResult<CustodianCall> {
+    debug!("-> contracts.multisig.custodian_call({})", params.payload.function);
+
+    let call = params.payload.custodian_call()
+        .map_err(|err| ApiError::contracts_multisig_failed(err))?;
+
+    debug!("<-");
+    Ok(call)
+}
+
+pub(crate) fn add_signature(_context: &mut ClientContext, params: ParamsOfAddSignature) -> ApiResult<MultisigPayload> {
+    debug!("-> contracts.multisig.add_signature({})", params.publicKeyHex);
+
+    let public_key = hex_decode(&params.publicKeyHex)?;
+    let signature = hex_decode(&params.signatureHex)?;
+
+    let mut payload = params.payload;
+    payload.add_signature(params.call, &public_key, &signature)
+        .map_err(|err| ApiError::contracts_multisig_invalid_signature(err))?;
+
+    debug!("<-");
+    Ok(payload)
+}
+
+pub(crate) fn verify(_context: &mut ClientContext, params: ParamsOfPayload) -> ApiResult<ResultOfVerifySignatures> {
+    debug!("-> contracts.multisig.verify({})", params.payload.function);
+
+    let signatures = params.payload.verify_signatures()
+        .map_err(|err| ApiError::contracts_multisig_failed(err))?;
+
+    debug!("<-");
+    Ok(ResultOfVerifySignatures { signatures })
+}
+
+pub(crate) fn get_messages(_context: &mut ClientContext, params: ParamsOfPayload) -> ApiResult<ResultOfGetMessages> {
+    debug!("-> contracts.multisig.messages({})", params.payload.function);
+
+    let messages = params.payload.messages()
+        .map_err(|err| ApiError::contracts_multisig_invalid_signature(err))?;
+
+    let mut result = Vec::new();
+    for (body, id) in messages {
+        result.push(EncodedMessage {
+            messageId: id.to_string(),
+            messageIdBase64: id.to_base64().map_err(|err| ApiError::contracts_multisig_failed(err))?,
+            messageBodyBase64: base64::encode(&body),
+        });
+    }
+
+    debug!("<-");
+    Ok(ResultOfGetMessages { messages: result })
+}
@@ -0,0 +1,256 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use ton_sdk::{Contract, AbiRegistry, AbiBodyType, AbiValueFormat};
+use types::{ApiResult, ApiError, base64_decode, hex_decode};
+use client::ClientContext;
+
+pub type AbiRegistryHandle = u32;
+
+fn bool_false() -> bool { false }
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfRegistry {
+    pub registry: AbiRegistryHandle,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfRegistryAdd {
+    pub registry: AbiRegistryHandle,
+    pub name: String,
+    pub abi: serde_json::Value,
+    /// Hex encoded code hashes of contracts using this ABI
+    #[serde(default)]
+    pub codeHashes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct RegisteredAbi {
+    pub name: String,
+    pub abi: serde_json::Value,
+    /// Hex encoded code hashes of contracts using this ABI
+    #[serde(default)]
+    pub codeHashes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfDecodeAny {
+    pub abis: Vec<RegisteredAbi>,
+    pub bodyBase64: String,
+    /// Hex encoded code hash of the contract, restricts decoding to the ABI bound to it
+    pub codeHash: Option<String>,
+    /// Message kind of function call body
+    #[serde(default = "bool_false")]
+    pub internal: bool,
+    /// Detect message kind of function call body instead of using `internal`
+    #[serde(default = "bool_false")]
+    pub detectMessageKind: bool,
+    #[serde(flatten)]
+    pub format: AbiValueFormat,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfRegistryDecode {
+    pub registry: AbiRegistryHandle,
+    pub bodyBase64: String,
+    /// Hex encoded code hash of the contract, restricts decoding to the ABI bound to it
+    pub codeHash: Option<String>,
+    /// Message kind of function call body
+    #[serde(default = "bool_false")]
+    pub internal: bool,
+    /// Detect message kind of function call body instead of using `internal`
+    #[serde(default = "bool_false")]
+    pub detectMessageKind: bool,
+    #[serde(flatten)]
+    pub format: AbiValueFormat,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ResultOfRegistryDecode {
+    pub abiName: String,
+    pub bodyType: AbiBodyType,
+    /// Function or event name
+    pub name: String,
+    pub params: serde_json::Value,
+    /// Message kind of function call body, not set for other bodies
+    pub internal: Option<bool>,
+    /// Signer public key of external function call, signature is verified
+    pub publicKey: Option<String>,
+    pub time: Option<u64>,
+}
+
+/// ABI registries created by the client. Registry is kept between calls, so ABIs are parsed once
+/// and bodies are decoded by handle
+pub struct AbiRegistries {
+    next_handle: AbiRegistryHandle,
+    registries: HashMap<AbiRegistryHandle, AbiRegistry>,
+}
+
+lazy_static! {
+    static ref ABI_REGISTRIES: Mutex<AbiRegistries> = Mutex::new(AbiRegistries::new());
+}
+
+impl AbiRegistries {
+    fn new() -> Self {
+        Self {
+            next_handle: 1,
+            registries: HashMap::new(),
+        }
+    }
+
+    pub fn create() -> AbiRegistryHandle {
+        let mut registries = ABI_REGISTRIES.lock().unwrap();
+        let handle = free_handle(registries.next_handle, &registries.registries);
+        registries.next_handle = handle.wrapping_add(1);
+        registries.registries.insert(handle, AbiRegistry::new());
+        handle
+    }
+
+    pub fn remove(handle: AbiRegistryHandle) {
+        ABI_REGISTRIES.lock().unwrap().registries.remove(&handle);
+    }
+
+    /// Calls `f` with the registry locked, decoding doesn't call back into the caller
+    pub fn with<R, F>(handle: AbiRegistryHandle, f: F) -> ApiResult<R>
+    where
+        F: FnOnce(&mut AbiRegistry) -> ApiResult<R>
+    {
+        let mut registries = ABI_REGISTRIES.lock().unwrap();
+        let registry = registries.registries.get_mut(&handle)
+            .ok_or(ApiError::contracts_invalid_abi_registry_handle(handle))?;
+        f(registry)
+    }
+}
+
+pub(crate) fn create(_context: &mut ClientContext) -> ApiResult<AbiRegistryHandle> {
+    Ok(AbiRegistries::create())
+}
+
+pub(crate) fn add(_context: &mut ClientContext, params: ParamsOfRegistryAdd) -> ApiResult<()> {
+    debug!("-> contracts.registry.add({}, {})", params.registry, params.name);
+
+    let code_hashes = decode_code_hashes(&params.codeHashes)?;
+
+    AbiRegistries::with(params.registry, |registry| {
+        add_abi(registry, &params.name, &params.abi, &code_hashes)
+    })
+}
+
+pub(crate) fn decode(_context: &mut ClientContext, params: ParamsOfRegistryDecode) -> ApiResult<ResultOfRegistryDecode> {
+    debug!("-> contracts.registry.decode({}, {:?})", params.registry, params.codeHash);
+
+    let body = base64_decode(&params.bodyBase64)?;
+    let code_hash = match params.codeHash {
+        Some(ref code_hash) => Some(hex_decode(code_hash)?),
+        None => None,
+    };
+    let internal = if params.detectMessageKind { None } else { Some(params.internal) };
+
+    let result = AbiRegistries::with(params.registry, |registry| {
+        decode_body(registry, &body, code_hash.as_ref().map(|hash| hash.as_slice()), internal, params.format)
+    })?;
+
+    debug!("<-");
+    Ok(result)
+}
+
+/// Decodes body with a registry made of the given ABIs only for this call
+pub(crate) fn decode_any(_context: &mut ClientContext, params: ParamsOfDecodeAny) -> ApiResult<ResultOfRegistryDecode> {
+    debug!("-> contracts.decode.any({:?})", params.codeHash);
+
+    let mut registry = AbiRegistry::new();
+    for abi in &params.abis {
+        add_abi(&mut registry, &abi.name, &abi.abi, &decode_code_hashes(&abi.codeHashes)?)?;
+    }
+
+    let body = base64_decode(&params.bodyBase64)?;
+    let code_hash = match params.codeHash {
+        Some(ref code_hash) => Some(hex_decode(code_hash)?),
+        None => None,
+    };
+    let internal = if params.detectMessageKind { None } else { Some(params.internal) };
+
+    let result = decode_body(&registry, &body, code_hash.as_ref().map(|hash| hash.as_slice()), internal, params.format)?;
+
+    debug!("<-");
+    Ok(result)
+}
+
+pub(crate) fn remove(_context: &mut ClientContext, params: ParamsOfRegistry) -> ApiResult<()> {
+    AbiRegistries::remove(params.registry);
+    Ok(())
+}
+
+// Internals
+
+/// Returns first handle starting from `next` which is not used. After the counter wraps around
+/// handles of live registries are skipped, zero is never used
+pub(crate) fn free_handle<T>(next: AbiRegistryHandle, used: &HashMap<AbiRegistryHandle, T>) -> AbiRegistryHandle {
+    let mut handle = next;
+    while handle == 0 || used.contains_key(&handle) {
+        handle = handle.wrapping_add(1);
+    }
+    handle
+}
+
+fn decode_code_hashes(code_hashes: &[String]) -> ApiResult<Vec<Vec<u8>>> {
+    code_hashes.iter()
+        .map(|code_hash| hex_decode(code_hash))
+        .collect()
+}
+
+fn add_abi(registry: &mut AbiRegistry, name: &str, abi: &serde_json::Value, code_hashes: &[Vec<u8>]) -> ApiResult<()> {
+    registry.add_json(name, &abi.to_string())
+        .map_err(|err| ApiError::contracts_abi_registry_add_failed(err))?;
+    for code_hash in code_hashes {
+        registry.add_code_hash(name, code_hash)
+            .map_err(|err| ApiError::contracts_abi_registry_add_failed(err))?;
+    }
+    Ok(())
+}
+
+fn decode_body(
+    registry: &AbiRegistry,
+    body: &[u8],
+    code_hash: Option<&[u8]>,
+    internal: Option<bool>,
+    format: AbiValueFormat,
+) -> ApiResult<ResultOfRegistryDecode> {
+    let (decoded, json) = Contract::decode_any_message_from_bytes_json(registry, body, code_hash, internal, format)
+        .map_err(|err| ApiError::contracts_decode_any_failed(err))?;
+
+    Ok(ResultOfRegistryDecode {
+        internal: match decoded.body_type {
+            AbiBodyType::FunctionInput => Some(decoded.header.is_none()),
+            _ => None,
+        },
+        publicKey: decoded.header.as_ref()
+            .and_then(|header| header.public_key.as_ref())
+            .map(|key| hex::encode(&key.to_bytes())),
+        time: decoded.header.as_ref().and_then(|header| header.time),
+        abiName: decoded.abi_name,
+        bodyType: decoded.body_type,
+        name: decoded.name,
+        params: serde_json::from_str(&json)
+            .map_err(|err| ApiError::contracts_decode_any_failed(err))?,
+    })
+}
@@ -12,9 +12,13 @@
 * limitations under the License.
 */
 
-use ton_sdk::{Contract, MessageType, AbiContract};
-use ton_sdk::json_abi::encode_function_call;
+use ton_sdk::{Contract, MessageType, AbiContract, AbiValueFormat, AbiPagination, MessageId};
+use ton_block::MsgAddressInt;
+use ed25519_dalek::Keypair;
+use ton_sdk::json_abi::{encode_function_call, prepare_function_call_for_sign, add_sign_to_function_call};
 use crypto::keys::{KeyPair, account_decode};
+use crypto::keystore::KeyStore;
+use crypto::signing_box::{SigningBoxes, SigningBoxHandle};
 use types::{ApiResult, ApiError, base64_decode};
 use ton_types::cells_serialization::BagOfCells;
 
@@ -24,9 +28,7 @@ use client::ClientContext;
 #[cfg(feature = "node_interaction")]
 use ton_sdk::{Transaction, AbiFunction, Message};
 #[cfg(feature = "node_interaction")]
-use ton_block::{TransactionProcessingStatus, MsgAddressInt, AccStatusChange};
-#[cfg(feature = "node_interaction")]
-use ed25519_dalek::Keypair;
+use ton_block::{TransactionProcessingStatus, AccStatusChange};
 #[cfg(feature = "node_interaction")]
 use futures::Stream;
 
@@ -40,6 +42,8 @@ pub(crate) struct ParamsOfRun {
     pub functionName: String,
     pub input: serde_json::Value,
     pub keyPair: Option<KeyPair>,
+    pub keystoreHandle: Option<String>,
+    pub signingBox: Option<SigningBoxHandle>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -51,6 +55,13 @@ pub(crate) struct ParamsOfLocalRun {
     pub functionName: String,
     pub input: serde_json::Value,
     pub keyPair: Option<KeyPair>,
+    pub keystoreHandle: Option<String>,
+    pub signingBox: Option<SigningBoxHandle>,
+    /// Range of elements decoded for every output array and map: `offset` and `limit`
+    pub pagination: Option<AbiPagination>,
+    /// Output encodings: `intFormat`, `addressFormat`, `cellFormat` and `bytesFormat`
+    #[serde(flatten)]
+    pub format: AbiValueFormat,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -70,6 +81,11 @@ pub(crate) struct ParamsOfDecodeRunOutput {
     pub bodyBase64: String,
     #[serde(default = "bool_false")]
     pub internal: bool,
+    /// Range of elements decoded for every output array and map: `offset` and `limit`
+    pub pagination: Option<AbiPagination>,
+    /// Output encodings: `intFormat`, `addressFormat`, `cellFormat` and `bytesFormat`
+    #[serde(flatten)]
+    pub format: AbiValueFormat,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -77,8 +93,14 @@ pub(crate) struct ParamsOfDecodeRunOutput {
 pub struct ParamsOfDecodeUnknownRun {
     pub abi: serde_json::Value,
     pub bodyBase64: String,
+    /// Message kind of the body. Function outputs are decoded the same way for both kinds
     #[serde(default = "bool_false")]
     pub internal: bool,
+    /// Detect message kind of function call body instead of using `internal`
+    #[serde(default = "bool_false")]
+    pub detectMessageKind: bool,
+    #[serde(flatten)]
+    pub format: AbiValueFormat,
 }
 
 #[allow(non_snake_case)]
@@ -91,7 +113,29 @@ pub(crate) struct ResultOfRun {
 #[derive(Serialize, Deserialize)]
 pub struct ResultOfDecodeUnknownRun {
     pub function: String,
-    pub output: serde_json::Value
+    pub output: serde_json::Value,
+    /// Message kind the function call body is decoded with, not set for function outputs
+    pub internal: Option<bool>,
+    /// Signer public key of external function call, signature is verified
+    pub publicKey: Option<String>,
+    /// Timestamp of external function call
+    pub time: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfVerifySignature {
+    pub abi: serde_json::Value,
+    pub functionName: Option<String>,
+    pub bodyBase64: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ResultOfVerifySignature {
+    pub function: String,
+    pub valid: bool,
+    pub publicKey: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -103,6 +147,8 @@ pub(crate) struct ParamsOfGetRunBody {
     #[serde(default = "bool_false")]
     pub internal: bool,
     pub keyPair: Option<KeyPair>,
+    pub keystoreHandle: Option<String>,
+    pub signingBox: Option<SigningBoxHandle>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -120,13 +166,15 @@ pub(crate) fn run(_context: &mut ClientContext, params: ParamsOfRun) -> ApiResul
     );
 
     let address = account_decode(&params.address)?;
-    let key_pair = if let Some(ref keys) = params.keyPair { Some(keys.decode()?) } else { None };
+    let key_pair = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
 
     debug!("run contract");
     let tr = call_contract(address, &params, key_pair.as_ref())?;
 
-    let abi_contract = AbiContract::load(params.abi.to_string().as_bytes()).expect("Couldn't parse ABI");
-    let abi_function = abi_contract.function(&params.functionName).expect("Couldn't find function");
+    let abi_contract = AbiContract::load(params.abi.to_string().as_bytes())
+        .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
+    let abi_function = abi_contract.function(&params.functionName)
+        .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
 
     if  tr.out_messages_id().len() == 0 ||
         !abi_function.has_output()
@@ -165,10 +213,7 @@ pub(crate) fn local_run(_context: &mut ClientContext, params: ParamsOfLocalRun)
 
     let address = account_decode(&params.address)?;
 
-    let key_pair = match params.keyPair {
-        None => None,
-        Some(pair) => Some(pair.decode()?)
-    };
+    let key_pair = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
 
     let contract = match params.account {
         // load contract data from node manually
@@ -191,15 +236,28 @@ pub(crate) fn local_run(_context: &mut ClientContext, params: ParamsOfLocalRun)
         }
     };
 
-    let messages = contract.local_call_json(
-        params.functionName.clone(),
-        params.input.to_string(),
-        params.abi.to_string(),
-        key_pair.as_ref())
-       .map_err(|err| ApiError::contracts_local_run_failed(err))?;
-
-    let abi_contract = AbiContract::load(params.abi.to_string().as_bytes()).expect("Couldn't parse ABI");
-    let abi_function = abi_contract.function(&params.functionName).expect("Couldn't find function");
+    let messages = if let Some(signing_box) = params.signingBox {
+        let message = Contract::get_call_message_bytes_for_signing(
+            contract.address(),
+            params.functionName.clone(),
+            params.input.to_string(),
+            params.abi.to_string())
+            .map_err(|err| ApiError::contracts_create_run_message_failed(err))?;
+        let (message, _id) = super::sign_message(message, signing_box)?;
+        Contract::deserialize_message(&message)
+            .and_then(|message| contract.local_call(message))
+    } else {
+        contract.local_call_json(
+            params.functionName.clone(),
+            params.input.to_string(),
+            params.abi.to_string(),
+            key_pair.as_ref())
+    }.map_err(|err| ApiError::contracts_local_run_failed(err))?;
+
+    let abi_contract = AbiContract::load(params.abi.to_string().as_bytes())
+        .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
+    let abi_function = abi_contract.function(&params.functionName)
+        .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
 
     for msg in messages {
         if  msg.msg_type() == MessageType::ExternalOutbound &&
@@ -208,9 +266,13 @@ pub(crate) fn local_run(_context: &mut ClientContext, params: ParamsOfLocalRun)
                 false)
                     .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?
         {
-            let output = Contract::decode_function_response_json(
-                params.abi.to_string(), params.functionName, msg.body().expect("Message has no body"), false)
-                     .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
+            let body = msg.body().expect("Message has no body");
+            let output = match params.pagination {
+                Some(_) => Contract::decode_function_response_json_paginated(
+                    params.abi.to_string(), params.functionName, body, params.pagination, params.format),
+                None => Contract::decode_function_response_json_with_format(
+                    params.abi.to_string(), params.functionName, body, false, params.format),
+            }.map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
 
             let output: serde_json::Value = serde_json::from_str(&output)
                 .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
@@ -230,16 +292,9 @@ pub(crate) fn encode_message(_context: &mut ClientContext, params: ParamsOfRun)
     );
 
     let address = account_decode(&params.address)?;
-    let key_pair = if let Some(keys) = params.keyPair { Some(keys.decode()?) } else { None };
+    let key_pair = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
 
-    let (body, id) = Contract::construct_call_message_json(
-        address,
-        params.functionName.to_owned(),
-        params.input.to_string().to_owned(),
-        params.abi.to_string().to_owned(),
-        false,
-        key_pair.as_ref())
-        .map_err(|err| ApiError::contracts_create_run_message_failed(err))?;
+    let (body, id) = encode_run_message(address, &params, key_pair.as_ref())?;
 
     debug!("<-");
     Ok(EncodedMessage {
@@ -264,12 +319,20 @@ pub(crate) fn encode_unsigned_message(_context: &mut ClientContext, params: Para
 
 pub(crate) fn decode_output(_context: &mut ClientContext, params: ParamsOfDecodeRunOutput) -> ApiResult<ResultOfRun> {
     let body = base64_decode(&params.bodyBase64)?;
-    let result = Contract::decode_function_response_from_bytes_json(
-        params.abi.to_string().to_owned(),
-        params.functionName.to_owned(),
-        &body,
-        params.internal)
-            .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
+    let result = match params.pagination {
+        Some(_) => Contract::decode_function_response_from_bytes_json_paginated(
+            params.abi.to_string().to_owned(),
+            params.functionName.to_owned(),
+            &body,
+            params.pagination,
+            params.format),
+        None => Contract::decode_function_response_from_bytes_json_with_format(
+            params.abi.to_string().to_owned(),
+            params.functionName.to_owned(),
+            &body,
+            params.internal,
+            params.format),
+    }.map_err(|err| ApiError::contracts_decode_run_output_failed(err))?;
     Ok(ResultOfRun {
         output: serde_json::from_str(result.as_str())
             .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?
@@ -278,47 +341,84 @@ pub(crate) fn decode_output(_context: &mut ClientContext, params: ParamsOfDecode
 
 pub(crate) fn decode_unknown_input(_context: &mut ClientContext, params: ParamsOfDecodeUnknownRun) -> ApiResult<ResultOfDecodeUnknownRun> {
     let body = base64_decode(&params.bodyBase64)?;
-    let result = Contract::decode_unknown_function_call_from_bytes_json(
+    let internal = if params.detectMessageKind { None } else { Some(params.internal) };
+    let result = Contract::decode_unknown_function_call_with_header_from_bytes_json(
         params.abi.to_string().to_owned(),
         &body,
-        params.internal)
+        internal,
+        params.format)
             .map_err(|err|ApiError::contracts_decode_run_input_failed(err))?;
     Ok(ResultOfDecodeUnknownRun {
         function: result.function_name,
         output: serde_json::from_str(result.params.as_str())
-            .map_err(|err| ApiError::contracts_decode_run_input_failed(err))?
+            .map_err(|err| ApiError::contracts_decode_run_input_failed(err))?,
+        internal: Some(result.internal),
+        publicKey: result.public_key.map(|key| hex::encode(&key)),
+        time: result.time,
+    })
+}
+
+pub(crate) fn verify_signature(_context: &mut ClientContext, params: ParamsOfVerifySignature) -> ApiResult<ResultOfVerifySignature> {
+    debug!("-> contracts.run.verify_signature({:?})", params.functionName);
+
+    let body = base64_decode(&params.bodyBase64)?;
+    let result = Contract::verify_function_call_signature_from_bytes(
+        params.abi.to_string(),
+        params.functionName,
+        &body)
+            .map_err(|err| ApiError::contracts_verify_signature_failed(err))?;
+
+    debug!("<-");
+    Ok(ResultOfVerifySignature {
+        function: result.function_name,
+        valid: result.valid,
+        publicKey: hex::encode(&result.public_key),
     })
 }
 
 pub(crate) fn decode_unknown_output(_context: &mut ClientContext, params: ParamsOfDecodeUnknownRun) -> ApiResult<ResultOfDecodeUnknownRun> {
     let body = base64_decode(&params.bodyBase64)?;
-    let result = Contract::decode_unknown_function_response_from_bytes_json(
+    let result = Contract::decode_unknown_function_response_from_bytes_json_with_format(
         params.abi.to_string().to_owned(),
         &body,
-        params.internal)
+        params.internal,
+        params.format)
             .map_err(|err|ApiError::contracts_decode_run_output_failed(err))?;
     Ok(ResultOfDecodeUnknownRun {
         function: result.function_name,
         output: serde_json::from_str(result.params.as_str())
-            .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?
+            .map_err(|err| ApiError::contracts_decode_run_output_failed(err))?,
+        internal: None,
+        publicKey: None,
+        time: None,
     })
 }
 
 pub(crate) fn get_run_body(_context: &mut ClientContext, params: ParamsOfGetRunBody) -> ApiResult<ResultOfGetRunBody> {
     debug!("-> contracts.run.body({})", params.params.to_string());
 
-    let keys = match params.keyPair {
-        Some(str_pair) => Some(str_pair.decode()?),
-        None => None
-    };
+    let keys = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
 
-    let body = encode_function_call(
-        params.abi.to_string(),
-        params.function,
-        params.params.to_string(),
-        params.internal,
-        keys.as_ref())
-            .map_err(|err| ApiError::contracts_run_body_creation_failed(err))?;
+    let body = if let Some(signing_box) = params.signingBox {
+        if params.internal {
+            return Err(ApiError::contracts_run_body_creation_failed(
+                "internal message body is not signed, signing box can not be used"));
+        }
+        let (body, data_to_sign) = prepare_function_call_for_sign(
+            params.abi.to_string(),
+            params.function,
+            params.params.to_string())
+                .map_err(|err| ApiError::contracts_run_body_creation_failed(err))?;
+        let (public_key, signature) = SigningBoxes::sign(signing_box, &data_to_sign)?;
+        add_sign_to_function_call(&signature, &public_key, body.into())
+    } else {
+        encode_function_call(
+            params.abi.to_string(),
+            params.function,
+            params.params.to_string(),
+            params.internal,
+            keys.as_ref())
+    }.map_err(|err| ApiError::contracts_run_body_creation_failed(err))?;
 
     let mut data = Vec::new();
     let bag = BagOfCells::with_root(&body.into());
@@ -399,24 +499,52 @@ fn load_contract(address: &MsgAddressInt) -> ApiResult<Contract> {
     Contract::load_wait_deployed(address).map_err(|err| ApiError::contracts_run_contract_load_failed(err))
 }
 
+fn encode_run_message(address: MsgAddressInt, params: &ParamsOfRun, key_pair: Option<&Keypair>)
+    -> ApiResult<(Vec<u8>, MessageId)>
+{
+    if let Some(signing_box) = params.signingBox {
+        let message = Contract::get_call_message_bytes_for_signing(
+            address,
+            params.functionName.to_owned(),
+            params.input.to_string(),
+            params.abi.to_string())
+            .map_err(|err| ApiError::contracts_create_run_message_failed(err))?;
+        super::sign_message(message, signing_box)
+    } else {
+        Contract::construct_call_message_json(
+            address,
+            params.functionName.to_owned(),
+            params.input.to_string(),
+            params.abi.to_string(),
+            false,
+            key_pair)
+            .map_err(|err| ApiError::contracts_create_run_message_failed(err))
+    }
+}
+
 #[cfg(feature = "node_interaction")]
 fn call_contract(
     address: MsgAddressInt,
     params: &ParamsOfRun,
     key_pair: Option<&Keypair>,
 ) -> ApiResult<Transaction> {
-    let changes_stream = Contract::call_json(
-        address,
-        params.functionName.to_owned(),
-        params.input.to_string().to_owned(),
-        params.abi.to_string().to_owned(),
-        key_pair)
-        .expect("Error calling contract method");
+    let changes_stream = if params.signingBox.is_some() {
+        let (message, id) = encode_run_message(address, params, key_pair)?;
+        super::send_message(&id, &message)?
+    } else {
+        Contract::call_json(
+            address,
+            params.functionName.to_owned(),
+            params.input.to_string().to_owned(),
+            params.abi.to_string().to_owned(),
+            key_pair)
+            .map_err(|err| ApiError::contracts_send_message_failed(err))?
+    };
 
     let mut tr = None;
     for transaction in changes_stream.wait() {
-        if let Err(e) = transaction {
-            panic!("error next state getting: {}", e);
+        if let Err(err) = transaction {
+            return Err(ApiError::contracts_send_message_failed(err));
         }
         if let Ok(transaction) = transaction {
             debug!("run: {:?}", transaction.status);
@@ -0,0 +1,198 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use ton_sdk::{Contract, ContractImage, CodeComparison, MessageId};
+use ton_block::MsgAddressInt;
+use ed25519_dalek::Keypair;
+use crypto::keys::{KeyPair, account_decode};
+use crypto::keystore::KeyStore;
+use crypto::signing_box::SigningBoxHandle;
+use types::{ApiResult, ApiError};
+
+use contracts::{EncodedMessage, EncodedUnsignedMessage};
+use contracts::account::{decode_image, load_account};
+use client::ClientContext;
+
+#[cfg(feature = "node_interaction")]
+use ton_sdk::Transaction;
+#[cfg(feature = "node_interaction")]
+use ton_block::TransactionProcessingStatus;
+#[cfg(feature = "node_interaction")]
+use futures::Stream;
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfCompareCode {
+    pub address: String,
+    pub account: Option<serde_json::Value>,
+    pub imageBase64: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfUpgrade {
+    pub address: String,
+    pub abi: Option<serde_json::Value>,
+    pub imageBase64: String,
+    pub keyPair: Option<KeyPair>,
+    pub keystoreHandle: Option<String>,
+    pub signingBox: Option<SigningBoxHandle>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfEncodeUnsignedUpgradeMessage {
+    pub address: String,
+    pub abi: Option<serde_json::Value>,
+    pub imageBase64: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ParamsOfVerifyUpgrade {
+    pub address: String,
+    pub imageBase64: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfUpgrade {
+    pub transactionId: String,
+    pub codeHash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct ResultOfVerifyUpgrade {
+    pub codeHash: String,
+}
+
+pub(crate) fn compare_code(_context: &mut ClientContext, params: ParamsOfCompareCode) -> ApiResult<CodeComparison> {
+    debug!("-> contracts.upgrade.compare({})", params.address);
+
+    let image = decode_image(&params.imageBase64)?;
+    let contract = load_account(&params.address, params.account)?;
+
+    let result = contract.compare_code(&image)
+        .map_err(|err| ApiError::contracts_upgrade_failed(err))?;
+
+    debug!("<-");
+    Ok(result)
+}
+
+pub(crate) fn encode_message(_context: &mut ClientContext, params: ParamsOfUpgrade) -> ApiResult<EncodedMessage> {
+    debug!("-> contracts.upgrade.message({})", params.address);
+
+    let address = account_decode(&params.address)?;
+    let image = decode_image(&params.imageBase64)?;
+    let key_pair = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
+
+    let (body, id) = encode_upgrade_message(address, &image, &params, key_pair.as_ref())?;
+
+    debug!("<-");
+    Ok(EncodedMessage {
+        messageId: id.to_string(),
+        messageIdBase64: id.to_base64().map_err(|err| ApiError::contracts_upgrade_failed(err))?,
+        messageBodyBase64: base64::encode(&body),
+    })
+}
+
+pub(crate) fn encode_unsigned_message(_context: &mut ClientContext, params: ParamsOfEncodeUnsignedUpgradeMessage) -> ApiResult<EncodedUnsignedMessage> {
+    debug!("-> contracts.upgrade.encode_unsigned_message({})", params.address);
+
+    let image = decode_image(&params.imageBase64)?;
+    let encoded = Contract::get_upgrade_message_bytes_for_signing(
+        account_decode(&params.address)?,
+        &image,
+        params.abi.map(|abi| abi.to_string()),
+    ).map_err(|err| ApiError::contracts_upgrade_failed(err))?;
+
+    debug!("<-");
+    Ok(EncodedUnsignedMessage {
+        unsignedBytesBase64: base64::encode(&encoded.message),
+        bytesToSignBase64: base64::encode(&encoded.data_to_sign),
+    })
+}
+
+#[cfg(feature = "node_interaction")]
+pub(crate) fn upgrade(_context: &mut ClientContext, params: ParamsOfUpgrade) -> ApiResult<ResultOfUpgrade> {
+    debug!("-> contracts.upgrade({})", params.address);
+
+    let address = account_decode(&params.address)?;
+    let image = decode_image(&params.imageBase64)?;
+    let code_hash = image.code_hash()
+        .map_err(|err| ApiError::contracts_upgrade_failed(err))?;
+    let key_pair = KeyStore::decode_key_pair(&params.keyPair, &params.keystoreHandle)?;
+
+    debug!("-> -> upgrade");
+    let (message, id) = encode_upgrade_message(address.clone(), &image, &params, key_pair.as_ref())?;
+    let tr = wait_transaction(super::send_message(&id, &message)?)?;
+    debug!("-> -> upgrade transaction: {}", tr.id());
+    super::run::check_transaction_status(&tr)?;
+
+    debug!("-> -> verify code hash");
+    Contract::verify_code_hash(&address, &code_hash, Some(&tr))
+        .map_err(|err| ApiError::contracts_code_hash_mismatch(err))?;
+
+    debug!("<-");
+    Ok(ResultOfUpgrade {
+        transactionId: tr.id().to_string(),
+        codeHash: hex::encode(&code_hash),
+    })
+}
+
+#[cfg(feature = "node_interaction")]
+pub(crate) fn verify(_context: &mut ClientContext, params: ParamsOfVerifyUpgrade) -> ApiResult<ResultOfVerifyUpgrade> {
+    debug!("-> contracts.upgrade.verify({})", params.address);
+
+    let address = account_decode(&params.address)?;
+    let code_hash = decode_image(&params.imageBase64)?.code_hash()
+        .map_err(|err| ApiError::contracts_upgrade_failed(err))?;
+
+    Contract::verify_code_hash(&address, &code_hash, None)
+        .map_err(|err| ApiError::contracts_code_hash_mismatch(err))?;
+
+    debug!("<-");
+    Ok(ResultOfVerifyUpgrade { codeHash: hex::encode(&code_hash) })
+}
+
+// Internals
+
+fn encode_upgrade_message(address: MsgAddressInt, image: &ContractImage, params: &ParamsOfUpgrade, key_pair: Option<&Keypair>)
+    -> ApiResult<(Vec<u8>, MessageId)>
+{
+    let abi = params.abi.as_ref().map(|abi| abi.to_string());
+    if let Some(signing_box) = params.signingBox {
+        let message = Contract::get_upgrade_message_bytes_for_signing(address, image, abi)
+            .map_err(|err| ApiError::contracts_upgrade_failed(err))?;
+        super::sign_message(message, signing_box)
+    } else {
+        Contract::construct_upgrade_message(address, image, abi, key_pair)
+            .map_err(|err| ApiError::contracts_upgrade_failed(err))
+    }
+}
+
+// Waits for the finalized transaction, code hash can be checked only after the account state is committed
+#[cfg(feature = "node_interaction")]
+fn wait_transaction(changes_stream: Box<dyn Stream<Item = Transaction, Error = ton_sdk::SdkError>>) -> ApiResult<Transaction> {
+    for transaction in changes_stream.wait() {
+        let transaction = transaction
+            .map_err(|err| ApiError::contracts_send_message_failed(err))?;
+        debug!("-> -> upgrade: {:?}", transaction.status);
+        if transaction.status == TransactionProcessingStatus::Finalized {
+            return Ok(transaction);
+        }
+    }
+    Err(ApiError::contracts_run_transaction_missing())
+}
@@ -21,9 +21,44 @@ use byteorder::{BigEndian, ByteOrder, LittleEndian};
 use crypto::sha::sha256;
 use pbkdf2::pbkdf2;
 use secp256k1::{PublicKey, SecretKey};
+use serde::{Deserialize, Deserializer};
+
+/// Key derivation scheme. Selected by `compliant` flag which is either boolean
+/// (secp256k1 BIP32) or `"slip10"` (SLIP-0010 Ed25519).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HDKeyCompliance {
+    Legacy,
+    Bip32,
+    Slip10,
+}
+
+impl From<bool> for HDKeyCompliance {
+    fn from(compliant: bool) -> Self {
+        if compliant { HDKeyCompliance::Bip32 } else { HDKeyCompliance::Legacy }
+    }
+}
+
+impl<'de> Deserialize<'de> for HDKeyCompliance {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Flag {
+            Bool(bool),
+            Name(String),
+        }
 
-pub fn hdkey_xprv_from_mnemonic(phrase: &String) -> ApiResult<String> {
-    Ok(HDPrivateKey::from_mnemonic(phrase)?.serialize_to_string())
+        match Flag::deserialize(deserializer)? {
+            Flag::Bool(compliant) => Ok(compliant.into()),
+            Flag::Name(ref name) if name == "bip32" => Ok(HDKeyCompliance::Bip32),
+            Flag::Name(ref name) if name == "slip10" => Ok(HDKeyCompliance::Slip10),
+            Flag::Name(name) => Err(serde::de::Error::custom(
+                format!("unknown derivation scheme `{}`", name))),
+        }
+    }
+}
+
+pub fn hdkey_xprv_from_mnemonic(phrase: &String, compliant: HDKeyCompliance) -> ApiResult<String> {
+    Ok(HDPrivateKey::from_mnemonic(phrase, compliant)?.serialize_to_string())
 }
 
 pub fn hdkey_secret_from_xprv(serialized: &String) -> ApiResult<String> {
@@ -32,19 +67,21 @@ pub fn hdkey_secret_from_xprv(serialized: &String) -> ApiResult<String> {
     ))
 }
 
-pub fn hdkey_public_from_xprv(serialized: &String) -> ApiResult<String> {
-    Ok(hex::encode(
-        HDPrivateKey::from_serialized_string(serialized)?
-            .public()
-            .as_ref(),
-    ))
+pub fn hdkey_public_from_xprv(serialized: &String, compliant: HDKeyCompliance) -> ApiResult<String> {
+    let xprv = HDPrivateKey::from_serialized_string(serialized)?;
+    xprv.check_compliance(compliant)?;
+    if compliant == HDKeyCompliance::Slip10 {
+        Ok(hex::encode(xprv.ed25519_public()?.as_bytes()))
+    } else {
+        Ok(hex::encode(xprv.public().as_ref()))
+    }
 }
 
 pub fn hdkey_derive_from_xprv(
     serialized: &String,
     child_index: u32,
     hardened: bool,
-    compliant: bool,
+    compliant: HDKeyCompliance,
 ) -> ApiResult<String> {
     let xprv = HDPrivateKey::from_serialized_string(serialized)?;
     let derived = xprv.derive(child_index, hardened, compliant)?;
@@ -56,7 +93,7 @@ pub fn hdkey_derive_from_xprv(
 pub fn hdkey_derive_from_xprv_path(
     serialized: &String,
     path: &String,
-    compliant: bool,
+    compliant: HDKeyCompliance,
 ) -> ApiResult<String> {
     let xprv = HDPrivateKey::from_serialized_string(serialized)?;
     Ok(xprv.derive_path(path, compliant)?.serialize_to_string())
@@ -69,22 +106,31 @@ pub(crate) struct HDPrivateKey {
     child_number: [u8; 4],
     child_chain: Key256,
     key: Key256,
+    // SLIP-0010 Ed25519 key, can not be derived as secp256k1 one and vice versa
+    slip10: bool,
 }
 
 static XPRV_VERSION: [u8; 4] = [0x04, 0x88, 0xAD, 0xE4];
+// There is no registered version for Ed25519 extended keys, so the one not used by
+// other schemes is taken to tell SLIP-0010 keys from BIP32 ones
+static SLIP10_XPRV_VERSION: [u8; 4] = [0x04, 0x88, 0xAD, 0xE5];
+
+const BIP32_SEED_KEY: &[u8] = b"Bitcoin seed";
+const SLIP10_ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
 
 impl HDPrivateKey {
-    fn master(child_chain: &Key256, key: &Key256) -> HDPrivateKey {
+    fn master(child_chain: &Key256, key: &Key256, slip10: bool) -> HDPrivateKey {
         HDPrivateKey {
             depth: 0,
             parent_fingerprint: [0; 4],
             child_number: [0; 4],
             child_chain: *child_chain,
             key: *key,
+            slip10,
         }
     }
 
-    pub fn from_mnemonic(phrase: &String) -> ApiResult<HDPrivateKey> {
+    pub fn from_mnemonic(phrase: &String, compliant: HDKeyCompliance) -> ApiResult<HDPrivateKey> {
         let salt = "mnemonic";
         let mut seed = vec![0u8; 64];
         pbkdf2::<Hmac<Sha512>>(
@@ -93,15 +139,36 @@ impl HDPrivateKey {
             2048,
             &mut seed,
         );
-        let mut hmac: Hmac<Sha512> = Hmac::new_varkey(b"Bitcoin seed").unwrap();
-        hmac.input(&seed);
+        Self::from_seed(&seed, compliant)
+    }
+
+    pub fn from_seed(seed: &[u8], compliant: HDKeyCompliance) -> ApiResult<HDPrivateKey> {
+        let seed_key = if compliant == HDKeyCompliance::Slip10 {
+            SLIP10_ED25519_SEED_KEY
+        } else {
+            BIP32_SEED_KEY
+        };
+        let mut hmac: Hmac<Sha512> = Hmac::new_varkey(seed_key).unwrap();
+        hmac.input(seed);
         let child_chain_with_key = key512(&hmac.result().code())?;
         Ok(HDPrivateKey::master(
             &key256(&child_chain_with_key[32..])?,
             &key256(&child_chain_with_key[..32])?,
+            compliant == HDKeyCompliance::Slip10,
         ))
     }
 
+    /// Checks that the key was created by the same scheme, SLIP-0010 and BIP32 keys
+    /// can not be used one instead of another
+    fn check_compliance(&self, compliant: HDKeyCompliance) -> ApiResult<()> {
+        if self.slip10 != (compliant == HDKeyCompliance::Slip10) {
+            return Err(ApiError::crypto_bip32_invalid_key(format!(
+                "{} key can not be used with {:?} derivation",
+                if self.slip10 { "SLIP-0010" } else { "BIP32" }, compliant)));
+        }
+        Ok(())
+    }
+
     pub(crate) fn secret(&self) -> Key256 {
         self.key
     }
@@ -112,6 +179,16 @@ impl HDPrivateKey {
         public_key.serialize_compressed()
     }
 
+    pub(crate) fn chain_code(&self) -> Key256 {
+        self.child_chain
+    }
+
+    fn ed25519_public(&self) -> ApiResult<ed25519_dalek::PublicKey> {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&self.key)
+            .map_err(|_| ApiError::crypto_bip32_invalid_key(hex::encode(&self.key)))?;
+        Ok(ed25519_dalek::PublicKey::from_secret::<Sha512>(&secret))
+    }
+
     fn map_secp_error(error: secp256k1::Error) -> ApiError {
         match error {
             secp256k1::Error::InvalidSignature => {
@@ -142,8 +219,13 @@ impl HDPrivateKey {
         &self,
         child_index: u32,
         hardened: bool,
-        compliant: bool,
+        compliant: HDKeyCompliance,
     ) -> ApiResult<HDPrivateKey> {
+        self.check_compliance(compliant)?;
+        if compliant == HDKeyCompliance::Slip10 {
+            return self.derive_slip10(child_index, hardened);
+        }
+
         let mut child: HDPrivateKey = Default::default();
         child.depth = self.depth + 1;
 
@@ -166,7 +248,7 @@ impl HDPrivateKey {
             .map_err(|err| ApiError::crypto_bip32_invalid_key(err))?;
 
         let secret_key = SecretKey::parse(&self.key).unwrap();
-        if hardened && !compliant {
+        if hardened && compliant == HDKeyCompliance::Legacy {
             // The private key serialization in this case will not be exactly 32 bytes and can be
             // any value less, and the value is not zero-padded.
             hmac.input(&[0]);
@@ -195,7 +277,40 @@ impl HDPrivateKey {
         Ok(child)
     }
 
-    pub fn derive_path(&self, path: &String, compliant: bool) -> ApiResult<HDPrivateKey> {
+    /// SLIP-0010 Ed25519 child key derivation. Ed25519 supports hardened derivation only,
+    /// child key is taken from HMAC output as is.
+    fn derive_slip10(&self, child_index: u32, hardened: bool) -> ApiResult<HDPrivateKey> {
+        if !hardened {
+            return Err(ApiError::crypto_bip32_invalid_derive_path(
+                format!("{} (SLIP-0010 Ed25519 requires hardened derivation)", child_index)));
+        }
+
+        let mut child: HDPrivateKey = Default::default();
+        child.depth = self.depth + 1;
+        child.slip10 = true;
+
+        let mut public = vec![0u8];
+        public.extend_from_slice(self.ed25519_public()?.as_bytes());
+        let fingerprint = Ripemd160::new().update(&sha256(&public)).digest();
+        child.parent_fingerprint.copy_from_slice(&fingerprint[0..4]);
+
+        BigEndian::write_u32(&mut child.child_number, 0x80000000 | child_index);
+
+        let mut hmac: Hmac<Sha512> = Hmac::new_varkey(&self.child_chain)
+            .map_err(|err| ApiError::crypto_bip32_invalid_key(err))?;
+        hmac.input(&[0]);
+        hmac.input(&self.key);
+        hmac.input(&child.child_number);
+        let result = hmac.result().code();
+        let (child_key_bytes, chain_code) = result.split_at(32);
+
+        child.key.copy_from_slice(&child_key_bytes);
+        child.child_chain.copy_from_slice(&chain_code);
+        Ok(child)
+    }
+
+    pub fn derive_path(&self, path: &String, compliant: HDKeyCompliance) -> ApiResult<HDPrivateKey> {
+        self.check_compliance(compliant)?;
         let mut child: HDPrivateKey = self.clone();
         for step in path.split("/") {
             if step == "m" {
@@ -222,10 +337,11 @@ impl HDPrivateKey {
         }
         let mut version = [0u8; 4];
         version.clone_from_slice(&bytes[0..4]);
-        if version != XPRV_VERSION {
+        if version != XPRV_VERSION && version != SLIP10_XPRV_VERSION {
             return Err(ApiError::crypto_bip32_invalid_key(bytes.to_base58()));
         }
         let mut xprv: HDPrivateKey = Default::default();
+        xprv.slip10 = version == SLIP10_XPRV_VERSION;
         xprv.depth = bytes[4];
         xprv.parent_fingerprint.copy_from_slice(&bytes[5..9]);
         xprv.child_number.copy_from_slice(&bytes[9..13]);
@@ -239,7 +355,7 @@ impl HDPrivateKey {
 
     fn serialize(&self) -> Vec<u8> {
         let mut bytes: Vec<u8> = Vec::new();
-        bytes.extend(&XPRV_VERSION);
+        bytes.extend(if self.slip10 { &SLIP10_XPRV_VERSION } else { &XPRV_VERSION });
         bytes.push(self.depth);
         bytes.extend(&self.parent_fingerprint);
         bytes.extend(&self.child_number);
@@ -258,7 +374,7 @@ impl HDPrivateKey {
         )
     }
 
-    fn serialize_to_string(&self) -> String {
+    pub(crate) fn serialize_to_string(&self) -> String {
         self.serialize().to_base58()
     }
 }
@@ -12,16 +12,13 @@
 * limitations under the License.
 */
 
-use std::sync::Mutex;
 use ton_block::MsgAddressInt;
 use ed25519_dalek::{Keypair, PublicKey, SecretKey};
-use types::{ApiResult, ApiError, hex_decode};
-use std::collections::HashMap;
+use types::{ApiResult, ApiError};
 use base64::URL_SAFE;
 use hmac::*;
 use sha2::Sha512;
 use crypto::math::ton_crc16;
-use std::str::FromStr;
 
 pub type Key192 = [u8; 24];
 pub type Key256 = [u8; 32];
@@ -48,64 +45,6 @@ impl KeyPair {
     }
 }
 
-type KeyPairHandle = String;
-
-pub struct KeyStore {
-    next_handle: u32,
-    keys: HashMap<KeyPairHandle, KeyPair>
-}
-
-lazy_static! {
-    static ref KEY_STORE: Mutex<KeyStore> = Mutex::new(KeyStore::new());
-}
-
-impl KeyStore {
-    pub fn new() -> KeyStore {
-        KeyStore {
-            next_handle: 1,
-            keys: HashMap::new(),
-        }
-    }
-
-    pub fn add(keys: &KeyPair) -> KeyPairHandle {
-        let mut store = KEY_STORE.lock().unwrap();
-        let handle: String = format!("{:x}", store.next_handle);
-        store.next_handle += 1;
-        store.keys.insert(handle.clone(), (*keys).clone());
-        handle
-    }
-
-    pub fn get(handle: &KeyPairHandle) -> Option<KeyPair> {
-        let store = KEY_STORE.lock().unwrap();
-        store.keys.get(handle).map(|key_ref|(*key_ref).clone())
-    }
-
-    pub fn remove(handle: &KeyPairHandle) {
-        let mut store = KEY_STORE.lock().unwrap();
-        store.keys.remove(handle);
-    }
-
-    pub fn clear() {
-        let mut store = KEY_STORE.lock().unwrap();
-        store.keys.clear();
-    }
-
-    pub fn decode_secret(secret: &Option<String>, handle: &Option<String>) -> ApiResult<Vec<u8>> {
-        if let Some(secret) = secret {
-            hex_decode(secret)
-        } else if let Some(handle) = handle {
-            if let Some(keys) = Self::get(handle) {
-                hex_decode(&keys.secret)
-            } else {
-                Err(ApiError::crypto_invalid_keystore_handle())
-            }
-        } else {
-            Err(ApiError::crypto_missing_key_source())
-        }
-    }
-}
-
-
 pub fn decode_public_key(string: &String) -> ApiResult<PublicKey> {
     PublicKey::from_bytes(parse_key(string)?.as_slice())
         .map_err(|err| ApiError::crypto_invalid_public_key(err, string))
@@ -151,16 +90,13 @@ pub(crate) fn account_encode_ex(
     }
 }
 
+// Bare account id is placed into the basic workchain
 pub fn account_decode(string: &str) -> ApiResult<MsgAddressInt> {
-    match MsgAddressInt::from_str(string) {
-        Ok(address) => {
-            match address {
-                MsgAddressInt::AddrNone => Err(ApiError::crypto_invalid_address("Empty address not allowed", string)),
-                _ => Ok(address)
-            }
+    match ton_sdk::Address::parse_with_workchain(string, 0) {
+        Ok(address) => match address.address {
+            MsgAddressInt::AddrNone => Err(ApiError::crypto_invalid_address("Empty address not allowed", string)),
+            address => Ok(address)
         },
-        Err(_) if string.len() == 48 => ton_sdk::decode_std_base64(string)
-        .map_err(|err| ApiError::crypto_invalid_address(err, string)),
         Err(err) => Err(ApiError::crypto_invalid_address(err, string))
     }
 }
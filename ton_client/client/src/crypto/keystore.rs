@@ -0,0 +1,451 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{compiler_fence, Ordering};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use crypto::keys::KeyPair;
+use crypto::nacl::{secret_box, secret_box_open};
+use crypto::random::generate_bytes;
+use crypto::scrypt::scrypt;
+use types::{ApiResult, ApiError, hex_decode};
+
+pub type KeyPairHandle = String;
+
+const KEYSTORE_VERSION: u8 = 1;
+const SCRYPT_LOG_N: u8 = 14;
+// keystores encrypted with cheaper parameters are accepted only to be re-encrypted on unlock
+const SCRYPT_MIN_LOG_N: u8 = 12;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+/// Overwrites bytes with zeros in a way the compiler can not optimize out
+pub(crate) fn zeroize(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+pub(crate) fn zeroize_string(string: String) {
+    zeroize(&mut string.into_bytes());
+}
+
+/// Byte buffer which is zeroized when dropped
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        SecretBytes(bytes)
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        zeroize(&mut self.0);
+    }
+}
+
+/// Storage of encrypted keystore content.
+///
+/// The keystore never passes unencrypted secrets to the storage.
+pub trait KeyStorage: Send {
+    /// Returns previously saved content or `None` if nothing was saved yet
+    fn load(&self) -> ApiResult<Option<String>>;
+    fn save(&self, content: &str) -> ApiResult<()>;
+}
+
+/// Keeps encrypted keystore content in a file
+pub struct FileKeyStorage {
+    path: PathBuf,
+}
+
+impl FileKeyStorage {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl KeyStorage for FileKeyStorage {
+    fn load(&self) -> ApiResult<Option<String>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(&self.path)
+            .map(Some)
+            .map_err(|err| ApiError::crypto_keystore_storage_failed(err))
+    }
+
+    fn save(&self, content: &str) -> ApiResult<()> {
+        // write to temporary file first to not corrupt existing keystore on failure
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, content)
+            .and_then(|_| fs::rename(&temp_path, &self.path))
+            .map_err(|err| ApiError::crypto_keystore_storage_failed(err))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+struct EncryptedContent {
+    version: u8,
+    logN: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+    nonce: String,
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(non_snake_case)]
+struct KeyStoreContent {
+    nextHandle: u32,
+    keys: HashMap<KeyPairHandle, KeyPair>,
+}
+
+pub(crate) struct KeyStoreEntry {
+    pub public: String,
+    pub secret: SecretBytes,
+}
+
+impl KeyStoreEntry {
+    fn from_key_pair(keys: &KeyPair) -> ApiResult<Self> {
+        Ok(Self {
+            public: keys.public.clone(),
+            secret: SecretBytes::new(hex_decode(&keys.secret)?),
+        })
+    }
+
+    fn decode(&self, handle: &KeyPairHandle) -> ApiResult<Keypair> {
+        let public = hex_decode(&self.public)?;
+        Ok(Keypair {
+            public: PublicKey::from_bytes(&public)
+                .map_err(|err| ApiError::crypto_invalid_public_key(err, &self.public))?,
+            secret: SecretKey::from_bytes(self.secret.as_slice())
+                .map_err(|err| ApiError::crypto_invalid_secret_key(err, handle))?,
+        })
+    }
+}
+
+struct Storage {
+    storage: Box<dyn KeyStorage>,
+    salt: Vec<u8>,
+    key: Option<SecretBytes>,
+}
+
+/// Keeps key pairs available by handles.
+///
+/// Without storage the keys live in memory only. After storage is attached with `open`
+/// every change is saved to it encrypted by the key derived from the password
+/// with scrypt. Locked keystore zeroizes all secrets in memory until it is unlocked
+/// with the same password.
+pub struct KeyStore {
+    next_handle: u32,
+    keys: HashMap<KeyPairHandle, KeyStoreEntry>,
+    storage: Option<Storage>,
+}
+
+lazy_static! {
+    static ref KEY_STORE: Mutex<KeyStore> = Mutex::new(KeyStore::new());
+}
+
+impl KeyStore {
+    pub fn new() -> KeyStore {
+        KeyStore {
+            next_handle: 1,
+            keys: HashMap::new(),
+            storage: None,
+        }
+    }
+
+    fn derive_key(password: &[u8], salt: &[u8], log_n: u8, r: u32, p: u32) -> ApiResult<SecretBytes> {
+        Ok(SecretBytes::new(scrypt(password, salt, log_n, r, p, KEY_LEN)?))
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.storage.as_ref().map(|storage| storage.key.is_none()).unwrap_or(false)
+    }
+
+    fn check_unlocked(&self) -> ApiResult<()> {
+        if self.is_locked() {
+            Err(ApiError::crypto_keystore_locked())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn load_content(key: &SecretBytes, encrypted: &EncryptedContent) -> ApiResult<KeyStoreContent> {
+        let data = SecretBytes::new(secret_box_open(
+            &hex_decode(&encrypted.data)?,
+            &hex_decode(&encrypted.nonce)?,
+            key.as_slice())
+                .map_err(|_| ApiError::crypto_keystore_invalid_password())?);
+        serde_json::from_slice(data.as_slice())
+            .map_err(|err| ApiError::crypto_keystore_storage_failed(err))
+    }
+
+    fn read_encrypted(storage: &dyn KeyStorage) -> ApiResult<Option<EncryptedContent>> {
+        match storage.load()? {
+            Some(content) => {
+                let encrypted: EncryptedContent = serde_json::from_str(&content)
+                    .map_err(|err| ApiError::crypto_keystore_storage_failed(err))?;
+                if encrypted.version != KEYSTORE_VERSION {
+                    return Err(ApiError::crypto_keystore_storage_failed(
+                        format!("unsupported keystore version {}", encrypted.version)));
+                }
+                // stored parameters are not trusted: too costly ones would exhaust memory or CPU,
+                // too cheap ones would make the password easy to brute force
+                if encrypted.logN < SCRYPT_MIN_LOG_N || encrypted.logN > SCRYPT_LOG_N
+                    || encrypted.r != SCRYPT_R || encrypted.p != SCRYPT_P
+                {
+                    return Err(ApiError::crypto_keystore_storage_failed(format!(
+                        "scrypt parameters logN={}, r={}, p={} are not supported, expected logN={}, r={}, p={}",
+                        encrypted.logN, encrypted.r, encrypted.p, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)));
+                }
+                Ok(Some(encrypted))
+            }
+            None => Ok(None)
+        }
+    }
+
+    fn has_default_params(encrypted: &EncryptedContent) -> bool {
+        encrypted.logN == SCRYPT_LOG_N && encrypted.r == SCRYPT_R && encrypted.p == SCRYPT_P
+    }
+
+    /// Saves content encrypted by the key derived from password with new salt and default
+    /// scrypt parameters. Previous salt and key are kept if saving fails.
+    fn reencrypt(&mut self, password: &[u8]) -> ApiResult<()> {
+        let salt = generate_bytes(SALT_LEN);
+        let key = Self::derive_key(password, &salt, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)?;
+        let (old_salt, old_key) = match self.storage {
+            Some(ref mut storage) => (
+                std::mem::replace(&mut storage.salt, salt),
+                std::mem::replace(&mut storage.key, Some(key))),
+            None => return Ok(())
+        };
+        let result = self.save();
+        if result.is_err() {
+            if let Some(ref mut storage) = self.storage {
+                storage.salt = old_salt;
+                storage.key = old_key;
+            }
+        }
+        result
+    }
+
+    fn apply_content(&mut self, content: KeyStoreContent) -> ApiResult<()> {
+        let mut keys = HashMap::new();
+        for (handle, pair) in content.keys.into_iter() {
+            let entry = KeyStoreEntry::from_key_pair(&pair);
+            zeroize_string(pair.secret);
+            keys.insert(handle, entry?);
+        }
+        self.keys = keys;
+        self.next_handle = content.nextHandle;
+        Ok(())
+    }
+
+    fn save(&self) -> ApiResult<()> {
+        let storage = match self.storage {
+            Some(ref storage) => storage,
+            None => return Ok(())
+        };
+        let key = storage.key.as_ref().ok_or(ApiError::crypto_keystore_locked())?;
+
+        let content = KeyStoreContent {
+            nextHandle: self.next_handle,
+            keys: self.keys.iter()
+                .map(|(handle, entry)| (
+                    handle.clone(),
+                    KeyPair::new(entry.public.clone(), hex::encode(entry.secret.as_slice()))))
+                .collect(),
+        };
+        let data = SecretBytes::new(serde_json::to_vec(&content)
+            .map_err(|err| ApiError::crypto_keystore_storage_failed(err))?);
+        for (_, pair) in content.keys.into_iter() {
+            zeroize_string(pair.secret);
+        }
+
+        let nonce = generate_bytes(NONCE_LEN);
+        let encrypted = secret_box(data.as_slice(), &nonce, key.as_slice())?;
+        let encrypted = EncryptedContent {
+            version: KEYSTORE_VERSION,
+            logN: SCRYPT_LOG_N,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt: hex::encode(&storage.salt),
+            nonce: hex::encode(&nonce),
+            data: hex::encode(&encrypted),
+        };
+        storage.storage.save(&serde_json::to_string(&encrypted)
+            .map_err(|err| ApiError::crypto_keystore_storage_failed(err))?)
+    }
+
+    /// Attaches storage to the keystore. If storage already has content it is loaded, keys
+    /// in memory which are not saved to any storage are not dropped silently: opening fails
+    /// until they are deleted. Empty storage receives keys in memory. Storage encrypted with
+    /// cheaper scrypt parameters is re-encrypted with the default ones.
+    pub fn open_storage(&mut self, storage: Box<dyn KeyStorage>, password: &[u8]) -> ApiResult<()> {
+        match Self::read_encrypted(storage.as_ref())? {
+            Some(encrypted) => {
+                if self.storage.is_none() && !self.keys.is_empty() {
+                    return Err(ApiError::crypto_keystore_storage_failed(format!(
+                        "keystore has {} keys which are not saved to storage, delete them before opening",
+                        self.keys.len())));
+                }
+                let salt = hex_decode(&encrypted.salt)?;
+                let key = Self::derive_key(password, &salt, encrypted.logN, encrypted.r, encrypted.p)?;
+                let content = Self::load_content(&key, &encrypted)?;
+                self.apply_content(content)?;
+                self.storage = Some(Storage { storage, salt, key: Some(key) });
+                if !Self::has_default_params(&encrypted) {
+                    self.reencrypt(password)?;
+                }
+            }
+            None => {
+                let salt = generate_bytes(SALT_LEN);
+                let key = Self::derive_key(password, &salt, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)?;
+                self.storage = Some(Storage { storage, salt, key: Some(key) });
+                self.save()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Zeroizes all secrets and the encryption key in memory
+    pub fn lock_storage(&mut self) -> ApiResult<()> {
+        let storage = self.storage.as_mut().ok_or(ApiError::crypto_keystore_storage_failed(
+            "keystore has no storage attached"))?;
+        storage.key = None;
+        self.keys.clear();
+        Ok(())
+    }
+
+    /// Loads secrets from the storage decrypting them with the key derived from password.
+    /// Storage encrypted with cheaper scrypt parameters is re-encrypted with the default ones.
+    pub fn unlock_storage(&mut self, password: &[u8]) -> ApiResult<()> {
+        let (content, encrypted) = {
+            let storage = self.storage.as_mut().ok_or(ApiError::crypto_keystore_storage_failed(
+                "keystore has no storage attached"))?;
+            let encrypted = Self::read_encrypted(storage.storage.as_ref())?
+                .ok_or(ApiError::crypto_keystore_storage_failed("keystore storage is empty"))?;
+            if hex_decode(&encrypted.salt)? != storage.salt {
+                return Err(ApiError::crypto_keystore_storage_failed("keystore storage was replaced"));
+            }
+            let key = Self::derive_key(password, &storage.salt, encrypted.logN, encrypted.r, encrypted.p)?;
+            let content = Self::load_content(&key, &encrypted)?;
+            storage.key = Some(key);
+            (content, encrypted)
+        };
+        self.apply_content(content)?;
+        if !Self::has_default_params(&encrypted) {
+            self.reencrypt(password)?;
+        }
+        Ok(())
+    }
+
+    pub fn insert(&mut self, keys: &KeyPair) -> ApiResult<KeyPairHandle> {
+        self.check_unlocked()?;
+        let entry = KeyStoreEntry::from_key_pair(keys)?;
+        let handle: String = format!("{:x}", self.next_handle);
+        self.next_handle += 1;
+        self.keys.insert(handle.clone(), entry);
+        self.save()?;
+        Ok(handle)
+    }
+
+    pub fn delete(&mut self, handle: &KeyPairHandle) -> ApiResult<()> {
+        self.check_unlocked()?;
+        self.keys.remove(handle);
+        self.save()
+    }
+
+    pub fn delete_all(&mut self) -> ApiResult<()> {
+        self.check_unlocked()?;
+        self.keys.clear();
+        self.save()
+    }
+
+    pub(crate) fn entry(&self, handle: &KeyPairHandle) -> ApiResult<&KeyStoreEntry> {
+        self.check_unlocked()?;
+        self.keys.get(handle).ok_or(ApiError::crypto_invalid_keystore_handle())
+    }
+
+    pub fn key_pair(&self, handle: &KeyPairHandle) -> ApiResult<Keypair> {
+        self.entry(handle)?.decode(handle)
+    }
+
+    // Shared keystore
+
+    pub fn add(keys: &KeyPair) -> ApiResult<KeyPairHandle> {
+        KEY_STORE.lock().unwrap().insert(keys)
+    }
+
+    pub fn remove(handle: &KeyPairHandle) -> ApiResult<()> {
+        KEY_STORE.lock().unwrap().delete(handle)
+    }
+
+    pub fn clear() -> ApiResult<()> {
+        KEY_STORE.lock().unwrap().delete_all()
+    }
+
+    pub fn open(storage: Box<dyn KeyStorage>, password: &[u8]) -> ApiResult<()> {
+        KEY_STORE.lock().unwrap().open_storage(storage, password)
+    }
+
+    pub fn lock() -> ApiResult<()> {
+        KEY_STORE.lock().unwrap().lock_storage()
+    }
+
+    pub fn unlock(password: &[u8]) -> ApiResult<()> {
+        KEY_STORE.lock().unwrap().unlock_storage(password)
+    }
+
+    pub fn decode_secret(secret: &Option<String>, handle: &Option<String>) -> ApiResult<SecretBytes> {
+        if let Some(secret) = secret {
+            Ok(SecretBytes::new(hex_decode(secret)?))
+        } else if let Some(handle) = handle {
+            let store = KEY_STORE.lock().unwrap();
+            Ok(SecretBytes::new(store.entry(handle)?.secret.as_slice().to_vec()))
+        } else {
+            Err(ApiError::crypto_missing_key_source())
+        }
+    }
+
+    /// Returns key pair either provided explicitly or stored under the handle
+    pub fn decode_key_pair(keys: &Option<KeyPair>, handle: &Option<String>) -> ApiResult<Option<Keypair>> {
+        if let Some(keys) = keys {
+            Ok(Some(keys.decode()?))
+        } else if let Some(handle) = handle {
+            Ok(Some(KEY_STORE.lock().unwrap().key_pair(handle)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn require_key_pair(keys: &Option<KeyPair>, handle: &Option<String>) -> ApiResult<Keypair> {
+        Self::decode_key_pair(keys, handle)?.ok_or(ApiError::crypto_missing_key_source())
+    }
+}
@@ -13,7 +13,7 @@
 */
 
 use bip39::{Language, Mnemonic, MnemonicType};
-use crypto::hdkey::HDPrivateKey;
+use crypto::hdkey::{HDPrivateKey, HDKeyCompliance};
 use crypto::keys::{hmac_sha512, pbkdf2_hmac_sha512, KeyPair};
 use ed25519_dalek::{PublicKey, SecretKey};
 use types::{ApiResult, ApiError};
@@ -21,6 +21,38 @@ use hmac::Hmac;
 use pbkdf2::pbkdf2;
 use sha2::Sha512;
 use crypto::random::generate_bytes;
+use unicode_normalization::UnicodeNormalization;
+
+const MAX_WORD_SUGGESTIONS: usize = 5;
+const MAX_PHRASE_SUGGESTIONS: usize = 10;
+const MAX_PHRASE_CHECKS: usize = 2048;
+const MAX_EDIT_DISTANCE: usize = 2;
+// BIP39 English words are unique by the first four letters
+const WORD_PREFIX_LENGTH: usize = 4;
+
+const BIP39_LANGUAGES: [Language; 8] = [
+    Language::English,
+    Language::ChineseSimplified,
+    Language::ChineseTraditional,
+    Language::French,
+    Language::Italian,
+    Language::Japanese,
+    Language::Korean,
+    Language::Spanish,
+];
+
+lazy_static! {
+    // Dictionaries are normalized once, words keep their indices
+    static ref BIP39_NORMALIZED_WORDS: Vec<Vec<String>> = BIP39_LANGUAGES.iter()
+        .map(|language| {
+            let wordlist = language.wordlist();
+            (0..2048u16).map(|i| normalize_phrase(wordlist.get_word(i.into()))).collect()
+        })
+        .collect();
+    static ref TON_NORMALIZED_WORDS: Vec<String> = TON_WORDS.iter()
+        .map(|word| normalize_phrase(word))
+        .collect();
+}
 
 pub trait CryptoMnemonic {
     fn get_words(&self) -> ApiResult<String>;
@@ -29,12 +61,14 @@ pub trait CryptoMnemonic {
         &self,
         phrase: &String,
         path: &String,
-        compliant: bool,
+        compliant: HDKeyCompliance,
     ) -> ApiResult<KeyPair>;
     fn phrase_from_entropy(&self, entropy: &[u8]) -> ApiResult<String>;
     fn is_phrase_valid(&self, phrase: &String) -> ApiResult<bool>;
     fn seed_from_phrase_and_salt(&self, phrase: &String, salt: &String) -> ApiResult<String>;
     fn entropy_from_phrase(&self, phrase: &String) -> ApiResult<String>;
+    /// Dictionary words in the form produced by `normalize_phrase`
+    fn normalized_words(&self) -> &'static [String];
 }
 
 pub struct Bip39Mnemonic {
@@ -49,6 +83,202 @@ impl Bip39Mnemonic {
             language,
         }
     }
+
+    // Replaces normalized words with their dictionary spelling
+    fn canonical_phrase(&self, phrase: &str) -> String {
+        let wordlist = self.language.wordlist();
+        let dictionary = self.normalized_words();
+        normalize_phrase(phrase)
+            .split(' ')
+            .map(|word| dictionary.iter()
+                .position(|dictionary_word| dictionary_word == word)
+                .map(|index| wordlist.get_word((index as u16).into()).to_string())
+                .unwrap_or(word.to_string()))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn language_index(&self) -> usize {
+        match self.language {
+            Language::English => 0,
+            Language::ChineseSimplified => 1,
+            Language::ChineseTraditional => 2,
+            Language::French => 3,
+            Language::Italian => 4,
+            Language::Japanese => 5,
+            Language::Korean => 6,
+            Language::Spanish => 7,
+        }
+    }
+}
+
+/// Brings phrase to the canonical form: NFKD, lower case, words separated by single space
+pub fn normalize_phrase(phrase: &str) -> String {
+    phrase.nfkd()
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct WordSuggestion {
+    pub position: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PhraseSuggestions {
+    pub valid: bool,
+    /// Words missing in the dictionary with their closest dictionary words
+    pub words: Vec<WordSuggestion>,
+    /// Valid phrases closest to the given one
+    pub phrases: Vec<String>,
+}
+
+/// Proposes corrections for the phrase: dictionary words for misspelled words and
+/// valid phrases which differ by the misspelled words, by two swapped neighbour words
+/// or by a single mistyped word
+pub fn suggest_phrase(mnemonic: &dyn CryptoMnemonic, phrase: &String) -> ApiResult<PhraseSuggestions> {
+    let phrase = normalize_phrase(phrase);
+    if mnemonic.is_phrase_valid(&phrase)? {
+        return Ok(PhraseSuggestions { valid: true, words: Vec::new(), phrases: Vec::new() });
+    }
+
+    let dictionary = mnemonic.normalized_words();
+    let words: Vec<String> = phrase.split(' ').map(|word| word.to_string()).collect();
+
+    let misspelled: Vec<WordSuggestion> = words.iter()
+        .enumerate()
+        .filter(|(_, word)| !dictionary.contains(word))
+        .map(|(position, word)| WordSuggestion {
+            position,
+            word: word.clone(),
+            suggestions: closest_words(dictionary, word),
+        })
+        .collect();
+
+    let mut search = PhraseSearch { mnemonic, checks: 0, phrases: Vec::new() };
+    if !misspelled.is_empty() {
+        if misspelled.iter().all(|word| !word.suggestions.is_empty()) {
+            let mut counters = vec![0usize; misspelled.len()];
+            'combinations: loop {
+                let mut candidate = words.clone();
+                for (word, counter) in misspelled.iter().zip(&counters) {
+                    candidate[word.position] = word.suggestions[*counter].clone();
+                }
+                if !search.check(&candidate)? {
+                    break;
+                }
+                let mut i = 0;
+                loop {
+                    if i == counters.len() {
+                        break 'combinations;
+                    }
+                    counters[i] += 1;
+                    if counters[i] < misspelled[i].suggestions.len() {
+                        break;
+                    }
+                    counters[i] = 0;
+                    i += 1;
+                }
+            }
+        }
+    } else {
+        search_mistyped(&words, dictionary, &mut search)?;
+    }
+
+    Ok(PhraseSuggestions { valid: false, words: misspelled, phrases: search.phrases })
+}
+
+// Looks for valid phrases with two swapped neighbour words or with a single mistyped word
+fn search_mistyped(words: &[String], dictionary: &[String], search: &mut PhraseSearch) -> ApiResult<()> {
+    for position in 1..words.len() {
+        if words[position - 1] == words[position] {
+            continue;
+        }
+        let mut candidate = words.to_vec();
+        candidate.swap(position - 1, position);
+        if !search.check(&candidate)? {
+            return Ok(());
+        }
+    }
+    for position in 0..words.len() {
+        for dictionary_word in dictionary {
+            let distance = edit_distance(&words[position], dictionary_word);
+            if distance == 0 || distance > MAX_EDIT_DISTANCE {
+                continue;
+            }
+            let mut candidate = words.to_vec();
+            candidate[position] = dictionary_word.clone();
+            if !search.check(&candidate)? {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+struct PhraseSearch<'a> {
+    mnemonic: &'a dyn CryptoMnemonic,
+    checks: usize,
+    phrases: Vec<String>,
+}
+
+impl<'a> PhraseSearch<'a> {
+    // Returns false when the search limits are reached
+    fn check(&mut self, words: &[String]) -> ApiResult<bool> {
+        let phrase = words.join(" ");
+        self.checks += 1;
+        if self.mnemonic.is_phrase_valid(&phrase)? && !self.phrases.contains(&phrase) {
+            self.phrases.push(phrase);
+        }
+        Ok(self.checks < MAX_PHRASE_CHECKS && self.phrases.len() < MAX_PHRASE_SUGGESTIONS)
+    }
+}
+
+fn closest_words(dictionary: &[String], word: &str) -> Vec<String> {
+    let prefix: String = word.chars().take(WORD_PREFIX_LENGTH).collect();
+    let has_prefix = prefix.chars().count() == WORD_PREFIX_LENGTH;
+
+    let mut scored: Vec<(bool, usize, &String)> = dictionary.iter()
+        .map(|candidate| (
+            !(has_prefix && candidate.starts_with(&prefix)),
+            edit_distance(word, candidate),
+            candidate))
+        .filter(|(no_prefix, distance, _)| !no_prefix || *distance <= MAX_EDIT_DISTANCE)
+        .collect();
+    scored.sort();
+    scored.into_iter()
+        .take(MAX_WORD_SUGGESTIONS)
+        .map(|(_, _, candidate)| candidate.clone())
+        .collect()
+}
+
+// Optimal string alignment distance: insertions, deletions, substitutions and
+// transpositions of adjacent characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in 0..=a.len() {
+        d[i][0] = i;
+    }
+    for j in 0..=b.len() {
+        d[0][j] = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
 }
 
 fn ed25519_keys_from_secret_bytes(bytes: &[u8]) -> ApiResult<KeyPair> {
@@ -83,9 +313,10 @@ impl CryptoMnemonic for Bip39Mnemonic {
         &self,
         phrase: &String,
         path: &String,
-        compliant: bool,
+        compliant: HDKeyCompliance,
     ) -> ApiResult<KeyPair> {
-        let derived = HDPrivateKey::from_mnemonic(phrase)?.derive_path(path, compliant)?;
+        let derived = HDPrivateKey::from_mnemonic(&normalize_phrase(phrase), compliant)?
+            .derive_path(path, compliant)?;
         ed25519_keys_from_secret_bytes(&derived.secret())
     }
 
@@ -96,17 +327,17 @@ impl CryptoMnemonic for Bip39Mnemonic {
     }
 
     fn is_phrase_valid(&self, phrase: &String) -> ApiResult<bool> {
-        Ok(Mnemonic::validate(phrase.as_str(), self.language).is_ok())
+        Ok(Mnemonic::validate(&self.canonical_phrase(phrase), self.language).is_ok())
     }
 
     fn seed_from_phrase_and_salt(&self, phrase: &String, salt: &String) -> ApiResult<String> {
-        let mnemonic = Mnemonic::from_phrase(phrase, self.language)
+        let mnemonic = Mnemonic::from_phrase(self.canonical_phrase(phrase), self.language)
             .map_err(|err| ApiError::crypto_bip39_invalid_phrase(err))?;
 
-        let salt = format!("mnemonic{}", salt);
+        let salt = format!("mnemonic{}", salt).nfkd().collect::<String>();
         let mut seed = vec![0u8; 64];
         pbkdf2::<Hmac<Sha512>>(
-            mnemonic.phrase().as_bytes(),
+            normalize_phrase(mnemonic.phrase()).as_bytes(),
             salt.as_bytes(),
             2048,
             &mut seed,
@@ -116,10 +347,14 @@ impl CryptoMnemonic for Bip39Mnemonic {
 
     #[allow(dead_code)]
     fn entropy_from_phrase(&self, phrase: &String) -> ApiResult<String> {
-        let mnemonic = Mnemonic::from_phrase(phrase, self.language)
+        let mnemonic = Mnemonic::from_phrase(self.canonical_phrase(phrase), self.language)
             .map_err(|err| ApiError::crypto_bip39_invalid_phrase(err))?;
         Ok(hex::encode(mnemonic.entropy()))
     }
+
+    fn normalized_words(&self) -> &'static [String] {
+        &BIP39_NORMALIZED_WORDS[self.language_index()]
+    }
 }
 
 pub struct TonMnemonic {
@@ -185,9 +420,9 @@ impl CryptoMnemonic for TonMnemonic {
         &self,
         phrase: &String,
         _path: &String,
-        _compliant: bool,
+        _compliant: HDKeyCompliance,
     ) -> ApiResult<KeyPair> {
-        let seed = Self::seed_from_phrase(&phrase, "TON default seed", 100_000);
+        let seed = Self::seed_from_phrase(&normalize_phrase(phrase), "TON default seed", 100_000);
         ed25519_keys_from_secret_bytes(&seed[..32])
     }
 
@@ -204,6 +439,7 @@ impl CryptoMnemonic for TonMnemonic {
     }
 
     fn is_phrase_valid(&self, phrase: &String) -> ApiResult<bool> {
+        let phrase = normalize_phrase(phrase);
         let mut count = 0u8;
         for word in phrase.split(" ") {
             if !TON_WORDS.contains(&word) {
@@ -211,7 +447,7 @@ impl CryptoMnemonic for TonMnemonic {
             }
             count += 1;
         };
-        Ok(count == self.word_count && Self::is_basic_seed(phrase))
+        Ok(count == self.word_count && Self::is_basic_seed(&phrase))
     }
 
     fn seed_from_phrase_and_salt(&self, phrase: &String, salt: &String) -> ApiResult<String> {
@@ -221,6 +457,10 @@ impl CryptoMnemonic for TonMnemonic {
     fn entropy_from_phrase(&self, phrase: &String) -> ApiResult<String> {
         Ok(hex::encode(Self::entropy_from_phrase(&phrase).as_ref()))
     }
+
+    fn normalized_words(&self) -> &'static [String] {
+        &TON_NORMALIZED_WORDS
+    }
 }
 
 const TON_WORDS: [&str; 2048] = [
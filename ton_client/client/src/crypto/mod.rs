@@ -18,18 +18,24 @@ pub(crate) mod sha;
 pub(crate) mod scrypt;
 pub(crate) mod nacl;
 pub(crate) mod keys;
+pub(crate) mod keystore;
+pub(crate) mod signing_box;
 pub(crate) mod ed25519;
 pub(crate) mod mnemonic;
 pub(crate) mod hdkey;
+pub(crate) mod slip39;
 
 use crypto as api;
 use types::{base64_decode, ApiError, ApiResult, hex_decode};
 use crypto::keys::{KeyPair, key_to_ton_string};
-use crypto::keys::KeyStore;
+use crypto::keystore::{KeyStore, FileKeyStorage, zeroize};
+use crypto::signing_box::{SigningBoxes, SigningBoxHandle, KeyPairSigningBox, KeyStoreSigningBox};
 use dispatch::DispatchTable;
 use client::ClientContext;
 use crypto::math::ton_crc16;
-use crypto::mnemonic::{CryptoMnemonic, TonMnemonic, Bip39Mnemonic};
+use crypto::mnemonic::{CryptoMnemonic, TonMnemonic, Bip39Mnemonic, suggest_phrase};
+use crypto::slip39::Slip39Group;
+use crypto::hdkey::HDKeyCompliance;
 use bip39::{MnemonicType, Language};
 
 #[derive(Serialize, Deserialize)]
@@ -123,6 +129,40 @@ pub(crate) struct NaclSignParams {
     pub outputEncoding: OutputEncoding,
 }
 
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct KeystoreOpenParams {
+    pub path: String,
+    pub password: InputMessage,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct KeystoreUnlockParams {
+    pub password: InputMessage,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct SigningBoxFromKeystoreParams {
+    pub keystoreHandle: String,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct SigningBoxParams {
+    pub signingBox: SigningBoxHandle,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct SigningBoxSignParams {
+    pub signingBox: SigningBoxHandle,
+    pub message: InputMessage,
+    #[serde(default = "default_result_encoding_hex")]
+    pub outputEncoding: OutputEncoding,
+}
+
 #[derive(Deserialize)]
 #[allow(non_snake_case)]
 pub(crate) struct MnemonicWordsParams {
@@ -161,6 +201,40 @@ pub(crate) struct MnemonicVerifyParams {
     pub phrase: String,
 }
 
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct Slip39SplitParams {
+    #[serde(default = "default_dictionary")]
+    pub dictionary: u8,
+    #[serde(default = "default_word_count")]
+    pub wordCount: u8,
+    pub phrase: String,
+    #[serde(default = "default_group_threshold")]
+    pub groupThreshold: u8,
+    pub groups: Vec<Slip39Group>,
+    #[serde(default)]
+    pub passphrase: String,
+    #[serde(default)]
+    pub iterationExponent: u8,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Slip39SplitResult {
+    pub groups: Vec<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+pub(crate) struct Slip39CombineParams {
+    #[serde(default = "default_dictionary")]
+    pub dictionary: u8,
+    #[serde(default = "default_word_count")]
+    pub wordCount: u8,
+    pub shares: Vec<String>,
+    #[serde(default)]
+    pub passphrase: String,
+}
+
 #[derive(Deserialize)]
 #[allow(non_snake_case)]
 pub(crate) struct HDKeyFromMnemonicParams {
@@ -169,6 +243,8 @@ pub(crate) struct HDKeyFromMnemonicParams {
     #[serde(default = "default_word_count")]
     pub wordCount: u8,
     pub phrase: String,
+    #[serde(default = "default_compliant")]
+    pub compliant: HDKeyCompliance,
 }
 
 #[derive(Deserialize)]
@@ -182,7 +258,7 @@ pub(crate) struct MnemonicDeriveSignKeysParams {
     #[serde(default = "default_path")]
     pub path: String,
     #[serde(default = "default_compliant")]
-    pub compliant: bool,
+    pub compliant: HDKeyCompliance,
 }
 
 #[derive(Deserialize)]
@@ -193,7 +269,7 @@ pub(crate) struct HDKeyDeriveParams {
     #[serde(default = "default_hardened")]
     pub hardened: bool,
     #[serde(default = "default_compliant")]
-    pub compliant: bool,
+    pub compliant: HDKeyCompliance,
 }
 
 #[derive(Deserialize)]
@@ -202,13 +278,15 @@ pub(crate) struct HDKeyDerivePathParams {
     pub serialized: String,
     pub path: String,
     #[serde(default = "default_compliant")]
-    pub compliant: bool,
+    pub compliant: HDKeyCompliance,
 }
 
 #[derive(Deserialize)]
 #[allow(non_snake_case)]
 pub(crate) struct HDKeyGetKeyParams {
     pub serialized: String,
+    #[serde(default = "default_compliant")]
+    pub compliant: HDKeyCompliance,
 }
 
 fn default_dictionary() -> u8 {
@@ -219,12 +297,16 @@ fn default_word_count() -> u8 {
     24
 }
 
+fn default_group_threshold() -> u8 {
+    1
+}
+
 fn default_hardened() -> bool {
     false
 }
 
-fn default_compliant() -> bool {
-    true
+fn default_compliant() -> HDKeyCompliance {
+    HDKeyCompliance::Bip32
 }
 
 fn default_path() -> String {
@@ -270,6 +352,11 @@ const ITALIAN_DICTIONARY: u8 = 5;
 const JAPANESE_DICTIONARY: u8 = 6;
 const KOREAN_DICTIONARY: u8 = 7;
 const SPANISH_DICTIONARY: u8 = 8;
+// BIP39 wordlists missing in the bip39 crate. They are rejected instead of being
+// bundled here: a wordlist which differs from the official one by a single word
+// produces phrases no other wallet can restore.
+const CZECH_DICTIONARY: u8 = 9;
+const PORTUGUESE_DICTIONARY: u8 = 10;
 
 fn mnemonics(dictionary: u8, word_count: u8) -> ApiResult<Box<dyn CryptoMnemonic>> {
     if dictionary == TON_DICTIONARY {
@@ -292,6 +379,8 @@ fn mnemonics(dictionary: u8, word_count: u8) -> ApiResult<Box<dyn CryptoMnemonic
         JAPANESE_DICTIONARY => Language::Japanese,
         KOREAN_DICTIONARY => Language::Korean,
         SPANISH_DICTIONARY => Language::Spanish,
+        CZECH_DICTIONARY => return Err(ApiError::crypto_bip39_unsupported_dictionary(dictionary, "Czech")),
+        PORTUGUESE_DICTIONARY => return Err(ApiError::crypto_bip39_unsupported_dictionary(dictionary, "Portuguese")),
         _ => return Err(ApiError::crypto_bip39_invalid_dictionary(dictionary))
     };
     Ok(Box::new(Bip39Mnemonic::new(mnemonic_type, language)))
@@ -340,16 +429,49 @@ pub(crate) fn register(handlers: &mut DispatchTable) {
     handlers.call_no_args("crypto.ed25519.keypair", |_context: &mut ClientContext|
         api::ed25519::generate_keypair());
     handlers.call("crypto.keystore.add", |_context: &mut ClientContext, keys: KeyPair| {
-        Ok(KeyStore::add(&keys))
+        KeyStore::add(&keys)
     });
     handlers.call("crypto.keystore.remove", |_context: &mut ClientContext, handle: String| {
-        KeyStore::remove(&handle);
-        Ok(())
+        KeyStore::remove(&handle)
     });
     handlers.call_no_args("crypto.keystore.clear", |_context: &mut ClientContext| {
-        KeyStore::clear();
+        KeyStore::clear()
+    });
+    handlers.call("crypto.keystore.open", |_context: &mut ClientContext, params: KeystoreOpenParams| {
+        let mut password = params.password.decode()?;
+        let result = KeyStore::open(Box::new(FileKeyStorage::new(params.path)), &password);
+        zeroize(&mut password);
+        result
+    });
+    handlers.call_no_args("crypto.keystore.lock", |_context: &mut ClientContext| {
+        KeyStore::lock()
+    });
+    handlers.call("crypto.keystore.unlock", |_context: &mut ClientContext, params: KeystoreUnlockParams| {
+        let mut password = params.password.decode()?;
+        let result = KeyStore::unlock(&password);
+        zeroize(&mut password);
+        result
+    });
+
+    // Signing boxes
+
+    handlers.call("crypto.signing_box.from_keys", |_context: &mut ClientContext, keys: KeyPair| {
+        Ok(SigningBoxes::add(Box::new(KeyPairSigningBox::new(keys.decode()?))))
+    });
+    handlers.call("crypto.signing_box.from_keystore", |_context: &mut ClientContext, params: SigningBoxFromKeystoreParams| {
+        Ok(SigningBoxes::add(Box::new(KeyStoreSigningBox::new(params.keystoreHandle))))
+    });
+    handlers.call("crypto.signing_box.remove", |_context: &mut ClientContext, params: SigningBoxParams| {
+        SigningBoxes::remove(params.signingBox);
         Ok(())
     });
+    handlers.spawn("crypto.signing_box.get_public_key", |_context: &mut ClientContext, params: SigningBoxParams| {
+        Ok(hex::encode(SigningBoxes::get_public_key(params.signingBox)?))
+    });
+    handlers.spawn("crypto.signing_box.sign", |_context: &mut ClientContext, params: SigningBoxSignParams| {
+        let (_public, signature) = SigningBoxes::sign(params.signingBox, &params.message.decode()?)?;
+        params.outputEncoding.encode(signature)
+    });
 
     // Sha
 
@@ -390,50 +512,50 @@ pub(crate) fn register(handlers: &mut DispatchTable) {
     });
     handlers.spawn("crypto.nacl.box", |_context: &mut ClientContext, params: NaclBoxParams| {
         params.outputEncoding.encode(api::nacl::box_(
-            params.message.decode()?,
-            hex_decode(&params.nonce)?,
-            hex_decode(&params.theirPublicKey)?,
-            KeyStore::decode_secret(&params.secretKey, &params.keystoreHandle)?,
+            &params.message.decode()?,
+            &hex_decode(&params.nonce)?,
+            &hex_decode(&params.theirPublicKey)?,
+            KeyStore::decode_secret(&params.secretKey, &params.keystoreHandle)?.as_slice(),
         )?)
     });
     handlers.spawn("crypto.nacl.box.open", |_context: &mut ClientContext, params: NaclBoxParams| {
         params.outputEncoding.encode(api::nacl::box_open(
-            params.message.decode()?,
-            hex_decode(&params.nonce)?,
-            hex_decode(&params.theirPublicKey)?,
-            KeyStore::decode_secret(&params.secretKey, &params.keystoreHandle)?,
+            &params.message.decode()?,
+            &hex_decode(&params.nonce)?,
+            &hex_decode(&params.theirPublicKey)?,
+            KeyStore::decode_secret(&params.secretKey, &params.keystoreHandle)?.as_slice(),
         )?)
     });
     handlers.spawn("crypto.nacl.secret.box", |_context: &mut ClientContext, params: NaclSecretBoxParams| {
         params.outputEncoding.encode(api::nacl::secret_box(
-            params.message.decode()?,
-            hex_decode(&params.nonce)?,
-            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?,
+            &params.message.decode()?,
+            &hex_decode(&params.nonce)?,
+            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?.as_slice(),
         )?)
     });
     handlers.spawn("crypto.nacl.secret.box.open", |_context: &mut ClientContext, params: NaclSecretBoxParams| {
         params.outputEncoding.encode(api::nacl::secret_box_open(
-            params.message.decode()?,
-            hex_decode(&params.nonce)?,
-            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?,
+            &params.message.decode()?,
+            &hex_decode(&params.nonce)?,
+            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?.as_slice(),
         )?)
     });
     handlers.spawn("crypto.nacl.sign", |_context: &mut ClientContext, params: NaclSignParams| {
         params.outputEncoding.encode(api::nacl::sign(
-            params.message.decode()?,
-            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?,
+            &params.message.decode()?,
+            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?.as_slice(),
         )?)
     });
     handlers.spawn("crypto.nacl.sign.open", |_context: &mut ClientContext, params: NaclSignParams| {
         params.outputEncoding.encode(api::nacl::sign_open(
-            params.message.decode()?,
-            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?,
+            &params.message.decode()?,
+            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?.as_slice(),
         )?)
     });
     handlers.spawn("crypto.nacl.sign.detached", |_context: &mut ClientContext, params: NaclSignParams| {
         params.outputEncoding.encode(api::nacl::sign_detached(
-            params.message.decode()?,
-            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?,
+            &params.message.decode()?,
+            KeyStore::decode_secret(&params.key, &params.keystoreHandle)?.as_slice(),
         )?)
     });
 
@@ -455,6 +577,30 @@ pub(crate) fn register(handlers: &mut DispatchTable) {
         mnemonics(params.dictionary, params.wordCount)?.is_phrase_valid(&params.phrase)
     });
 
+    handlers.spawn("crypto.mnemonic.suggest", |_context: &mut ClientContext, params: MnemonicVerifyParams| {
+        suggest_phrase(&*mnemonics(params.dictionary, params.wordCount)?, &params.phrase)
+    });
+
+    handlers.spawn("crypto.mnemonic.slip39.split", |_context: &mut ClientContext, params: Slip39SplitParams| {
+        if params.dictionary == TON_DICTIONARY {
+            // TON phrase can not be restored from its entropy
+            return Err(ApiError::crypto_slip39_failed("only BIP39 phrases can be shared"));
+        }
+        let entropy = mnemonics(params.dictionary, params.wordCount)?.entropy_from_phrase(&params.phrase)?;
+        let groups = api::slip39::split_secret(
+            &hex_decode(&entropy)?,
+            &params.passphrase,
+            params.groupThreshold,
+            &params.groups,
+            params.iterationExponent)?;
+        Ok(Slip39SplitResult { groups })
+    });
+
+    handlers.spawn("crypto.mnemonic.slip39.combine", |_context: &mut ClientContext, params: Slip39CombineParams| {
+        let entropy = api::slip39::combine_mnemonics(&params.shares, &params.passphrase)?;
+        mnemonics(params.dictionary, params.wordCount)?.phrase_from_entropy(&entropy)
+    });
+
     handlers.spawn("crypto.mnemonic.derive.sign.keys", |_context: &mut ClientContext, params: MnemonicDeriveSignKeysParams| {
         mnemonics(params.dictionary, params.wordCount)?.derive_ed25519_keys_from_phrase(
             &params.phrase,
@@ -465,7 +611,7 @@ pub(crate) fn register(handlers: &mut DispatchTable) {
     // HDKey
 
     handlers.spawn("crypto.hdkey.xprv.from.mnemonic", |_context: &mut ClientContext, params: HDKeyFromMnemonicParams| {
-        api::hdkey::hdkey_xprv_from_mnemonic(&params.phrase)
+        api::hdkey::hdkey_xprv_from_mnemonic(&params.phrase, params.compliant)
     });
 
     handlers.spawn("crypto.hdkey.xprv.derive", |_context: &mut ClientContext, params: HDKeyDeriveParams| {
@@ -488,7 +634,7 @@ pub(crate) fn register(handlers: &mut DispatchTable) {
     });
 
     handlers.spawn("crypto.hdkey.xprv.public", |_context: &mut ClientContext, params: HDKeyGetKeyParams| {
-        api::hdkey::hdkey_public_from_xprv(&params.serialized)
+        api::hdkey::hdkey_public_from_xprv(&params.serialized, params.compliant)
     });
 }
 
@@ -13,6 +13,7 @@
 */
 
 use crypto::keys::{KeyPair, key512, key256, key192};
+use crypto::keystore::zeroize;
 use types::{ApiResult, ApiError};
 
 // Keys
@@ -53,7 +54,7 @@ pub fn box_keypair_from_secret_key(secret: &String) -> ApiResult<KeyPair> {
 
 // Secret Box
 
-fn prepare_to_convert(input: &Vec<u8>, nonce: &Vec<u8>, key: &Vec<u8>, pad_len: usize)
+fn prepare_to_convert(input: &[u8], nonce: &[u8], key: &[u8], pad_len: usize)
     -> ApiResult<(Vec<u8>, Vec<u8>, [u8; 24], [u8;32])> {
     let mut padded_input = Vec::new();
     padded_input.resize(pad_len, 0);
@@ -63,107 +64,124 @@ fn prepare_to_convert(input: &Vec<u8>, nonce: &Vec<u8>, key: &Vec<u8>, pad_len:
     Ok((padded_output, padded_input, key192(&nonce)?, key256(&key)?))
 }
 
-pub fn secret_box(input: Vec<u8>, nonce: Vec<u8>, key: Vec<u8>) -> ApiResult<Vec<u8>> {
+// Copies output without padding and zeroizes all intermediate buffers
+fn unpad_output(mut padded_output: Vec<u8>, mut padded_input: Vec<u8>, key: &mut [u8], pad_len: usize) -> Vec<u8> {
+    let output = padded_output[pad_len..].to_vec();
+    zeroize(&mut padded_output);
+    zeroize(&mut padded_input);
+    zeroize(key);
+    output
+}
+
+pub fn secret_box(input: &[u8], nonce: &[u8], key: &[u8]) -> ApiResult<Vec<u8>> {
     let (
         mut padded_output,
         padded_input,
         nonce,
-        key
-    ) = prepare_to_convert(&input, &nonce, &key, 32)?;
+        mut key
+    ) = prepare_to_convert(input, nonce, key, 32)?;
 
-    sodalite::secretbox(
+    let result = sodalite::secretbox(
         &mut padded_output,
         &padded_input,
         &nonce,
         &key
-    ).map_err(|_|ApiError::crypto_nacl_secret_box_failed("secret box failed"))?;
-    padded_output.drain(..16);
-    Ok(padded_output)
+    );
+    let output = unpad_output(padded_output, padded_input, &mut key, 16);
+    result.map_err(|_|ApiError::crypto_nacl_secret_box_failed("secret box failed"))?;
+    Ok(output)
 }
 
-pub fn secret_box_open(input: Vec<u8>, nonce: Vec<u8>, key: Vec<u8>) -> ApiResult<Vec<u8>> {
+pub fn secret_box_open(input: &[u8], nonce: &[u8], key: &[u8]) -> ApiResult<Vec<u8>> {
     let (
         mut padded_output,
         padded_input,
         nonce,
-        key
-    ) = prepare_to_convert(&input, &nonce, &key, 16)?;
+        mut key
+    ) = prepare_to_convert(input, nonce, key, 16)?;
 
-    sodalite::secretbox_open(
+    let result = sodalite::secretbox_open(
         &mut padded_output,
         &padded_input,
         &nonce,
         &key
-    ).map_err(|_|ApiError::crypto_nacl_secret_box_failed("secret box open failed"))?;
-    padded_output.drain(..32);
-    Ok(padded_output)
+    );
+    let output = unpad_output(padded_output, padded_input, &mut key, 32);
+    result.map_err(|_|ApiError::crypto_nacl_secret_box_failed("secret box open failed"))?;
+    Ok(output)
 }
 
 // Box
 
-pub fn box_(input: Vec<u8>, nonce: Vec<u8>, their_public: Vec<u8>, secret: Vec<u8>) -> ApiResult<Vec<u8>> {
+pub fn box_(input: &[u8], nonce: &[u8], their_public: &[u8], secret: &[u8]) -> ApiResult<Vec<u8>> {
+    let their_public = key256(their_public)?;
     let (
         mut padded_output,
         padded_input,
         nonce,
-        secret
-    ) = prepare_to_convert(&input, &nonce, &secret, 32)?;
+        mut secret
+    ) = prepare_to_convert(input, nonce, secret, 32)?;
 
-    sodalite::box_(
+    let result = sodalite::box_(
         &mut padded_output,
         &padded_input,
         &nonce,
-        &key256(&their_public)?,
+        &their_public,
         &secret
-    ).map_err(|_|ApiError::crypto_nacl_box_failed("box failed"))?;
-    padded_output.drain(..16);
-    Ok(padded_output)
+    );
+    let output = unpad_output(padded_output, padded_input, &mut secret, 16);
+    result.map_err(|_|ApiError::crypto_nacl_box_failed("box failed"))?;
+    Ok(output)
 }
 
-pub fn box_open(input: Vec<u8>, nonce: Vec<u8>, their_public: Vec<u8>, secret: Vec<u8>) -> ApiResult<Vec<u8>> {
+pub fn box_open(input: &[u8], nonce: &[u8], their_public: &[u8], secret: &[u8]) -> ApiResult<Vec<u8>> {
+    let their_public = key256(their_public)?;
     let (
         mut padded_output,
         padded_input,
         nonce,
-        secret
-    ) = prepare_to_convert(&input, &nonce, &secret, 16)?;
-    sodalite::box_open(
+        mut secret
+    ) = prepare_to_convert(input, nonce, secret, 16)?;
+    let result = sodalite::box_open(
         &mut padded_output,
         &padded_input,
         &nonce,
-        &key256(&their_public)?,
+        &their_public,
         &secret
-    ).map_err(|_|ApiError::crypto_nacl_box_failed("box open failed"))?;
-    padded_output.drain(..32);
-    Ok(padded_output)
+    );
+    let output = unpad_output(padded_output, padded_input, &mut secret, 32);
+    result.map_err(|_|ApiError::crypto_nacl_box_failed("box open failed"))?;
+    Ok(output)
 }
 
 // Sign
 
-pub fn sign(input: Vec<u8>, secret: Vec<u8>) -> ApiResult<Vec<u8>> {
+pub fn sign(input: &[u8], secret: &[u8]) -> ApiResult<Vec<u8>> {
     let mut output: Vec<u8> = Vec::new();
     output.resize(input.len() + sodalite::SIGN_LEN, 0);
+    let mut secret = key512(secret)?;
     sodalite::sign_attached(
         &mut output,
-        &input,
-        &key512(&secret)?
+        input,
+        &secret
     );
+    zeroize(&mut secret);
     Ok(output)
 }
 
-pub fn sign_open(input: Vec<u8>, public: Vec<u8>) -> ApiResult<Vec<u8>> {
+pub fn sign_open(input: &[u8], public: &[u8]) -> ApiResult<Vec<u8>> {
     let mut output: Vec<u8> = Vec::new();
     output.resize(input.len(), 0);
     let len = sodalite::sign_attached_open(
         &mut output,
-        &input,
-        &key256(&public)?
+        input,
+        &key256(public)?
     ).map_err(|_|ApiError::crypto_nacl_sign_failed("box sign open failed"))?;
     output.resize(len, 0);
     Ok(output)
 }
 
-pub fn sign_detached(input: Vec<u8>, secret: Vec<u8>) -> ApiResult<Vec<u8>> {
+pub fn sign_detached(input: &[u8], secret: &[u8]) -> ApiResult<Vec<u8>> {
     let signed = sign(input, secret)?;
     let mut sign: Vec<u8> = Vec::new();
     sign.resize(64, 0);
@@ -0,0 +1,197 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use ed25519_dalek::{Keypair, PublicKey, Signature};
+use sha2::Sha512;
+use crypto::keystore::{KeyStore, KeyPairHandle};
+use types::{ApiResult, ApiError};
+use ::InteropSigningCallback;
+
+pub type SigningBoxHandle = u32;
+
+const SIGNATURE_LEN: usize = 64;
+
+/// Provider of Ed25519 signatures which does not expose the secret key.
+///
+/// Implemented by keystore entries, in-process keys and signers registered
+/// over the C interface (e.g. hardware wallets). Signing is called without
+/// any lock held, so boxes can be used from several threads at once.
+pub trait SigningBox: Send + Sync {
+    fn get_public_key(&self) -> ApiResult<Vec<u8>>;
+    /// Returns detached signature of data
+    fn sign(&self, data: &[u8]) -> ApiResult<Vec<u8>>;
+    /// Called when the box is removed from the registry. Boxes which call out of the process
+    /// wait here for signing in progress and reject later requests.
+    fn close(&self) {}
+}
+
+/// Signs with key pair held in process memory
+pub struct KeyPairSigningBox {
+    keys: Keypair,
+}
+
+impl KeyPairSigningBox {
+    pub fn new(keys: Keypair) -> Self {
+        Self { keys }
+    }
+}
+
+impl SigningBox for KeyPairSigningBox {
+    fn get_public_key(&self) -> ApiResult<Vec<u8>> {
+        Ok(self.keys.public.to_bytes().to_vec())
+    }
+
+    fn sign(&self, data: &[u8]) -> ApiResult<Vec<u8>> {
+        Ok(self.keys.sign::<Sha512>(data).to_bytes().to_vec())
+    }
+}
+
+/// Signs with key pair stored in the keystore. The key is taken from the keystore
+/// on every signing so the box stops working when the keystore is locked.
+pub struct KeyStoreSigningBox {
+    handle: KeyPairHandle,
+}
+
+impl KeyStoreSigningBox {
+    pub fn new(handle: KeyPairHandle) -> Self {
+        Self { handle }
+    }
+}
+
+impl SigningBox for KeyStoreSigningBox {
+    fn get_public_key(&self) -> ApiResult<Vec<u8>> {
+        let keys = KeyStore::decode_key_pair(&None, &Some(self.handle.clone()))?
+            .ok_or(ApiError::crypto_invalid_keystore_handle())?;
+        Ok(keys.public.to_bytes().to_vec())
+    }
+
+    fn sign(&self, data: &[u8]) -> ApiResult<Vec<u8>> {
+        let keys = KeyStore::decode_key_pair(&None, &Some(self.handle.clone()))?
+            .ok_or(ApiError::crypto_invalid_keystore_handle())?;
+        Ok(keys.sign::<Sha512>(data).to_bytes().to_vec())
+    }
+}
+
+/// Delegates signing to the callback registered over the C interface
+pub struct CallbackSigningBox {
+    public_key: Vec<u8>,
+    callback: InteropSigningCallback,
+    // opaque pointer of the caller passed back to the callback, `None` after the box
+    // is closed. Read lock is held while the callback runs, so closing waits for it.
+    user_data: RwLock<Option<usize>>,
+}
+
+impl CallbackSigningBox {
+    pub fn new(public_key: Vec<u8>, callback: InteropSigningCallback, user_data: usize) -> Self {
+        Self { public_key, callback, user_data: RwLock::new(Some(user_data)) }
+    }
+}
+
+impl SigningBox for CallbackSigningBox {
+    fn get_public_key(&self) -> ApiResult<Vec<u8>> {
+        Ok(self.public_key.clone())
+    }
+
+    fn sign(&self, data: &[u8]) -> ApiResult<Vec<u8>> {
+        let guard = self.user_data.read().unwrap();
+        let user_data = guard.ok_or(ApiError::crypto_signing_box_failed("signing box is unregistered"))?;
+        let mut signature = vec![0u8; SIGNATURE_LEN];
+        let succeeded = unsafe {
+            (self.callback)(
+                user_data as *mut std::os::raw::c_void,
+                data.as_ptr(),
+                data.len() as u32,
+                signature.as_mut_ptr())
+        };
+        if succeeded {
+            Ok(signature)
+        } else {
+            Err(ApiError::crypto_signing_box_failed("external signer rejected the request"))
+        }
+    }
+
+    fn close(&self) {
+        *self.user_data.write().unwrap() = None;
+    }
+}
+
+pub struct SigningBoxes {
+    next_handle: SigningBoxHandle,
+    boxes: HashMap<SigningBoxHandle, Arc<dyn SigningBox>>,
+}
+
+lazy_static! {
+    static ref SIGNING_BOXES: Mutex<SigningBoxes> = Mutex::new(SigningBoxes::new());
+}
+
+impl SigningBoxes {
+    fn new() -> Self {
+        Self {
+            next_handle: 1,
+            boxes: HashMap::new(),
+        }
+    }
+
+    pub fn add(signing_box: Box<dyn SigningBox>) -> SigningBoxHandle {
+        let mut boxes = SIGNING_BOXES.lock().unwrap();
+        let handle = boxes.next_handle;
+        boxes.next_handle = handle.wrapping_add(1);
+        boxes.boxes.insert(handle, Arc::from(signing_box));
+        handle
+    }
+
+    /// Removes the box and waits for signing in progress, so the box makes no calls
+    /// after it returns
+    pub fn remove(handle: SigningBoxHandle) {
+        let signing_box = SIGNING_BOXES.lock().unwrap().boxes.remove(&handle);
+        // signing is waited without the registry lock held
+        if let Some(signing_box) = signing_box {
+            signing_box.close();
+        }
+    }
+
+    // Box is taken out of the registry so that callbacks into the caller are made
+    // without the registry lock held
+    fn get(handle: SigningBoxHandle) -> ApiResult<Arc<dyn SigningBox>> {
+        SIGNING_BOXES.lock().unwrap().boxes.get(&handle)
+            .cloned()
+            .ok_or(ApiError::crypto_invalid_signing_box_handle(handle))
+    }
+
+    pub fn get_public_key(handle: SigningBoxHandle) -> ApiResult<Vec<u8>> {
+        Self::get(handle)?.get_public_key()
+    }
+
+    /// Signs data returning public key and signature. The signature is verified
+    /// with the public key of the box
+    pub fn sign(handle: SigningBoxHandle, data: &[u8]) -> ApiResult<(Vec<u8>, Vec<u8>)> {
+        let signing_box = Self::get(handle)?;
+        let public_key = signing_box.get_public_key()?;
+        let signature = signing_box.sign(data)?;
+        if signature.len() != SIGNATURE_LEN {
+            return Err(ApiError::crypto_signing_box_failed(
+                format!("invalid signature length {}", signature.len())));
+        }
+        let public = PublicKey::from_bytes(&public_key)
+            .map_err(|err| ApiError::crypto_invalid_public_key(err, &hex::encode(&public_key)))?;
+        let verified = Signature::from_bytes(&signature)
+            .and_then(|signature| public.verify::<Sha512>(data, &signature));
+        if verified.is_err() {
+            return Err(ApiError::crypto_signing_box_failed("signature does not match public key of the box"));
+        }
+        Ok((public_key, signature))
+    }
+}
@@ -0,0 +1,581 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! SLIP-0039 Shamir's secret sharing for mnemonic codes
+
+use std::collections::BTreeMap;
+use hmac::{Hmac, Mac};
+use pbkdf2::pbkdf2;
+use sha2::Sha256;
+use crypto::random::generate_bytes;
+use types::{ApiResult, ApiError};
+
+const CUSTOMIZATION_STRING: &[u8] = b"shamir";
+const RADIX_BITS: usize = 10;
+const ID_BITS: usize = 15;
+const ITERATION_EXPONENT_BITS: usize = 5;
+const METADATA_WORDS: usize = 4;
+const CHECKSUM_WORDS: usize = 3;
+const MIN_SECRET_LENGTH: usize = 16;
+const MAX_SHARE_COUNT: u8 = 16;
+const DIGEST_LENGTH: usize = 4;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const BASE_ITERATION_COUNT: u32 = 10000;
+// Five bits of a share can encode exponents up to 31, but each step doubles PBKDF2 work:
+// larger exponents overflow iteration count and make recovery run for days
+const MAX_ITERATION_EXPONENT: u8 = 16;
+const ROUND_COUNT: u8 = 4;
+
+/// Member shares of one group: `threshold` of `count` shares recover the group secret
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Slip39Group {
+    pub threshold: u8,
+    pub count: u8,
+}
+
+/// Splits master secret into groups of mnemonic shares. Any `group_threshold` groups
+/// recover the secret.
+pub fn split_secret(
+    master_secret: &[u8],
+    passphrase: &str,
+    group_threshold: u8,
+    groups: &[Slip39Group],
+    iteration_exponent: u8,
+) -> ApiResult<Vec<Vec<String>>> {
+    if master_secret.len() < MIN_SECRET_LENGTH || master_secret.len() % 2 != 0 {
+        return Err(ApiError::crypto_slip39_failed(
+            "master secret must be at least 128 bits long and contain even number of bytes"));
+    }
+    if groups.len() > MAX_SHARE_COUNT as usize {
+        return Err(ApiError::crypto_slip39_failed("too many groups"));
+    }
+    if group_threshold as usize > groups.len() {
+        return Err(ApiError::crypto_slip39_failed(
+            "group threshold can not exceed the number of groups"));
+    }
+    check_iteration_exponent(iteration_exponent)?;
+    for group in groups {
+        if group.threshold == 1 && group.count > 1 {
+            return Err(ApiError::crypto_slip39_failed(
+                "creating multiple member shares with member threshold 1 is not allowed"));
+        }
+    }
+    check_passphrase(passphrase)?;
+
+    let random = generate_bytes(2);
+    let id = ((random[0] as u16) << 8 | random[1] as u16) & ((1 << ID_BITS) - 1);
+
+    let encrypted = encrypt(master_secret, passphrase.as_bytes(), iteration_exponent, id);
+    let group_shares = split(group_threshold, groups.len() as u8, &encrypted)?;
+
+    let mut result = Vec::new();
+    for ((group_index, group_secret), group) in group_shares.into_iter().zip(groups) {
+        let mut mnemonics = Vec::new();
+        for (member_index, value) in split(group.threshold, group.count, &group_secret)? {
+            let share = Share {
+                id,
+                iteration_exponent,
+                group_index,
+                group_threshold,
+                group_count: groups.len() as u8,
+                member_index,
+                member_threshold: group.threshold,
+                value,
+            };
+            mnemonics.push(share.to_mnemonic());
+        }
+        result.push(mnemonics);
+    }
+    Ok(result)
+}
+
+/// Recovers master secret from mnemonic shares
+pub fn combine_mnemonics(mnemonics: &[String], passphrase: &str) -> ApiResult<Vec<u8>> {
+    if mnemonics.is_empty() {
+        return Err(ApiError::crypto_slip39_failed("no shares provided"));
+    }
+    check_passphrase(passphrase)?;
+
+    let shares = mnemonics.iter()
+        .map(|mnemonic| Share::from_mnemonic(mnemonic))
+        .collect::<ApiResult<Vec<Share>>>()?;
+
+    let first = &shares[0];
+    let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+    for share in &shares {
+        if share.id != first.id || share.iteration_exponent != first.iteration_exponent {
+            return Err(ApiError::crypto_slip39_failed("shares belong to different secrets"));
+        }
+        if share.group_threshold != first.group_threshold || share.group_count != first.group_count {
+            return Err(ApiError::crypto_slip39_failed("shares have inconsistent group parameters"));
+        }
+        groups.entry(share.group_index).or_insert_with(Vec::new).push(share);
+    }
+
+    if groups.len() < first.group_threshold as usize {
+        return Err(ApiError::crypto_slip39_failed(format!(
+            "insufficient number of groups: {} of {} required",
+            groups.len(), first.group_threshold)));
+    }
+
+    let mut group_secrets = Vec::new();
+    for (group_index, members) in groups.iter().take(first.group_threshold as usize) {
+        let threshold = members[0].member_threshold;
+        if members.iter().any(|share| share.member_threshold != threshold) {
+            return Err(ApiError::crypto_slip39_failed("shares have inconsistent member threshold"));
+        }
+        if members.len() < threshold as usize {
+            return Err(ApiError::crypto_slip39_failed(format!(
+                "insufficient number of shares in group {}: {} of {} required",
+                group_index, members.len(), threshold)));
+        }
+        let member_shares: Vec<(u8, Vec<u8>)> = members.iter()
+            .take(threshold as usize)
+            .map(|share| (share.member_index, share.value.clone()))
+            .collect();
+        group_secrets.push((*group_index, recover(threshold, &member_shares)?));
+    }
+
+    let encrypted = recover(first.group_threshold, &group_secrets)?;
+    Ok(decrypt(&encrypted, passphrase.as_bytes(), first.iteration_exponent, first.id))
+}
+
+fn check_iteration_exponent(iteration_exponent: u8) -> ApiResult<()> {
+    if iteration_exponent > MAX_ITERATION_EXPONENT {
+        Err(ApiError::crypto_slip39_failed(format!(
+            "iteration exponent {} exceeds {}", iteration_exponent, MAX_ITERATION_EXPONENT)))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_passphrase(passphrase: &str) -> ApiResult<()> {
+    if passphrase.bytes().all(|byte| byte >= 32 && byte <= 126) {
+        Ok(())
+    } else {
+        Err(ApiError::crypto_slip39_failed("passphrase must contain only printable ASCII characters"))
+    }
+}
+
+struct Share {
+    id: u16,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Vec<u8>,
+}
+
+impl Share {
+    fn to_mnemonic(&self) -> String {
+        let id_exp = (self.id as u32) << ITERATION_EXPONENT_BITS | self.iteration_exponent as u32;
+        let params = (self.group_index as u32) << 16
+            | ((self.group_threshold - 1) as u32) << 12
+            | ((self.group_count - 1) as u32) << 8
+            | (self.member_index as u32) << 4
+            | (self.member_threshold - 1) as u32;
+
+        let mut indices = vec![
+            (id_exp >> RADIX_BITS) as u16, (id_exp & 0x3FF) as u16,
+            (params >> RADIX_BITS) as u16, (params & 0x3FF) as u16,
+        ];
+        indices.extend(bytes_to_indices(&self.value));
+        let checksum = create_checksum(&indices);
+        indices.extend(checksum);
+
+        indices.iter()
+            .map(|index| SLIP39_WORDS[*index as usize])
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    fn from_mnemonic(mnemonic: &str) -> ApiResult<Share> {
+        let indices = mnemonic.split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                SLIP39_WORDS.binary_search(&word.as_str())
+                    .map(|index| index as u16)
+                    .map_err(|_| ApiError::crypto_slip39_failed(format!("invalid word `{}`", word)))
+            })
+            .collect::<ApiResult<Vec<u16>>>()?;
+
+        let min_words = METADATA_WORDS + CHECKSUM_WORDS
+            + (MIN_SECRET_LENGTH * 8 + RADIX_BITS - 1) / RADIX_BITS;
+        if indices.len() < min_words {
+            return Err(ApiError::crypto_slip39_failed(format!(
+                "share must contain at least {} words", min_words)));
+        }
+        if !verify_checksum(&indices) {
+            return Err(ApiError::crypto_slip39_failed(format!("invalid checksum of share `{}`", mnemonic)));
+        }
+
+        let id_exp = (indices[0] as u32) << RADIX_BITS | indices[1] as u32;
+        let params = (indices[2] as u32) << RADIX_BITS | indices[3] as u32;
+
+        let share = Share {
+            id: (id_exp >> ITERATION_EXPONENT_BITS) as u16,
+            iteration_exponent: (id_exp & ((1 << ITERATION_EXPONENT_BITS) - 1)) as u8,
+            group_index: (params >> 16) as u8 & 0xF,
+            group_threshold: (params >> 12) as u8 % 16 + 1,
+            group_count: (params >> 8) as u8 % 16 + 1,
+            member_index: (params >> 4) as u8 & 0xF,
+            member_threshold: params as u8 % 16 + 1,
+            value: indices_to_bytes(&indices[METADATA_WORDS..indices.len() - CHECKSUM_WORDS])?,
+        };
+        if share.group_threshold > share.group_count {
+            return Err(ApiError::crypto_slip39_failed("group threshold exceeds the number of groups"));
+        }
+        check_iteration_exponent(share.iteration_exponent)?;
+        Ok(share)
+    }
+}
+
+// Share value is left padded with zero bits to the multiple of word length
+fn bytes_to_indices(bytes: &[u8]) -> Vec<u16> {
+    let word_count = (bytes.len() * 8 + RADIX_BITS - 1) / RADIX_BITS;
+    let mut bits = vec![false; word_count * RADIX_BITS - bytes.len() * 8];
+    for byte in bytes {
+        for i in (0..8).rev() {
+            bits.push((byte >> i) & 1 == 1);
+        }
+    }
+    bits.chunks(RADIX_BITS)
+        .map(|chunk| chunk.iter().fold(0u16, |acc, bit| acc << 1 | *bit as u16))
+        .collect()
+}
+
+fn indices_to_bytes(indices: &[u16]) -> ApiResult<Vec<u8>> {
+    let padding = indices.len() * RADIX_BITS % 16;
+    if padding > 8 {
+        return Err(ApiError::crypto_slip39_failed("invalid share length"));
+    }
+    let mut bits = Vec::new();
+    for index in indices {
+        for i in (0..RADIX_BITS).rev() {
+            bits.push((index >> i) & 1 == 1);
+        }
+    }
+    if bits[..padding].iter().any(|bit| *bit) {
+        return Err(ApiError::crypto_slip39_failed("invalid share padding"));
+    }
+    Ok(bits[padding..].chunks(8)
+        .map(|chunk| chunk.iter().fold(0u8, |acc, bit| acc << 1 | *bit as u8))
+        .collect())
+}
+
+// RS1024 checksum
+
+fn polymod(values: &[u32]) -> u32 {
+    const GEN: [u32; 10] = [
+        0xE0E040, 0x1C1C080, 0x3838100, 0x7070200, 0xE0E0009,
+        0x1C0C2412, 0x38086C24, 0x3090FC48, 0x21B1F890, 0x3F3F120,
+    ];
+    let mut chk = 1u32;
+    for value in values {
+        let b = chk >> 20;
+        chk = (chk & 0xFFFFF) << 10 ^ value;
+        for i in 0..10 {
+            if (b >> i) & 1 == 1 {
+                chk ^= GEN[i];
+            }
+        }
+    }
+    chk
+}
+
+fn checksum_data(indices: &[u16]) -> Vec<u32> {
+    CUSTOMIZATION_STRING.iter()
+        .map(|byte| *byte as u32)
+        .chain(indices.iter().map(|index| *index as u32))
+        .collect()
+}
+
+fn create_checksum(indices: &[u16]) -> Vec<u16> {
+    let mut values = checksum_data(indices);
+    values.extend_from_slice(&[0; CHECKSUM_WORDS]);
+    let polymod = polymod(&values) ^ 1;
+    (0..CHECKSUM_WORDS)
+        .map(|i| ((polymod >> (RADIX_BITS * (CHECKSUM_WORDS - 1 - i))) & 0x3FF) as u16)
+        .collect()
+}
+
+fn verify_checksum(indices: &[u16]) -> bool {
+    polymod(&checksum_data(indices)) == 1
+}
+
+// Master secret encryption: 4 round Feistel network with PBKDF2 round function
+
+fn round_function(round: u8, passphrase: &[u8], iteration_exponent: u8, salt: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut password = vec![round];
+    password.extend_from_slice(passphrase);
+    let mut salt = salt.to_vec();
+    salt.extend_from_slice(data);
+
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / ROUND_COUNT as u32;
+    let mut result = vec![0u8; data.len()];
+    pbkdf2::<Hmac<Sha256>>(&password, &salt, iterations as usize, &mut result);
+    result
+}
+
+fn feistel(data: &[u8], passphrase: &[u8], iteration_exponent: u8, id: u16, rounds: &[u8]) -> Vec<u8> {
+    let mut salt = CUSTOMIZATION_STRING.to_vec();
+    salt.push((id >> 8) as u8);
+    salt.push(id as u8);
+
+    let (left, right) = data.split_at(data.len() / 2);
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+    for round in rounds {
+        let f = round_function(*round, passphrase, iteration_exponent, &salt, &right);
+        let next = left.iter().zip(f).map(|(l, f)| l ^ f).collect();
+        left = std::mem::replace(&mut right, next);
+    }
+    right.extend(left);
+    right
+}
+
+fn encrypt(master_secret: &[u8], passphrase: &[u8], iteration_exponent: u8, id: u16) -> Vec<u8> {
+    feistel(master_secret, passphrase, iteration_exponent, id, &[0, 1, 2, 3])
+}
+
+fn decrypt(encrypted: &[u8], passphrase: &[u8], iteration_exponent: u8, id: u16) -> Vec<u8> {
+    feistel(encrypted, passphrase, iteration_exponent, id, &[3, 2, 1, 0])
+}
+
+// Shamir's secret sharing over GF(256)
+
+struct GaloisField {
+    exp: [u8; 255],
+    log: [u8; 256],
+}
+
+impl GaloisField {
+    fn new() -> Self {
+        let mut field = GaloisField { exp: [0; 255], log: [0; 256] };
+        let mut poly: u16 = 1;
+        for i in 0..255 {
+            field.exp[i] = poly as u8;
+            field.log[poly as usize] = i as u8;
+            // multiply by generator x + 1 modulo x^8 + x^4 + x^3 + x + 1
+            poly = (poly << 1) ^ poly;
+            if poly & 0x100 != 0 {
+                poly ^= 0x11B;
+            }
+        }
+        field
+    }
+}
+
+lazy_static! {
+    static ref GF256: GaloisField = GaloisField::new();
+}
+
+fn interpolate(shares: &[(u8, Vec<u8>)], x: u8) -> ApiResult<Vec<u8>> {
+    if let Some((_, value)) = shares.iter().find(|(index, _)| *index == x) {
+        return Ok(value.clone());
+    }
+    let len = shares[0].1.len();
+    if shares.iter().any(|(_, value)| value.len() != len) {
+        return Err(ApiError::crypto_slip39_failed("shares have different lengths"));
+    }
+
+    for (i, (index, _)) in shares.iter().enumerate() {
+        if shares[..i].iter().any(|(other, _)| other == index) {
+            return Err(ApiError::crypto_slip39_failed("share indices must be unique"));
+        }
+    }
+
+    let mut result = vec![0u8; len];
+    for (xi, yi) in shares {
+        let mut log_basis = 0i32;
+        for (xj, _) in shares {
+            if xj == xi {
+                continue;
+            }
+            log_basis += GF256.log[(x ^ xj) as usize] as i32 - GF256.log[(xi ^ xj) as usize] as i32;
+        }
+        let log_basis = (log_basis % 255 + 255) % 255;
+        for (result, y) in result.iter_mut().zip(yi) {
+            if *y != 0 {
+                *result ^= GF256.exp[((GF256.log[*y as usize] as i32 + log_basis) % 255) as usize];
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn create_digest(random_data: &[u8], secret: &[u8]) -> Vec<u8> {
+    let mut hmac: Hmac<Sha256> = Hmac::new_varkey(random_data).unwrap();
+    hmac.input(secret);
+    hmac.result().code()[..DIGEST_LENGTH].to_vec()
+}
+
+fn split(threshold: u8, share_count: u8, secret: &[u8]) -> ApiResult<Vec<(u8, Vec<u8>)>> {
+    if threshold < 1 || threshold > share_count || share_count > MAX_SHARE_COUNT {
+        return Err(ApiError::crypto_slip39_failed(format!(
+            "invalid threshold {} of {} shares", threshold, share_count)));
+    }
+    if threshold == 1 {
+        return Ok((0..share_count).map(|index| (index, secret.to_vec())).collect());
+    }
+
+    let random_share_count = threshold - 2;
+    let mut shares: Vec<(u8, Vec<u8>)> = (0..random_share_count)
+        .map(|index| (index, generate_bytes(secret.len())))
+        .collect();
+
+    let random_part = generate_bytes(secret.len() - DIGEST_LENGTH);
+    let mut digest = create_digest(&random_part, secret);
+    digest.extend(random_part);
+
+    let mut base_shares = shares.clone();
+    base_shares.push((DIGEST_INDEX, digest));
+    base_shares.push((SECRET_INDEX, secret.to_vec()));
+
+    for index in random_share_count..share_count {
+        shares.push((index, interpolate(&base_shares, index)?));
+    }
+    Ok(shares)
+}
+
+fn recover(threshold: u8, shares: &[(u8, Vec<u8>)]) -> ApiResult<Vec<u8>> {
+    if threshold == 1 {
+        return Ok(shares[0].1.clone());
+    }
+    let secret = interpolate(shares, SECRET_INDEX)?;
+    let digest_share = interpolate(shares, DIGEST_INDEX)?;
+    let (digest, random_part) = digest_share.split_at(DIGEST_LENGTH);
+    if digest != &create_digest(random_part, &secret)[..] {
+        return Err(ApiError::crypto_slip39_failed("invalid digest of the shared secret"));
+    }
+    Ok(secret)
+}
+
+const SLIP39_WORDS: [&str; 1024] = [
+    "academic", "acid", "acne", "acquire", "acrobat", "activity", "actress", "adapt", "adequate",
+    "adjust", "admit", "adorn", "adult", "advance", "advocate", "afraid", "again", "agency",
+    "agree", "aide", "aircraft", "airline", "airport", "ajar", "alarm", "album", "alcohol", "alien",
+    "alive", "alpha", "already", "alto", "aluminum", "always", "amazing", "ambition", "amount",
+    "amuse", "analysis", "anatomy", "ancestor", "ancient", "angel", "angry", "animal", "answer",
+    "antenna", "anxiety", "apart", "aquatic", "arcade", "arena", "argue", "armed", "artist",
+    "artwork", "aspect", "auction", "august", "aunt", "average", "aviation", "avoid", "award",
+    "away", "axis", "axle", "beam", "beard", "beaver", "become", "bedroom", "behavior", "being",
+    "believe", "belong", "benefit", "best", "beyond", "bike", "biology", "birthday", "bishop",
+    "black", "blanket", "blessing", "blimp", "blind", "blue", "body", "bolt", "boring", "born",
+    "both", "boundary", "bracelet", "branch", "brave", "breathe", "briefing", "broken", "brother",
+    "browser", "bucket", "budget", "building", "bulb", "bulge", "bumpy", "bundle", "burden",
+    "burning", "busy", "buyer", "cage", "calcium", "camera", "campus", "canyon", "capacity",
+    "capital", "capture", "carbon", "cards", "careful", "cargo", "carpet", "carve", "category",
+    "cause", "ceiling", "center", "ceramic", "champion", "change", "charity", "check", "chemical",
+    "chest", "chew", "chubby", "cinema", "civil", "class", "clay", "cleanup", "client", "climate",
+    "clinic", "clock", "clogs", "closet", "clothes", "club", "cluster", "coal", "coastal", "coding",
+    "column", "company", "corner", "costume", "counter", "course", "cover", "cowboy", "cradle",
+    "craft", "crazy", "credit", "cricket", "criminal", "crisis", "critical", "crowd", "crucial",
+    "crunch", "crush", "crystal", "cubic", "cultural", "curious", "curly", "custody", "cylinder",
+    "daisy", "damage", "dance", "darkness", "database", "daughter", "deadline", "deal", "debris",
+    "debut", "decent", "decision", "declare", "decorate", "decrease", "deliver", "demand",
+    "density", "deny", "depart", "depend", "depict", "deploy", "describe", "desert", "desire",
+    "desktop", "destroy", "detailed", "detect", "device", "devote", "diagnose", "dictate", "diet",
+    "dilemma", "diminish", "dining", "diploma", "disaster", "discuss", "disease", "dish", "dismiss",
+    "display", "distance", "dive", "divorce", "document", "domain", "domestic", "dominant", "dough",
+    "downtown", "dragon", "dramatic", "dream", "dress", "drift", "drink", "drove", "drug", "dryer",
+    "duckling", "duke", "duration", "dwarf", "dynamic", "early", "earth", "easel", "easy", "echo",
+    "eclipse", "ecology", "edge", "editor", "educate", "either", "elbow", "elder", "election",
+    "elegant", "element", "elephant", "elevator", "elite", "else", "email", "emerald", "emission",
+    "emperor", "emphasis", "employer", "empty", "ending", "endless", "endorse", "enemy", "energy",
+    "enforce", "engage", "enjoy", "enlarge", "entrance", "envelope", "envy", "epidemic", "episode",
+    "equation", "equip", "eraser", "erode", "escape", "estate", "estimate", "evaluate", "evening",
+    "evidence", "evil", "evoke", "exact", "example", "exceed", "exchange", "exclude", "excuse",
+    "execute", "exercise", "exhaust", "exotic", "expand", "expect", "explain", "express", "extend",
+    "extra", "eyebrow", "facility", "fact", "failure", "faint", "fake", "false", "family", "famous",
+    "fancy", "fangs", "fantasy", "fatal", "fatigue", "favorite", "fawn", "fiber", "fiction",
+    "filter", "finance", "findings", "finger", "firefly", "firm", "fiscal", "fishing", "fitness",
+    "flame", "flash", "flavor", "flea", "flexible", "flip", "float", "floral", "fluff", "focus",
+    "forbid", "force", "forecast", "forget", "formal", "fortune", "forward", "founder", "fraction",
+    "fragment", "frequent", "freshman", "friar", "fridge", "friendly", "frost", "froth", "frozen",
+    "fumes", "funding", "furl", "fused", "galaxy", "game", "garbage", "garden", "garlic",
+    "gasoline", "gather", "general", "genius", "genre", "genuine", "geology", "gesture", "glad",
+    "glance", "glasses", "glen", "glimpse", "goat", "golden", "graduate", "grant", "grasp",
+    "gravity", "gray", "greatest", "grief", "grill", "grin", "grocery", "gross", "group", "grownup",
+    "grumpy", "guard", "guest", "guilt", "guitar", "gums", "hairy", "hamster", "hand", "hanger",
+    "harvest", "have", "havoc", "hawk", "hazard", "headset", "health", "hearing", "heat", "helpful",
+    "herald", "herd", "hesitate", "hobo", "holiday", "holy", "home", "hormone", "hospital", "hour",
+    "huge", "human", "humidity", "hunting", "husband", "hush", "husky", "hybrid", "idea",
+    "identify", "idle", "image", "impact", "imply", "improve", "impulse", "include", "income",
+    "increase", "index", "indicate", "industry", "infant", "inform", "inherit", "injury", "inmate",
+    "insect", "inside", "install", "intend", "intimate", "invasion", "involve", "iris", "island",
+    "isolate", "item", "ivory", "jacket", "jerky", "jewelry", "join", "judicial", "juice", "jump",
+    "junction", "junior", "junk", "jury", "justice", "kernel", "keyboard", "kidney", "kind",
+    "kitchen", "knife", "knit", "laden", "ladle", "ladybug", "lair", "lamp", "language", "large",
+    "laser", "laundry", "lawsuit", "leader", "leaf", "learn", "leaves", "lecture", "legal",
+    "legend", "legs", "lend", "length", "level", "liberty", "library", "license", "lift", "likely",
+    "lilac", "lily", "lips", "liquid", "listen", "literary", "living", "lizard", "loan", "lobe",
+    "location", "losing", "loud", "loyalty", "luck", "lunar", "lunch", "lungs", "luxury", "lying",
+    "lyrics", "machine", "magazine", "maiden", "mailman", "main", "makeup", "making", "mama",
+    "manager", "mandate", "mansion", "manual", "marathon", "march", "market", "marvel", "mason",
+    "material", "math", "maximum", "mayor", "meaning", "medal", "medical", "member", "memory",
+    "mental", "merchant", "merit", "method", "metric", "midst", "mild", "military", "mineral",
+    "minister", "miracle", "mixed", "mixture", "mobile", "modern", "modify", "moisture", "moment",
+    "morning", "mortgage", "mother", "mountain", "mouse", "move", "much", "mule", "multiple",
+    "muscle", "museum", "music", "mustang", "nail", "national", "necklace", "negative", "nervous",
+    "network", "news", "nuclear", "numb", "numerous", "nylon", "oasis", "obesity", "object",
+    "observe", "obtain", "ocean", "often", "olympic", "omit", "oral", "orange", "orbit", "order",
+    "ordinary", "organize", "ounce", "oven", "overall", "owner", "paces", "pacific", "package",
+    "paid", "painting", "pajamas", "pancake", "pants", "papa", "paper", "parcel", "parking",
+    "party", "patent", "patrol", "payment", "payroll", "peaceful", "peanut", "peasant", "pecan",
+    "penalty", "pencil", "percent", "perfect", "permit", "petition", "phantom", "pharmacy", "photo",
+    "phrase", "physics", "pickup", "picture", "piece", "pile", "pink", "pipeline", "pistol",
+    "pitch", "plains", "plan", "plastic", "platform", "playoff", "pleasure", "plot", "plunge",
+    "practice", "prayer", "preach", "predator", "pregnant", "premium", "prepare", "presence",
+    "prevent", "priest", "primary", "priority", "prisoner", "privacy", "prize", "problem",
+    "process", "profile", "program", "promise", "prospect", "provide", "prune", "public", "pulse",
+    "pumps", "punish", "puny", "pupal", "purchase", "purple", "python", "quantity", "quarter",
+    "quick", "quiet", "race", "racism", "radar", "railroad", "rainbow", "raisin", "random",
+    "ranked", "rapids", "raspy", "reaction", "realize", "rebound", "rebuild", "recall", "receiver",
+    "recover", "regret", "regular", "reject", "relate", "remember", "remind", "remove", "render",
+    "repair", "repeat", "replace", "require", "rescue", "research", "resident", "response",
+    "result", "retailer", "retreat", "reunion", "revenue", "review", "reward", "rhyme", "rhythm",
+    "rich", "rival", "river", "robin", "rocky", "romantic", "romp", "roster", "round", "royal",
+    "ruin", "ruler", "rumor", "sack", "safari", "salary", "salon", "salt", "satisfy", "satoshi",
+    "saver", "says", "scandal", "scared", "scatter", "scene", "scholar", "science", "scout",
+    "scramble", "screw", "script", "scroll", "seafood", "season", "secret", "security", "segment",
+    "senior", "shadow", "shaft", "shame", "shaped", "sharp", "shelter", "sheriff", "short",
+    "should", "shrimp", "sidewalk", "silent", "silver", "similar", "simple", "single", "sister",
+    "skin", "skunk", "slap", "slavery", "sled", "slice", "slim", "slow", "slush", "smart", "smear",
+    "smell", "smirk", "smith", "smoking", "smug", "snake", "snapshot", "sniff", "society",
+    "software", "soldier", "solution", "soul", "source", "space", "spark", "speak", "species",
+    "spelling", "spend", "spew", "spider", "spill", "spine", "spirit", "spit", "spray", "sprinkle",
+    "square", "squeeze", "stadium", "staff", "standard", "starting", "station", "stay", "steady",
+    "step", "stick", "stilt", "story", "strategy", "strike", "style", "subject", "submit", "sugar",
+    "suitable", "sunlight", "superior", "surface", "surprise", "survive", "sweater", "swimming",
+    "swing", "switch", "symbolic", "sympathy", "syndrome", "system", "tackle", "tactics", "tadpole",
+    "talent", "task", "taste", "taught", "taxi", "teacher", "teammate", "teaspoon", "temple",
+    "tenant", "tendency", "tension", "terminal", "testify", "texture", "thank", "that", "theater",
+    "theory", "therapy", "thorn", "threaten", "thumb", "thunder", "ticket", "tidy", "timber",
+    "timely", "ting", "tofu", "together", "tolerate", "total", "toxic", "tracks", "traffic",
+    "training", "transfer", "trash", "traveler", "treat", "trend", "trial", "tricycle", "trip",
+    "triumph", "trouble", "true", "trust", "twice", "twin", "type", "typical", "ugly", "ultimate",
+    "umbrella", "uncover", "undergo", "unfair", "unfold", "unhappy", "union", "universe", "unkind",
+    "unknown", "unusual", "unwrap", "upgrade", "upstairs", "username", "usher", "usual", "valid",
+    "valuable", "vampire", "vanish", "various", "vegan", "velvet", "venture", "verdict", "verify",
+    "very", "veteran", "vexed", "victim", "video", "view", "vintage", "violence", "viral",
+    "visitor", "visual", "vitamins", "vocal", "voice", "volume", "voter", "voting", "walnut",
+    "warmth", "warn", "watch", "wavy", "wealthy", "weapon", "webcam", "welcome", "welfare",
+    "western", "width", "wildlife", "window", "wine", "wireless", "wisdom", "withdraw", "wits",
+    "wolf", "woman", "work", "worthy", "wrap", "wrist", "writing", "wrote", "year", "yelp", "yield",
+    "yoga", "zero",
+];
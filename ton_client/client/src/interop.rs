@@ -13,6 +13,8 @@
 */
 
 use client::*;
+use crypto::signing_box::{SigningBoxes, SigningBoxHandle, CallbackSigningBox};
+use std::os::raw::c_void;
 use std::ptr::null;
 
 // Rust exported functions
@@ -72,6 +74,26 @@ pub unsafe extern "C" fn tc_destroy_json_response(
     drop(response);
 }
 
+/// Registers external signer. `public_key` must point to 32 bytes of Ed25519 public key.
+/// Returned handle can be passed as `signingBox` to the methods which sign messages.
+/// `callback` can be called from any thread with `user_data` until the box is unregistered.
+#[no_mangle]
+pub unsafe extern "C" fn tc_register_signing_box(
+    public_key: *const u8,
+    callback: InteropSigningCallback,
+    user_data: *mut c_void,
+) -> SigningBoxHandle {
+    let public_key = std::slice::from_raw_parts(public_key, 32).to_vec();
+    SigningBoxes::add(Box::new(CallbackSigningBox::new(public_key, callback, user_data as usize)))
+}
+
+/// Unregisters external signer. Returns after the signing requests in progress are finished,
+/// so `user_data` can be freed then. Must not be called from the callback of the same box.
+#[no_mangle]
+pub unsafe extern "C" fn tc_unregister_signing_box(handle: SigningBoxHandle) {
+    SigningBoxes::remove(handle)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn tc_read_json_response(
     response: *const JsonResponse
@@ -87,6 +109,15 @@ pub unsafe extern "C" fn tc_read_json_response(
 
 pub type InteropContext = u32;
 
+/// Signs `data_len` bytes pointed by `data` and writes 64 bytes of signature
+/// into `signature`. Returns `false` if signing is rejected.
+pub type InteropSigningCallback = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    data: *const u8,
+    data_len: u32,
+    signature: *mut u8,
+) -> bool;
+
 #[repr(C)]
 pub struct InteropString {
     pub content: *const u8,
@@ -43,6 +43,7 @@ extern crate ton_types;
 extern crate ton_block;
 extern crate base64;
 extern crate crc_any;
+extern crate unicode_normalization;
 
 mod types;
 mod dispatch;
@@ -50,6 +51,7 @@ mod client;
 mod setup;
 mod contracts;
 mod crypto;
+mod boc;
 
 #[cfg(feature = "node_interaction")]
 mod queries;
@@ -13,8 +13,17 @@
 */
 
 use crypto::keys::account_decode;
+use crypto::ed25519::generate_keypair;
+use crypto::keystore::{KeyStore, KeyStorage};
+use crypto::nacl::secret_box;
+use crypto::scrypt::scrypt;
+use crypto::signing_box::{SigningBox, SigningBoxes};
+use crypto::hdkey::{HDPrivateKey, HDKeyCompliance};
+use contracts::registry::free_handle;
+use types::ApiResult;
 use ::InteropContext;
 use ::{tc_json_request, InteropString};
+use ::{tc_register_signing_box, tc_unregister_signing_box};
 use ::{tc_read_json_response, tc_destroy_json_response};
 use serde_json::{Value, Map};
 use log::{Metadata, Record, LevelFilter};
@@ -409,3 +418,1142 @@ fn test_address_parsing() {
     assert_eq!(encode_base64(&address, true, true, false).unwrap(), base64);
     assert_eq!(encode_base64(&address, true, true, true).unwrap(), base64_url);
 }
+
+#[test]
+fn test_boc_tree() {
+    let client = TestClient::new();
+
+    let hash = get_map_string(&parse_object(client.request(
+        "boc.get_hash",
+        json!({ "bocBase64": WALLET_CODE_BASE64 }),
+    )), "hash");
+    assert_eq!(hash.len(), 64);
+
+    let depth = parse_object(client.request(
+        "boc.get_depth",
+        json!({ "bocBase64": WALLET_CODE_BASE64 }),
+    ));
+    assert!(depth.get("depth").unwrap().as_u64().unwrap() > 0);
+
+    let tree = parse_object(client.request(
+        "boc.to_tree",
+        json!({ "bocBase64": WALLET_CODE_BASE64 }),
+    ));
+    assert_eq!(tree.get("cellType").unwrap(), "Ordinary");
+
+    let restored = parse_object(client.request(
+        "boc.from_tree",
+        json!({ "tree": tree }),
+    ));
+    assert_eq!(get_map_string(&restored, "hash"), hash);
+
+    let restored_hash = get_map_string(&parse_object(client.request(
+        "boc.get_hash",
+        json!({ "bocBase64": get_map_string(&restored, "bocBase64") }),
+    )), "hash");
+    assert_eq!(restored_hash, hash);
+
+    let with_options = parse_object(client.request(
+        "boc.serialize",
+        json!({
+            "bocBase64": WALLET_CODE_BASE64,
+            "options": { "index": true, "crc32": true }
+        }),
+    ));
+    assert_eq!(get_map_string(&with_options, "hash"), hash);
+    assert_ne!(get_map_string(&with_options, "bocBase64"), get_map_string(&restored, "bocBase64"));
+}
+
+#[derive(Clone)]
+struct MemoryKeyStorage {
+    content: std::sync::Arc<std::sync::Mutex<Option<String>>>,
+}
+
+impl KeyStorage for MemoryKeyStorage {
+    fn load(&self) -> ApiResult<Option<String>> {
+        Ok(self.content.lock().unwrap().clone())
+    }
+
+    fn save(&self, content: &str) -> ApiResult<()> {
+        *self.content.lock().unwrap() = Some(content.to_owned());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_keystore_storage() {
+    let storage = MemoryKeyStorage { content: Default::default() };
+    let keys = generate_keypair().unwrap();
+
+    let mut store = KeyStore::new();
+    store.open_storage(Box::new(storage.clone()), b"password").unwrap();
+    let handle = store.insert(&keys).unwrap();
+
+    let saved = storage.load().unwrap().unwrap();
+    assert!(!saved.contains(&keys.secret));
+
+    store.lock_storage().unwrap();
+    assert!(store.is_locked());
+    assert!(store.key_pair(&handle).is_err());
+    assert!(store.insert(&keys).is_err());
+
+    assert!(store.unlock_storage(b"wrong").is_err());
+    store.unlock_storage(b"password").unwrap();
+    assert_eq!(hex::encode(store.key_pair(&handle).unwrap().public.as_bytes()), keys.public);
+
+    let mut reopened = KeyStore::new();
+    assert!(reopened.open_storage(Box::new(storage.clone()), b"wrong").is_err());
+    reopened.open_storage(Box::new(storage.clone()), b"password").unwrap();
+    assert_eq!(reopened.key_pair(&handle).unwrap().secret.as_bytes(), store.key_pair(&handle).unwrap().secret.as_bytes());
+    assert_ne!(reopened.insert(&keys).unwrap(), handle);
+
+    // keys only in memory are not dropped by opening storage with content
+    let mut unsaved = KeyStore::new();
+    let unsaved_handle = unsaved.insert(&keys).unwrap();
+    assert!(unsaved.open_storage(Box::new(storage.clone()), b"password").is_err());
+    assert!(unsaved.key_pair(&unsaved_handle).is_ok());
+
+    // too costly scrypt parameters are rejected before deriving the key
+    let mut content: Value = serde_json::from_str(&storage.load().unwrap().unwrap()).unwrap();
+    content["logN"] = json!(30);
+    let tampered = MemoryKeyStorage { content: Default::default() };
+    tampered.save(&content.to_string()).unwrap();
+    assert!(KeyStore::new().open_storage(Box::new(tampered.clone()), b"password").is_err());
+
+    // as well as too cheap ones
+    for (name, value) in &[("logN", 11), ("r", 1), ("p", 2)] {
+        let mut content: Value = serde_json::from_str(&storage.load().unwrap().unwrap()).unwrap();
+        content[*name] = json!(value);
+        tampered.save(&content.to_string()).unwrap();
+        assert!(KeyStore::new().open_storage(Box::new(tampered.clone()), b"password").is_err());
+    }
+}
+
+#[test]
+fn test_keystore_reencrypts_cheap_storage() {
+    let keys = generate_keypair().unwrap();
+    let salt = vec![1u8; 32];
+    let nonce = vec![2u8; 24];
+    let key = scrypt(b"password", &salt, 12, 8, 1, 32).unwrap();
+    let data = json!({ "nextHandle": 2, "keys": { "1": keys.clone() } }).to_string();
+    let storage = MemoryKeyStorage { content: Default::default() };
+    storage.save(&json!({
+        "version": 1,
+        "logN": 12,
+        "r": 8,
+        "p": 1,
+        "salt": hex::encode(&salt),
+        "nonce": hex::encode(&nonce),
+        "data": hex::encode(secret_box(data.as_bytes(), &nonce, &key).unwrap()),
+    }).to_string()).unwrap();
+
+    let mut store = KeyStore::new();
+    store.open_storage(Box::new(storage.clone()), b"password").unwrap();
+    assert_eq!(hex::encode(store.key_pair(&"1".to_owned()).unwrap().public.as_bytes()), keys.public);
+
+    let content: Value = serde_json::from_str(&storage.load().unwrap().unwrap()).unwrap();
+    assert_eq!(content["logN"], json!(14));
+    assert_ne!(content["salt"], json!(hex::encode(&salt)));
+
+    // re-encrypted storage is unlocked with the same password
+    store.lock_storage().unwrap();
+    store.unlock_storage(b"password").unwrap();
+    let mut reopened = KeyStore::new();
+    reopened.open_storage(Box::new(storage.clone()), b"password").unwrap();
+    assert_eq!(hex::encode(reopened.key_pair(&"1".to_owned()).unwrap().public.as_bytes()), keys.public);
+}
+
+struct MockSigningBox {
+    keys: ed25519_dalek::Keypair,
+    signed: std::sync::Arc<std::sync::Mutex<Vec<Vec<u8>>>>,
+}
+
+impl SigningBox for MockSigningBox {
+    fn get_public_key(&self) -> ApiResult<Vec<u8>> {
+        Ok(self.keys.public.to_bytes().to_vec())
+    }
+
+    fn sign(&self, data: &[u8]) -> ApiResult<Vec<u8>> {
+        self.signed.lock().unwrap().push(data.to_vec());
+        Ok(self.keys.sign::<sha2::Sha512>(data).to_bytes().to_vec())
+    }
+}
+
+// Reports public key which does not match the signing key
+struct ForgingSigningBox {
+    public: Vec<u8>,
+    keys: ed25519_dalek::Keypair,
+}
+
+impl SigningBox for ForgingSigningBox {
+    fn get_public_key(&self) -> ApiResult<Vec<u8>> {
+        Ok(self.public.clone())
+    }
+
+    fn sign(&self, data: &[u8]) -> ApiResult<Vec<u8>> {
+        Ok(self.keys.sign::<sha2::Sha512>(data).to_bytes().to_vec())
+    }
+}
+
+#[test]
+fn test_signing_box() {
+    let client = TestClient::new();
+    let keys = generate_keypair().unwrap();
+    let signed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let handle = SigningBoxes::add(Box::new(MockSigningBox {
+        keys: keys.decode().unwrap(),
+        signed: signed.clone(),
+    }));
+
+    let public = parse_string(client.request(
+        "crypto.signing_box.get_public_key",
+        json!({ "signingBox": handle }),
+    ));
+    assert_eq!(public, keys.public);
+
+    let signature = parse_string(client.request(
+        "crypto.signing_box.sign",
+        json!({ "signingBox": handle, "message": { "text": "hello" } }),
+    ));
+    assert_eq!(signature.len(), 128);
+    assert_eq!(signed.lock().unwrap().pop().unwrap(), b"hello".to_vec());
+
+    let message = parse_object(client.request(
+        "contracts.run.message",
+        json!({
+            "address": GIVER_ADDRESS,
+            "abi": serde_json::from_str::<Value>(WALLET_ABI).unwrap(),
+            "functionName": "setSubscriptionAccount",
+            "input": { "addr": GIVER_ADDRESS },
+            "signingBox": handle,
+        }),
+    ));
+    assert!(!get_map_string(&message, "messageBodyBase64").is_empty());
+    assert_eq!(signed.lock().unwrap().len(), 1);
+
+    let body = parse_object(client.request(
+        "contracts.run.body",
+        json!({
+            "abi": serde_json::from_str::<Value>(WALLET_ABI).unwrap(),
+            "function": "setSubscriptionAccount",
+            "params": { "addr": GIVER_ADDRESS },
+            "signingBox": handle,
+        }),
+    ));
+    let verified = parse_object(client.request(
+        "contracts.run.verify_signature",
+        json!({
+            "abi": serde_json::from_str::<Value>(WALLET_ABI).unwrap(),
+            "bodyBase64": get_map_string(&body, "bodyBase64"),
+        }),
+    ));
+    assert_eq!(verified["valid"], true);
+    assert_eq!(get_map_string(&verified, "publicKey"), keys.public);
+
+    let forging = SigningBoxes::add(Box::new(ForgingSigningBox {
+        public: keys.decode().unwrap().public.to_bytes().to_vec(),
+        keys: generate_keypair().unwrap().decode().unwrap(),
+    }));
+    assert!(client.request(
+        "crypto.signing_box.sign",
+        json!({ "signingBox": forging, "message": { "text": "hello" } }),
+    ).is_err());
+    SigningBoxes::remove(forging);
+
+    SigningBoxes::remove(handle);
+    assert!(client.request(
+        "crypto.signing_box.get_public_key",
+        json!({ "signingBox": handle }),
+    ).is_err());
+}
+
+struct SlowSigner {
+    keys: ed25519_dalek::Keypair,
+    started: std::sync::atomic::AtomicBool,
+    finished: std::sync::atomic::AtomicBool,
+}
+
+unsafe extern "C" fn slow_sign(
+    user_data: *mut std::os::raw::c_void,
+    data: *const u8,
+    data_len: u32,
+    signature: *mut u8,
+) -> bool {
+    let signer = &*(user_data as *const SlowSigner);
+    signer.started.store(true, std::sync::atomic::Ordering::SeqCst);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let data = std::slice::from_raw_parts(data, data_len as usize);
+    let signed = signer.keys.sign::<sha2::Sha512>(data).to_bytes();
+    std::ptr::copy_nonoverlapping(signed.as_ptr(), signature, signed.len());
+    signer.finished.store(true, std::sync::atomic::Ordering::SeqCst);
+    true
+}
+
+#[test]
+fn test_unregister_signing_box_waits_for_signing() {
+    let signer = std::sync::Arc::new(SlowSigner {
+        keys: generate_keypair().unwrap().decode().unwrap(),
+        started: Default::default(),
+        finished: Default::default(),
+    });
+    let handle = unsafe {
+        tc_register_signing_box(
+            signer.keys.public.as_bytes().as_ptr(),
+            slow_sign,
+            &*signer as *const SlowSigner as *mut std::os::raw::c_void)
+    };
+
+    let signing = std::thread::spawn(move || SigningBoxes::sign(handle, b"hello").is_ok());
+    while !signer.started.load(std::sync::atomic::Ordering::SeqCst) {
+        std::thread::yield_now();
+    }
+    unsafe { tc_unregister_signing_box(handle) };
+    // callback is not running after unregister returns, so user data can be freed
+    assert!(signer.finished.load(std::sync::atomic::Ordering::SeqCst));
+    assert!(signing.join().unwrap());
+    assert!(SigningBoxes::sign(handle, b"hello").is_err());
+}
+
+#[test]
+fn test_multisig_payload() {
+    let client = TestClient::new();
+    let first = generate_keypair().unwrap();
+    let second = generate_keypair().unwrap();
+
+    let payload = parse_object(client.request(
+        "contracts.multisig.confirm",
+        json!({ "address": GIVER_ADDRESS, "transactionId": "0x5e00000000000001" }),
+    ));
+    let payload = parse_object(client.request(
+        "contracts.multisig.sign",
+        json!({ "payload": payload, "keyPair": first }),
+    ));
+    // the second custodian call gets own timestamp
+    let call = serde_json::from_value::<ton_sdk::MultisigPayload>(Value::Object(payload.clone())).unwrap()
+        .custodian_call_with_time(1_600_000_000_000).unwrap();
+    let signature = second.decode().unwrap().sign::<sha2::Sha512>(&call.unsigned_hash().unwrap()).to_bytes();
+    let payload = parse_object(client.request(
+        "contracts.multisig.add_signature",
+        json!({
+            "payload": payload,
+            "call": call,
+            "publicKeyHex": second.public,
+            "signatureHex": hex::encode(&signature[..]),
+        }),
+    ));
+    let signatures = payload["signatures"].as_array().unwrap();
+    assert_eq!(signatures.len(), 2);
+    // every custodian signs own message with own timestamp
+    assert_ne!(signatures[0]["unsignedMessage"], signatures[1]["unsignedMessage"]);
+
+    let checks = parse_object(client.request(
+        "contracts.multisig.verify",
+        json!({ "payload": payload }),
+    ));
+    let checks = checks["signatures"].as_array().unwrap();
+    assert!(checks.iter().all(|check| check["valid"] == Value::Bool(true)));
+
+    let messages = parse_object(client.request(
+        "contracts.multisig.messages",
+        json!({ "payload": payload }),
+    ));
+    let messages = messages["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_ne!(messages[0]["messageBodyBase64"], messages[1]["messageBodyBase64"]);
+
+    // signature of the other data is rejected
+    let call = parse_object(client.request(
+        "contracts.multisig.custodian_call",
+        json!({ "payload": payload }),
+    ));
+    assert!(!get_map_string(&call, "dataToSign").is_empty());
+    assert!(client.request(
+        "contracts.multisig.add_signature",
+        json!({
+            "payload": payload,
+            "call": call,
+            "publicKeyHex": first.public,
+            "signatureHex": "00".repeat(64),
+        }),
+    ).is_err());
+
+    let submit = parse_object(client.request(
+        "contracts.multisig.submit",
+        json!({
+            "address": GIVER_ADDRESS,
+            "dest": GIVER_ADDRESS,
+            "value": "1000000000",
+            "bounce": false,
+        }),
+    ));
+    let submit = parse_object(client.request(
+        "contracts.multisig.sign",
+        json!({ "payload": submit, "keyPair": first }),
+    ));
+    assert!(client.request(
+        "contracts.multisig.sign",
+        json!({ "payload": submit, "keyPair": second }),
+    ).is_err());
+}
+
+#[test]
+fn test_verify_signature() {
+    let client = TestClient::new();
+    let keys = generate_keypair().unwrap();
+    let abi = serde_json::from_str::<Value>(WALLET_ABI).unwrap();
+
+    let message = parse_object(client.request(
+        "contracts.run.body",
+        json!({
+            "abi": abi,
+            "function": "setSubscriptionAccount",
+            "params": { "addr": GIVER_ADDRESS },
+            "keyPair": keys,
+        }),
+    ));
+
+    let result = parse_object(client.request(
+        "contracts.run.verify_signature",
+        json!({
+            "abi": abi,
+            "bodyBase64": get_map_string(&message, "bodyBase64"),
+        }),
+    ));
+    assert_eq!(result["function"], "setSubscriptionAccount");
+    assert_eq!(result["valid"], true);
+    assert_eq!(get_map_string(&result, "publicKey"), keys.public);
+}
+
+#[test]
+fn test_slip10_ed25519_vectors() {
+    // SLIP-0010 test vectors for ed25519: (path, chain code, private key, public key)
+    let vectors = vec![
+        ("000102030405060708090a0b0c0d0e0f", vec![
+            ("m",
+                "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+                "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+                "a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed"),
+            ("m/0'",
+                "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+                "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+                "8c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c"),
+            ("m/0'/1'",
+                "a320425f77d1b5c2505a6b1b27382b37368ee640e3557c315416801243552f14",
+                "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+                "1932a5270f335bed617d5b935c80aedb1a35bd9fc1e31acafd5372c30f5c1187"),
+            ("m/0'/1'/2'",
+                "2e69929e00b5ab250f49c3fb1c12f252de4fed2c1db88387094a0f8c4c9ccd6c",
+                "92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9",
+                "ae98736566d30ed0e9d2f4486a64bc95740d89c7db33f52121f8ea8f76ff0fc1"),
+            ("m/0'/1'/2'/2'",
+                "8f6d87f93d750e0efccda017d662a1b31a266e4a6f5993b15f5c1f07f74dd5cc",
+                "30d1dc7e5fc04c31219ab25a27ae00b50f6fd66622f6e9c913253d6511d1e662",
+                "8abae2d66361c879b900d204ad2cc4984fa2aa344dd7ddc46007329ac76c429c"),
+            ("m/0'/1'/2'/2'/1000000000'",
+                "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+                "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+                "3c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a"),
+        ]),
+        ("fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542", vec![
+            ("m",
+                "ef70a74db9c3a5af931b5fe73ed8e1a53464133654fd55e7a66f8570b8e33c3b",
+                "171cb88b1b3c1db25add599712e36245d75bc65a1a5c9e18d76f9f2b1eab4012",
+                "8fe9693f8fa62a4305a140b9764c5ee01e455963744fe18204b4fb948249308a"),
+            ("m/0'",
+                "0b78a3226f915c082bf118f83618a618ab6dec793752624cbeb622acb562862d",
+                "1559eb2bbec5790b0c65d8693e4d0875b1747f4970ae8b650486ed7470845635",
+                "86fab68dcb57aa196c77c5f264f215a112c22a912c10d123b0d03c3c28ef1037"),
+            ("m/0'/2147483647'",
+                "138f0b2551bcafeca6ff2aa88ba8ed0ed8de070841f0c4ef0165df8181eaad7f",
+                "ea4f5bfe8694d8bb74b7b59404632fd5968b774ed545e810de9c32a4fb4192f4",
+                "5ba3b9ac6e90e83effcd25ac4e58a1365a9e35a3d3ae5eb07b9e4d90bcf7506d"),
+            ("m/0'/2147483647'/1'",
+                "73bd9fff1cfbde33a1b846c27085f711c0fe2d66fd32e139d3ebc28e5a4a6b90",
+                "3757c7577170179c7868353ada796c839135b3d30554bbb74a4b1e4a5a58505c",
+                "2e66aa57069c86cc18249aecf5cb5a9cebbfd6fadeab056254763874a9352b45"),
+            ("m/0'/2147483647'/1'/2147483646'",
+                "0902fe8a29f9140480a00ef244bd183e8a13288e4412d8389d140aac1794825a",
+                "5837736c89570de861ebc173b1086da4f505d4adb387c6a1b1342d5e4ac9ec72",
+                "e33c0f7d81d843c572275f287498e8d408654fdf0d1e065b84e2e6f157aab09b"),
+            ("m/0'/2147483647'/1'/2147483646'/2'",
+                "5d70af781f3a37b829f0d060924d5e960bdc02e85423494afc0b1a41bbe196d4",
+                "551d333177df541ad876a60ea71f00447931c0a9da16f227c11ea080d7391b8d",
+                "47150c75db263559a70d5778bf36abbab30fb061ad69f69ece61a72b0cfa4fc0"),
+        ]),
+    ];
+
+    let client = TestClient::new();
+    for (seed, steps) in vectors {
+        let master = HDPrivateKey::from_seed(&hex::decode(seed).unwrap(), HDKeyCompliance::Slip10).unwrap();
+        let xprv = master.serialize_to_string();
+        for (path, chain_code, secret, public) in steps {
+            let derived = master.derive_path(&path.to_owned(), HDKeyCompliance::Slip10).unwrap();
+            assert_eq!(hex::encode(derived.chain_code()), chain_code);
+            assert_eq!(hex::encode(derived.secret()), secret);
+
+            // the same keys are returned by API for the serialized master key
+            let derived = parse_string(client.request(
+                "crypto.hdkey.xprv.derive.path",
+                json!({ "serialized": xprv, "path": path, "compliant": "slip10" }),
+            ));
+            assert_eq!(parse_string(client.request(
+                "crypto.hdkey.xprv.secret",
+                json!({ "serialized": derived, "compliant": "slip10" }),
+            )), secret);
+            assert_eq!(parse_string(client.request(
+                "crypto.hdkey.xprv.public",
+                json!({ "serialized": derived, "compliant": "slip10" }),
+            )), public);
+        }
+
+        // SLIP-0010 key is not accepted by BIP32 derivation
+        for method in &["crypto.hdkey.xprv.derive.path", "crypto.hdkey.xprv.public"] {
+            assert!(client.request(
+                method,
+                json!({ "serialized": xprv, "path": "m/0'", "compliant": true }),
+            ).is_err());
+        }
+    }
+
+    // and BIP32 key is not accepted by SLIP-0010 derivation
+    let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let bip32 = HDPrivateKey::from_seed(&seed, HDKeyCompliance::Bip32).unwrap().serialize_to_string();
+    assert!(client.request(
+        "crypto.hdkey.xprv.derive.path",
+        json!({ "serialized": bip32, "path": "m/0'", "compliant": "slip10" }),
+    ).is_err());
+
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    assert!(client.request(
+        "crypto.mnemonic.derive.sign.keys",
+        json!({
+            "dictionary": 1,
+            "phrase": phrase,
+            "wordCount": 12,
+            "path": "m/44'/396'/0'/0'/0'",
+            "compliant": "slip10",
+        }),
+    ).is_ok());
+
+    // non hardened derivation is not defined for ed25519
+    assert!(client.request(
+        "crypto.mnemonic.derive.sign.keys",
+        json!({
+            "dictionary": 1,
+            "phrase": phrase,
+            "wordCount": 12,
+            "path": "m/44'/396'/0'/0/0",
+            "compliant": "slip10",
+        }),
+    ).is_err());
+}
+
+#[test]
+fn test_mnemonic_suggest() {
+    let client = TestClient::new();
+    let valid = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    let is_valid = client.request(
+        "crypto.mnemonic.verify",
+        json!({
+            "dictionary": 1,
+            "wordCount": 12,
+            "phrase": format!("  Abandon\t{}\n", &valid[8..]),
+        }),
+    ).unwrap();
+    assert_eq!(is_valid, "true");
+
+    let suggest = |phrase: &str| parse_object(client.request(
+        "crypto.mnemonic.suggest",
+        json!({ "dictionary": 1, "wordCount": 12, "phrase": phrase }),
+    ));
+
+    let result = suggest("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandn abandon about");
+    assert_eq!(result["valid"], false);
+    assert_eq!(result["words"][0]["position"], 9);
+    assert_eq!(result["words"][0]["suggestions"][0], "abandon");
+    assert_eq!(result["phrases"][0], valid);
+
+    // checksum fails because of swapped words
+    let result = suggest("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about abandon");
+    assert!(result["words"].as_array().unwrap().is_empty());
+    assert!(result["phrases"].as_array().unwrap().contains(&Value::from(valid)));
+
+    let result = suggest(valid);
+    assert_eq!(result["valid"], true);
+
+    // Czech and Portuguese wordlists are not available
+    for dictionary in &[9, 10] {
+        assert!(client.request(
+            "crypto.mnemonic.from.random",
+            json!({ "dictionary": dictionary, "wordCount": 12 }),
+        ).is_err());
+    }
+}
+
+#[test]
+fn test_slip39() {
+    let client = TestClient::new();
+
+    // SLIP-0039 test vector, passphrase "TREZOR"
+    let phrase = parse_string(client.request(
+        "crypto.mnemonic.slip39.combine",
+        json!({
+            "dictionary": 1,
+            "wordCount": 12,
+            "shares": [
+                "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard"
+            ],
+            "passphrase": "TREZOR",
+        }),
+    ));
+    let expected = parse_string(client.request(
+        "crypto.mnemonic.from.entropy",
+        json!({
+            "dictionary": 1,
+            "wordCount": 12,
+            "entropy": { "hex": "bb54aac4b89dc868ba37d9cc21b2cece" },
+        }),
+    ));
+    assert_eq!(phrase, expected);
+
+    let combine_error = |shares: Vec<&str>| {
+        let error = client.request(
+            "crypto.mnemonic.slip39.combine",
+            json!({ "dictionary": 1, "wordCount": 12, "shares": shares, "passphrase": "TREZOR" }),
+        ).unwrap_err();
+        let error: Value = serde_json::from_str(&error).unwrap();
+        error["message"].as_str().unwrap().to_owned()
+    };
+    // SLIP-0039 test vectors which must fail: invalid checksum, invalid padding, mixed identifiers
+    assert!(combine_error(vec![
+        "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision kidney"
+    ]).contains("invalid checksum"));
+    assert!(combine_error(vec![
+        "duckling enlarge academic academic email result length solution fridge kidney coal piece deal husband erode duke ajar music cargo fitness"
+    ]).contains("invalid share padding"));
+    assert!(combine_error(vec![
+        "adequate smoking academic acid debut wine petition glen cluster slow rhyme slow simple epidemic rumor junk tracks treat olympic tolerate",
+        "adequate stay academic agency agency formal party ting frequent learn upstairs remember smear leaf damage anatomy ladle market hush corner",
+    ]).contains("shares belong to different secrets"));
+
+    let combine = |shares: Vec<&String>| client.request(
+        "crypto.mnemonic.slip39.combine",
+        json!({ "dictionary": 1, "wordCount": 12, "shares": shares, "passphrase": "secret" }),
+    );
+
+    let split = parse_object(client.request(
+        "crypto.mnemonic.slip39.split",
+        json!({
+            "dictionary": 1,
+            "wordCount": 12,
+            "phrase": phrase,
+            "groupThreshold": 2,
+            "groups": [{ "threshold": 2, "count": 3 }, { "threshold": 1, "count": 1 }],
+            "passphrase": "secret",
+        }),
+    ));
+    let groups: Vec<Vec<String>> = serde_json::from_value(split["groups"].clone()).unwrap();
+    assert_eq!(groups[0].len(), 3);
+    assert_eq!(groups[1].len(), 1);
+
+    assert_eq!(parse_string(combine(vec![&groups[0][2], &groups[1][0], &groups[0][0]])), phrase);
+    assert_eq!(parse_string(combine(vec![&groups[1][0], &groups[0][1], &groups[0][2]])), phrase);
+    // one share of the first group is not enough
+    assert!(combine(vec![&groups[0][1], &groups[1][0]]).is_err());
+
+    // exponents which make key derivation run for days are rejected
+    assert!(client.request(
+        "crypto.mnemonic.slip39.split",
+        json!({
+            "dictionary": 1,
+            "wordCount": 12,
+            "phrase": phrase,
+            "groupThreshold": 1,
+            "groups": [{ "threshold": 1, "count": 1 }],
+            "iterationExponent": 31,
+        }),
+    ).is_err());
+}
+
+#[test]
+fn test_calc_address() {
+    let client = TestClient::new();
+    let keys = generate_keypair().unwrap();
+    let abi = serde_json::from_str::<Value>(WALLET_ABI).unwrap();
+
+    let deploy_address = parse_string(client.request(
+        "contracts.deploy.address",
+        json!({
+            "abi": abi,
+            "imageBase64": WALLET_CODE_BASE64,
+            "keyPair": keys,
+        }),
+    ));
+
+    let from_image = parse_object(client.request(
+        "contracts.address.from_image",
+        json!({
+            "abi": abi,
+            "imageBase64": WALLET_CODE_BASE64,
+            "publicKey": keys.public,
+            "flags": { "bounce": false, "test": true },
+        }),
+    ));
+    assert_eq!(get_map_string(&from_image, "address"), deploy_address);
+    assert_eq!(from_image["bounce"], false);
+    assert_eq!(from_image["test"], true);
+
+    let address = MsgAddressInt::from_str(&deploy_address).unwrap();
+    assert_eq!(
+        get_map_string(&from_image, "base64"),
+        encode_base64(&address, false, true, false).unwrap());
+    assert_eq!(
+        get_map_string(&from_image, "base64Url"),
+        encode_base64(&address, false, true, true).unwrap());
+    assert_eq!(account_decode(&get_map_string(&from_image, "base64Url")).unwrap(), address);
+
+    let from_state_init = parse_object(client.request(
+        "contracts.address.from_state_init",
+        json!({ "stateInitBase64": WALLET_CODE_BASE64, "workchainId": -1 }),
+    ));
+    let without_key = parse_object(client.request(
+        "contracts.address.from_image",
+        json!({ "abi": abi, "imageBase64": WALLET_CODE_BASE64, "workchainId": -1 }),
+    ));
+    assert_eq!(get_map_string(&from_state_init, "address"), get_map_string(&without_key, "address"));
+    assert!(get_map_string(&from_state_init, "address").starts_with("-1:"));
+    assert_eq!(from_state_init["bounce"], true);
+
+    let code = parse_object(client.request(
+        "contracts.image.code",
+        json!({ "imageBase64": WALLET_CODE_BASE64 }),
+    ));
+    let from_code = parse_object(client.request(
+        "contracts.address.from_code_and_data",
+        json!({ "codeBase64": get_map_string(&code, "codeBase64") }),
+    ));
+    assert_eq!(get_map_string(&from_code, "accountId").len(), 64);
+    assert_ne!(get_map_string(&from_code, "address"), deploy_address);
+}
+
+#[test]
+fn test_parse_address() {
+    let client = TestClient::new();
+    let parse = |address: &str| parse_object(client.request(
+        "contracts.address.parse",
+        json!({ "address": address }),
+    ));
+
+    let result = parse("kf_8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15-KsQHFLbKSMiYIny");
+    assert_eq!(result["valid"], true);
+    assert_eq!(result["format"], "Base64Url");
+    assert_eq!(result["bounceable"], true);
+    assert_eq!(result["testnet"], true);
+    assert_eq!(result["workchainId"], -1);
+    assert_eq!(get_map_string(&result, "raw"), "-1:fcb91a3a3816d0f7b8c2c76108b8a9bc5a6b7a55bd79f8ab101c52db29232260");
+    assert_eq!(get_map_string(&result, "base64"), "kf/8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYIny");
+
+    let result = parse("fcb91a3a3816d0f7b8c2c76108b8a9bc5a6b7a55bd79f8ab101c52db29232260");
+    assert_eq!(result["valid"], false);
+
+    let result = parse_object(client.request(
+        "contracts.address.parse",
+        json!({
+            "address": "fcb91a3a3816d0f7b8c2c76108b8a9bc5a6b7a55bd79f8ab101c52db29232260",
+            "defaultWorkchainId": 0,
+        }),
+    ));
+    assert_eq!(result["format"], "AccountId");
+    assert_eq!(result["bounceable"], Value::Null);
+    assert_eq!(get_map_string(&result, "base64Url"), "EQD8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15-KsQHFLbKSMiYM0w");
+
+    let result = parse("5:abcd");
+    assert_eq!(result["valid"], true);
+    assert_eq!(result["base64"], Value::Null);
+    assert_eq!(result["errors"].as_array().unwrap().len(), 1);
+
+    let result = parse("kf_8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15-KsQHFLbKSMiYIn0");
+    assert_eq!(result["valid"], false);
+    assert_eq!(result["raw"], Value::Null);
+    assert_eq!(result["errors"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_upgrade_code() {
+    let client = TestClient::new();
+    let address = "0:1111111111111111111111111111111111111111111111111111111111111111";
+
+    let code = parse_object(client.request(
+        "contracts.image.code",
+        json!({ "imageBase64": WALLET_CODE_BASE64 }),
+    ));
+
+    let compare = |account: Value| parse_object(client.request(
+        "contracts.upgrade.compare",
+        json!({ "address": address, "account": account, "imageBase64": WALLET_CODE_BASE64 }),
+    ));
+
+    let result = compare(json!({ "id": address, "code": get_map_string(&code, "codeBase64") }));
+    assert_eq!(result["matches"], true);
+    assert_eq!(result["accountCodeHash"], result["imageCodeHash"]);
+
+    let result = compare(json!({ "id": address }));
+    assert_eq!(result["matches"], false);
+    assert_eq!(result["accountCodeHash"], Value::Null);
+    assert_eq!(get_map_string(&result, "imageCodeHash").len(), 64);
+
+    let message = parse_object(client.request(
+        "contracts.upgrade.message",
+        json!({ "address": address, "imageBase64": WALLET_CODE_BASE64, "keyPair": generate_keypair().unwrap() }),
+    ));
+    assert_eq!(get_map_string(&message, "messageId").len(), 64);
+
+    let unsigned = parse_object(client.request(
+        "contracts.upgrade.encode_unsigned_message",
+        json!({ "address": address, "imageBase64": WALLET_CODE_BASE64 }),
+    ));
+    assert_eq!(base64::decode(&get_map_string(&unsigned, "bytesToSignBase64")).unwrap().len(), 32);
+}
+
+#[test]
+fn test_account_info() {
+    let client = TestClient::new();
+    let address = "0:1111111111111111111111111111111111111111111111111111111111111111";
+
+    let code = get_map_string(&parse_object(client.request(
+        "contracts.image.code",
+        json!({ "imageBase64": WALLET_CODE_BASE64 }),
+    )), "codeBase64");
+    let account = json!({ "acc_type": 1, "last_paid": 1576526553, "code": code });
+
+    let info = parse_object(client.request(
+        "contracts.account.info",
+        json!({ "address": address, "account": account }),
+    ));
+    assert_eq!(get_map_string(&info, "address"), address);
+    assert_eq!(get_map_string(&info, "status"), "Active");
+    assert_eq!(info["dataHash"], Value::Null);
+    assert_eq!(info["lastPaid"], 1576526553);
+    assert!(info["depth"].as_u64().unwrap() > 0);
+    assert!(info["storageUsage"]["cells"].as_u64().unwrap() > 1);
+
+    let verified = parse_object(client.request(
+        "contracts.account.verify_code",
+        json!({ "address": address, "account": account, "imageBase64": WALLET_CODE_BASE64 }),
+    ));
+    assert_eq!(verified["matches"], true);
+    assert_eq!(verified["accountCodeHash"], info["codeHash"]);
+
+    let verified = parse_object(client.request(
+        "contracts.account.verify_code",
+        json!({ "address": address, "account": account, "codeHash": "00".repeat(32) }),
+    ));
+    assert_eq!(verified["matches"], false);
+}
+
+#[test]
+fn test_validate_abi() {
+    let client = TestClient::new();
+
+    let result = parse_object(client.request(
+        "contracts.abi.validate",
+        json!({ "abi": serde_json::from_str::<Value>(WALLET_ABI).unwrap() }),
+    ));
+    assert_eq!(result["valid"], true);
+
+    let result = parse_object(client.request(
+        "contracts.abi.validate",
+        json!({ "abi": "{\n\"ABI version\": 1,\n\"functions\": [\n{\"name\": \"f\", \"inputs\": [{\"name\": \"a\", \"type\": \"uint0\"}]}\n]\n}" }),
+    ));
+    assert_eq!(result["valid"], false);
+    assert_eq!(result["errors"][0]["path"], "functions[0].inputs[0].type");
+    assert_eq!(result["errors"][0]["line"], 4);
+}
+
+#[test]
+fn test_abi_schema() {
+    let client = TestClient::new();
+
+    let schema = parse_object(client.request(
+        "contracts.abi.schema",
+        json!({ "abi": serde_json::from_str::<Value>(WALLET_ABI).unwrap() }),
+    ));
+    assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+    let input = &schema["functions"]["sendTransaction"]["input"];
+    assert_eq!(input["required"], json!(["dest", "value", "bounce"]));
+    assert_eq!(input["properties"]["dest"]["description"], "address");
+    assert_eq!(input["additionalProperties"], false);
+
+    let schema_from_text = parse_object(client.request(
+        "contracts.abi.schema",
+        json!({ "abi": WALLET_ABI }),
+    ));
+    assert_eq!(schema_from_text, schema);
+
+    assert!(client.request("contracts.abi.schema", json!({ "abi": "{}" })).is_err());
+}
+
+#[test]
+fn test_invalid_abi_parameter() {
+    let client = TestClient::new();
+    let keys = generate_keypair().unwrap();
+
+    let error = client.request(
+        "contracts.run.body",
+        json!({
+            "abi": serde_json::from_str::<Value>(WALLET_ABI).unwrap(),
+            "function": "sendTransaction",
+            "params": { "dest": GIVER_ADDRESS, "value": -1, "bounce": false },
+            "keyPair": keys,
+        }),
+    ).unwrap_err();
+    let error: Value = serde_json::from_str(&error).unwrap();
+    let message = error["message"].as_str().unwrap();
+    assert!(message.contains("`input.value`"));
+    assert!(message.contains("negative value -1 can not be unsigned"));
+    assert!(message.contains("Expected type: uint128"));
+}
+
+#[test]
+fn test_decode_output_int_format() {
+    use ton_sdk::{AbiContract, Tokenizer, TokenValue};
+    use ton_types::{BuilderData, IBitstring};
+    use ton_types::cells_serialization::BagOfCells;
+
+    let client = TestClient::new();
+    let abi = json!({
+        "ABI version": 1,
+        "functions": [{
+            "name": "getBalance",
+            "inputs": [],
+            "outputs": [
+                {"name": "balance", "type": "uint256"},
+                {"name": "delta", "type": "int64"}
+            ]
+        }]
+    });
+    let max_uint256 = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+    let contract = AbiContract::load(abi.to_string().as_bytes()).unwrap();
+    let function = contract.function("getBalance").unwrap();
+    let tokens = Tokenizer::tokenize_all(
+        &function.output_params(),
+        &json!({ "balance": format!("0x{}", "f".repeat(64)), "delta": -5 }),
+    ).unwrap();
+    let mut builder = BuilderData::new();
+    builder.append_u32(function.get_output_id()).unwrap();
+    let builder = TokenValue::pack_values_into_chain(&tokens, vec![builder], function.layout).unwrap();
+    let mut body = Vec::new();
+    BagOfCells::with_root(&builder.into()).write_to(&mut body, false).unwrap();
+
+    let decode = |format: Option<&str>| {
+        let mut params = json!({
+            "abi": abi,
+            "functionName": "getBalance",
+            "bodyBase64": base64::encode(&body),
+        });
+        if let Some(format) = format {
+            params["intFormat"] = json!(format);
+        }
+        parse_object(client.request("contracts.run.output", params))
+    };
+
+    let output = decode(None);
+    assert_eq!(output["output"], json!({ "balance": format!("0x{}", "f".repeat(64)), "delta": "-0x5" }));
+
+    let output = decode(Some("decimal"));
+    assert_eq!(output["output"], json!({ "balance": max_uint256, "delta": "-5" }));
+
+    let output = decode(Some("number"));
+    assert_eq!(output["output"], json!({ "balance": max_uint256, "delta": -5 }));
+}
+
+#[test]
+fn test_decode_output_paginated() {
+    use ton_sdk::{AbiContract, Tokenizer, TokenValue};
+    use ton_types::{BuilderData, IBitstring};
+    use ton_types::cells_serialization::BagOfCells;
+
+    let client = TestClient::new();
+    let abi = json!({
+        "ABI version": 1,
+        "functions": [{
+            "name": "getItems",
+            "inputs": [],
+            "outputs": [
+                {"name": "items", "type": "uint32[]"},
+                {"name": "owners", "type": "map(uint32,uint64)"}
+            ]
+        }]
+    });
+
+    let contract = AbiContract::load(abi.to_string().as_bytes()).unwrap();
+    let function = contract.function("getItems").unwrap();
+    let tokens = Tokenizer::tokenize_all(
+        &function.output_params(),
+        &json!({
+            "items": (0..10).collect::<Vec<_>>(),
+            "owners": (0..10).map(|i| (i.to_string(), json!(i + 100))).collect::<serde_json::Map<_, _>>(),
+        }),
+    ).unwrap();
+    let mut builder = BuilderData::new();
+    builder.append_u32(function.get_output_id()).unwrap();
+    let builder = TokenValue::pack_values_into_chain(&tokens, vec![builder], function.layout).unwrap();
+    let mut body = Vec::new();
+    BagOfCells::with_root(&builder.into()).write_to(&mut body, false).unwrap();
+
+    let output = parse_object(client.request("contracts.run.output", json!({
+        "abi": abi,
+        "functionName": "getItems",
+        "bodyBase64": base64::encode(&body),
+        "pagination": { "offset": 8, "limit": 5 },
+        "intFormat": "number",
+    })));
+    assert_eq!(output["output"], json!({ "items": [8, 9], "owners": { "8": 108, "9": 109 } }));
+}
+
+#[test]
+fn test_decode_unknown_input_auto() {
+    let client = TestClient::new();
+    let keys = generate_keypair().unwrap();
+    let abi = serde_json::from_str::<Value>(WALLET_ABI).unwrap();
+
+    let body = |internal: bool| {
+        let message = parse_object(client.request(
+            "contracts.run.body",
+            json!({
+                "abi": abi,
+                "function": "setSubscriptionAccount",
+                "params": { "addr": GIVER_ADDRESS },
+                "internal": internal,
+                "keyPair": keys,
+            }),
+        ));
+        get_map_string(&message, "bodyBase64")
+    };
+
+    let external = parse_object(client.request(
+        "contracts.run.unknown.input",
+        json!({ "abi": abi, "bodyBase64": body(false), "detectMessageKind": true }),
+    ));
+    assert_eq!(external["function"], "setSubscriptionAccount");
+    assert_eq!(external["output"], json!({ "addr": GIVER_ADDRESS }));
+    assert_eq!(external["internal"], false);
+    assert_eq!(get_map_string(&external, "publicKey"), keys.public);
+    assert!(external["time"].is_u64());
+
+    let internal = parse_object(client.request(
+        "contracts.run.unknown.input",
+        json!({ "abi": abi, "bodyBase64": body(true), "detectMessageKind": true }),
+    ));
+    assert_eq!(internal["function"], "setSubscriptionAccount");
+    assert_eq!(internal["output"], json!({ "addr": GIVER_ADDRESS }));
+    assert_eq!(internal["internal"], true);
+    assert!(internal["publicKey"].is_null());
+    assert!(internal["time"].is_null());
+
+    // message kind is external by default and checked if not detected
+    let external = parse_object(client.request(
+        "contracts.run.unknown.input",
+        json!({ "abi": abi, "bodyBase64": body(false) }),
+    ));
+    assert_eq!(external["internal"], false);
+    assert!(client.request(
+        "contracts.run.unknown.input",
+        json!({ "abi": abi, "bodyBase64": body(true) }),
+    ).is_err());
+    assert!(client.request(
+        "contracts.run.unknown.input",
+        json!({ "abi": abi, "bodyBase64": body(true), "internal": false }),
+    ).is_err());
+}
+
+#[test]
+fn test_abi_registry() {
+    let client = TestClient::new();
+    let keys = generate_keypair().unwrap();
+    let abi = serde_json::from_str::<Value>(WALLET_ABI).unwrap();
+    let other_abi = json!({
+        "ABI version": 1,
+        "functions": [{ "name": "mint", "inputs": [{ "name": "amount", "type": "uint8" }], "outputs": [] }]
+    });
+    let code_hash = "11".repeat(32);
+
+    let message = parse_object(client.request(
+        "contracts.run.body",
+        json!({
+            "abi": abi,
+            "function": "setSubscriptionAccount",
+            "params": { "addr": GIVER_ADDRESS },
+            "keyPair": keys,
+        }),
+    ));
+    let body = get_map_string(&message, "bodyBase64");
+
+    let registry = client.request("contracts.registry.create", Value::Null).unwrap();
+    let registry: u32 = serde_json::from_str(&registry).unwrap();
+    let add = |name: &str, abi: &Value, code_hashes: Vec<&str>| client.request(
+        "contracts.registry.add",
+        json!({ "registry": registry, "name": name, "abi": abi, "codeHashes": code_hashes }),
+    );
+    add("other", &other_abi, vec![]).unwrap();
+
+    // body is not decoded by ABIs which don't have its function
+    assert!(client.request(
+        "contracts.registry.decode",
+        json!({ "registry": registry, "bodyBase64": body }),
+    ).is_err());
+
+    add("wallet", &abi, vec![code_hash.as_str()]).unwrap();
+    assert!(add("wallet", &abi, vec![]).is_err());
+
+    let decoded = parse_object(client.request(
+        "contracts.registry.decode",
+        json!({ "registry": registry, "bodyBase64": body }),
+    ));
+    assert_eq!(decoded["abiName"], "wallet");
+    assert_eq!(decoded["bodyType"], "functionInput");
+    assert_eq!(decoded["name"], "setSubscriptionAccount");
+    assert_eq!(decoded["params"], json!({ "addr": GIVER_ADDRESS }));
+    assert_eq!(decoded["internal"], false);
+    assert_eq!(get_map_string(&decoded, "publicKey"), keys.public);
+
+    let decoded = parse_object(client.request(
+        "contracts.registry.decode",
+        json!({ "registry": registry, "bodyBase64": body, "codeHash": code_hash, "detectMessageKind": true }),
+    ));
+    assert_eq!(decoded["abiName"], "wallet");
+    assert_eq!(decoded["internal"], false);
+
+    client.request("contracts.registry.drop", json!({ "registry": registry })).unwrap();
+    assert!(client.request(
+        "contracts.registry.decode",
+        json!({ "registry": registry, "bodyBase64": body }),
+    ).is_err());
+
+    // one-shot decoding with ABIs given in the call
+    let abis = json!([
+        { "name": "other", "abi": other_abi },
+        { "name": "wallet", "abi": abi, "codeHashes": [code_hash] },
+    ]);
+    let decoded = parse_object(client.request(
+        "contracts.decode.any",
+        json!({ "abis": abis, "bodyBase64": body, "codeHash": code_hash, "detectMessageKind": true }),
+    ));
+    assert_eq!(decoded["abiName"], "wallet");
+    assert_eq!(decoded["name"], "setSubscriptionAccount");
+    assert_eq!(decoded["params"], json!({ "addr": GIVER_ADDRESS }));
+    assert_eq!(decoded["internal"], false);
+    assert!(client.request(
+        "contracts.decode.any",
+        json!({ "abis": [{ "name": "other", "abi": other_abi }], "bodyBase64": body }),
+    ).is_err());
+
+    // handles of live registries are not reused after the counter wraps around
+    let mut used = std::collections::HashMap::new();
+    used.insert(u32::max_value(), ());
+    used.insert(1, ());
+    assert_eq!(free_handle(u32::max_value(), &used), 2);
+    assert_eq!(free_handle(5, &used), 5);
+}
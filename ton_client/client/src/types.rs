@@ -209,6 +209,11 @@ impl ApiError {
             "Invalid mnemonic dictionary: {}", dictionary)
     }
 
+    pub fn crypto_bip39_unsupported_dictionary(dictionary: u8, name: &str) -> Self {
+        sdk_err!(CryptoBip39InvalidDictionary,
+            "Mnemonic dictionary {} ({}) is not supported yet", dictionary, name)
+    }
+
     pub fn crypto_bip39_invalid_word_count(word_count: u8) -> Self {
         sdk_err!(CryptoBip39InvalidWordCount,
             "Invalid mnemonic word count: {}", word_count)
@@ -244,6 +249,31 @@ impl ApiError {
             "Either Key or Keystore Handle must be specified".into())
     }
 
+    pub fn crypto_keystore_locked() -> Self {
+        ApiError::sdk(CryptoKeystoreLocked,
+            "Keystore is locked".into())
+    }
+
+    pub fn crypto_keystore_storage_failed<E: Display>(err: E) -> Self {
+        sdk_err!(CryptoKeystoreStorageFailed,
+            "Keystore storage failed: {}", err)
+    }
+
+    pub fn crypto_keystore_invalid_password() -> Self {
+        ApiError::sdk(CryptoKeystoreInvalidPassword,
+            "Invalid keystore password".into())
+    }
+
+    pub fn crypto_invalid_signing_box_handle(handle: u32) -> Self {
+        sdk_err!(CryptoInvalidSigningBoxHandle,
+            "Signing box handle is invalid or has been removed: {}", handle)
+    }
+
+    pub fn crypto_signing_box_failed<E: Display>(err: E) -> Self {
+        sdk_err!(CryptoSigningBoxFailed,
+            "Signing box failed: {}", err)
+    }
+
     pub fn crypto_mnemonic_generation_failed() -> Self {
         ApiError::sdk(CryptoMnemonicGenerationFailed,
             "Mnemonic generation failed (this must never be)".into())
@@ -254,6 +284,11 @@ impl ApiError {
             format!("Mnemonic from entropy failed: {}", reason))
     }
 
+    pub fn crypto_slip39_failed<E: Display>(err: E) -> Self {
+        sdk_err!(CryptoSlip39Failed,
+            "SLIP-0039 secret sharing failed: {}", err)
+    }
+
 // SDK Contracts
 
     pub fn contracts_load_failed<E: Display>(err: E, address: &String) -> Self {
@@ -345,6 +380,56 @@ impl ApiError {
             "Address conversion failed: {}", err)
     }
 
+    pub fn contracts_decode_data_failed<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsDecodeDataFailed,
+            "Decode contract data failed: {}", err)
+    }
+
+    pub fn contracts_multisig_failed<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsMultisigFailed,
+            "Multisig operation failed: {}", err)
+    }
+
+    pub fn contracts_multisig_invalid_signature<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsMultisigInvalidSignature,
+            "Invalid custodian signature: {}", err)
+    }
+
+    pub fn contracts_verify_signature_failed<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsVerifySignatureFailed,
+            "Verify signature failed: {}", err)
+    }
+
+    pub fn contracts_upgrade_failed<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsUpgradeFailed,
+            "Upgrade failed: {}", err)
+    }
+
+    pub fn contracts_code_hash_mismatch<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsCodeHashMismatch,
+            "Code hash verification failed: {}", err)
+    }
+
+    pub fn contracts_decode_any_failed<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsDecodeAnyFailed,
+            "Decode message body failed: {}", err)
+    }
+
+    pub fn contracts_abi_schema_failed<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsAbiSchemaFailed,
+            "Generate ABI schema failed: {}", err)
+    }
+
+    pub fn contracts_invalid_abi_registry_handle(handle: u32) -> Self {
+        sdk_err!(ContractsInvalidAbiRegistryHandle,
+            "ABI registry handle is invalid or has been dropped: {}", handle)
+    }
+
+    pub fn contracts_abi_registry_add_failed<E: Display>(err: E) -> Self {
+        sdk_err!(ContractsAbiRegistryAddFailed,
+            "Add ABI to registry failed: {}", err)
+    }
+
     // SDK queries
 
     pub fn queries_query_failed<E: Display>(err: E) -> Self {
@@ -367,6 +452,23 @@ impl ApiError {
             "Get next failed: {}", err)
     }
 
+    // SDK boc
+
+    pub fn boc_invalid_boc<E: Display>(err: E) -> Self {
+        sdk_err!(BocInvalidBoc,
+            "Invalid BOC: {}", err)
+    }
+
+    pub fn boc_invalid_tree<E: Display>(err: E) -> Self {
+        sdk_err!(BocInvalidTree,
+            "Invalid cell tree: {}", err)
+    }
+
+    pub fn boc_serialization_failed<E: Display>(err: E) -> Self {
+        sdk_err!(BocSerializationFailed,
+            "BOC serialization failed: {}", err)
+    }
+
     // Failed transaction phases
 
     pub fn transaction_parse_failed() -> ApiError {
@@ -471,6 +573,12 @@ pub enum ApiSdkErrorCode {
     CryptoBip39InvalidWordCount = 2023,
     CryptoMnemonicGenerationFailed = 2024,
     CryptoMnemonicFromEntropyFailed = 2025,
+    CryptoKeystoreLocked = 2026,
+    CryptoKeystoreStorageFailed = 2027,
+    CryptoKeystoreInvalidPassword = 2028,
+    CryptoInvalidSigningBoxHandle = 2029,
+    CryptoSigningBoxFailed = 2030,
+    CryptoSlip39Failed = 2031,
 
     ContractsLoadFailed = 3001,
     ContractsInvalidImage = 3002,
@@ -490,6 +598,16 @@ pub enum ApiSdkErrorCode {
     ContractsGetFunctionIdFailed = 3017,
     ContractsLocalRunFailed = 3018,
     ContractsAddressConversionFailed = 3019,
+    ContractsDecodeDataFailed = 3020,
+    ContractsMultisigFailed = 3021,
+    ContractsMultisigInvalidSignature = 3022,
+    ContractsVerifySignatureFailed = 3023,
+    ContractsUpgradeFailed = 3024,
+    ContractsCodeHashMismatch = 3025,
+    ContractsDecodeAnyFailed = 3026,
+    ContractsAbiSchemaFailed = 3027,
+    ContractsInvalidAbiRegistryHandle = 3028,
+    ContractsAbiRegistryAddFailed = 3029,
 
     QueriesQueryFailed = 4001,
     QueriesSubscribeFailed = 4002,
@@ -498,6 +616,10 @@ pub enum ApiSdkErrorCode {
 
     Wallet = 5000,
 
+    BocInvalidBoc = 6001,
+    BocInvalidTree = 6002,
+    BocSerializationFailed = 6003,
+
 }
 
 impl ApiErrorCode for ApiSdkErrorCode {
@@ -4,6 +4,7 @@
 #pragma once
 
 #include <stdint.h>
+#include <stdbool.h>
 
 typedef struct {
 } TonSdkRustString;
@@ -17,6 +18,11 @@ enum OnResultFlags {
     OnResultFinished = 1,
 };
 
+// External signer registered with `tc_register_signing_box`. Signs `data_len` bytes pointed
+// by `data` and writes 64 bytes of Ed25519 signature into `signature`. Returns `false` if
+// signing is rejected. Can be called from any thread until the box is unregistered.
+typedef bool (*tc_signing_callback_t)(void* user_data, const uint8_t* data, uint32_t data_len, uint8_t* signature);
+
 #ifdef __APPLE__
 
 #include <iostream>
@@ -31,18 +37,19 @@ typedef void (*ton_sdk_json_rpc_request_t)(
     TonSdkUtf8String* params_json,
     int32_t requestId,
     on_result_t on_result);
+typedef uint32_t (*tc_register_signing_box_t)(const uint8_t* public_key, tc_signing_callback_t callback, void* user_data);
+typedef void (*tc_unregister_signing_box_t)(uint32_t handle);
 
 static ton_sdk_json_rpc_request_t ton_sdk_json_rpc_request_impl = NULL;
+static tc_register_signing_box_t tc_register_signing_box_impl = NULL;
+static tc_unregister_signing_box_t tc_unregister_signing_box_impl = NULL;
 
-void ton_sdk_json_rpc_request(
-    TonSdkUtf8String* method,
-    TonSdkUtf8String* params_json,
-    int32_t requestId,
-    on_result_t on_result)
+static void* ton_sdk_find_symbol(const char* name)
 {
-    if (!ton_sdk_json_rpc_request_impl) {
+    static void* lib_handle = NULL;
+    if (!lib_handle) {
         Dl_info info;
-        if (!dladdr((void*)ton_sdk_json_rpc_request, &info)) {
+        if (!dladdr((void*)ton_sdk_find_symbol, &info)) {
             cerr << "[" << __FILE__ << "]: Unable to get lib info: "
                  << dlerror() << "\n";
             exit(EXIT_FAILURE);
@@ -53,23 +60,51 @@ void ton_sdk_json_rpc_request(
             libpath = libpath.substr(0, slash_pos) + "/libtonclientnodejs.dylib";
         }
 
-        void* lib_handle = dlopen(libpath.c_str(), RTLD_LOCAL);
+        lib_handle = dlopen(libpath.c_str(), RTLD_LOCAL);
         if (!lib_handle) {
             cerr << "[" << __FILE__ << "]: Unable to open library: "
                  << dlerror() << "\n";
             exit(EXIT_FAILURE);
         }
+    }
 
-        ton_sdk_json_rpc_request_impl = (ton_sdk_json_rpc_request_t)dlsym(lib_handle, "ton_sdk_json_rpc_request");
-        if (!ton_sdk_json_rpc_request_impl) {
-            cerr << "[" << __FILE__ << "] Unable to find [ton_sdk_json_rpc_request] function: "
-              << dlerror() << "\n";
-            exit(EXIT_FAILURE);
-        }
+    void* symbol = dlsym(lib_handle, name);
+    if (!symbol) {
+        cerr << "[" << __FILE__ << "] Unable to find [" << name << "] function: "
+          << dlerror() << "\n";
+        exit(EXIT_FAILURE);
+    }
+    return symbol;
+}
+
+void ton_sdk_json_rpc_request(
+    TonSdkUtf8String* method,
+    TonSdkUtf8String* params_json,
+    int32_t requestId,
+    on_result_t on_result)
+{
+    if (!ton_sdk_json_rpc_request_impl) {
+        ton_sdk_json_rpc_request_impl = (ton_sdk_json_rpc_request_t)ton_sdk_find_symbol("ton_sdk_json_rpc_request");
     }
     (*ton_sdk_json_rpc_request_impl)(method, params_json, requestId, on_result);
 }
 
+uint32_t tc_register_signing_box(const uint8_t* public_key, tc_signing_callback_t callback, void* user_data)
+{
+    if (!tc_register_signing_box_impl) {
+        tc_register_signing_box_impl = (tc_register_signing_box_t)ton_sdk_find_symbol("tc_register_signing_box");
+    }
+    return (*tc_register_signing_box_impl)(public_key, callback, user_data);
+}
+
+void tc_unregister_signing_box(uint32_t handle)
+{
+    if (!tc_unregister_signing_box_impl) {
+        tc_unregister_signing_box_impl = (tc_unregister_signing_box_t)ton_sdk_find_symbol("tc_unregister_signing_box");
+    }
+    (*tc_unregister_signing_box_impl)(handle);
+}
+
 #else
 
 #ifdef __cplusplus
@@ -82,6 +117,14 @@ void ton_sdk_json_rpc_request(
     int32_t requestId,
     void (*on_result)(int32_t request_id, TonSdkUtf8String result_json, TonSdkUtf8String error_json, int32_t flags));
 
+// Registers external signer, `public_key` points to 32 bytes of Ed25519 public key.
+// Returned handle is passed as `signingBox` parameter of the methods which sign messages.
+uint32_t tc_register_signing_box(const uint8_t* public_key, tc_signing_callback_t callback, void* user_data);
+
+// Unregisters external signer. Returns after signing requests in progress are finished,
+// so `user_data` can be freed then. Must not be called from the callback of the same box.
+void tc_unregister_signing_box(uint32_t handle);
+
 #ifdef __cplusplus
 }
 #endif
@@ -4,6 +4,7 @@
 #pragma once
 
 #include <stdint.h>
+#include <stdbool.h>
 
 typedef struct {
 } TONSDKRustString;
@@ -27,6 +28,19 @@ void ton_sdk_json_rpc_request(
     int32_t requestId,
     void (*on_result)(int32_t request_id, TONSDKUtf8String result_json, TONSDKUtf8String error_json, int32_t flags));
 
+// External signer registered with `tc_register_signing_box`. Signs `data_len` bytes pointed
+// by `data` and writes 64 bytes of Ed25519 signature into `signature`. Returns `false` if
+// signing is rejected. Can be called from any thread until the box is unregistered.
+typedef bool (*tc_signing_callback_t)(void* user_data, const uint8_t* data, uint32_t data_len, uint8_t* signature);
+
+// Registers external signer, `public_key` points to 32 bytes of Ed25519 public key.
+// Returned handle is passed as `signingBox` parameter of the methods which sign messages.
+uint32_t tc_register_signing_box(const uint8_t* public_key, tc_signing_callback_t callback, void* user_data);
+
+// Unregisters external signer. Returns after signing requests in progress are finished,
+// so `user_data` can be freed then. Must not be called from the callback of the same box.
+void tc_unregister_signing_box(uint32_t handle);
+
 #ifdef __cplusplus
 }
 #endif
@@ -0,0 +1,106 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::*;
+use std::collections::HashSet;
+use ton_block::AccountStatus;
+use ton_types::Cell;
+
+#[cfg(test)]
+#[path = "tests/test_account.rs"]
+mod tests;
+
+/// Number of unique cells and data bits of account code and data
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct StorageUsage {
+    pub cells: u64,
+    pub bits: u64,
+}
+
+/// Account properties used to verify deployed state against known artifacts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AccountInfo {
+    pub address: String,
+    pub status: String,
+    /// Code hash in hex, `None` if account has no code
+    #[serde(rename = "codeHash")]
+    pub code_hash: Option<String>,
+    /// Data hash in hex, `None` if account has no data
+    #[serde(rename = "dataHash")]
+    pub data_hash: Option<String>,
+    /// Maximal depth of code and data cell trees
+    pub depth: u16,
+    #[serde(rename = "storageUsage")]
+    pub storage_usage: StorageUsage,
+    /// Unix time of the last storage fee payment
+    #[serde(rename = "lastPaid")]
+    pub last_paid: Option<u32>,
+}
+
+impl Contract {
+    /// Returns representation hash of the contract data or `None` if account has no data
+    pub fn data_hash(&self) -> Option<Vec<u8>> {
+        self.data.as_ref().map(|data| data.repr_hash().as_slice().to_vec())
+    }
+
+    /// Counts unique cells and bits of contract code and data
+    pub fn storage_usage(&self) -> StorageUsage {
+        let mut usage = StorageUsage::default();
+        let mut visited = HashSet::new();
+        for root in self.code.iter().chain(self.data.iter()) {
+            count_cells(root, &mut visited, &mut usage);
+        }
+        usage
+    }
+
+    /// Collects code and data hashes, storage usage and status of the account
+    pub fn info(&self) -> AccountInfo {
+        let depth = self.code.iter().chain(self.data.iter())
+            .map(|cell| cell.repr_depth())
+            .max()
+            .unwrap_or_default();
+
+        AccountInfo {
+            address: self.id.to_string(),
+            status: account_status_to_string(&self.acc_type).to_owned(),
+            code_hash: self.code_hash().map(|hash| hex::encode(&hash)),
+            data_hash: self.data_hash().map(|hash| hex::encode(&hash)),
+            depth,
+            storage_usage: self.storage_usage(),
+            last_paid: self.last_paid,
+        }
+    }
+}
+
+fn count_cells(cell: &Cell, visited: &mut HashSet<Vec<u8>>, usage: &mut StorageUsage) {
+    if !visited.insert(cell.repr_hash().as_slice().to_vec()) {
+        return;
+    }
+    usage.cells += 1;
+    usage.bits += cell.bit_length() as u64;
+    for i in 0..cell.references_count() {
+        if let Ok(child) = cell.reference(i) {
+            count_cells(&child, visited, usage);
+        }
+    }
+}
+
+fn account_status_to_string(status: &AccountStatus) -> &'static str {
+    match status {
+        AccountStatus::AccStateUninit => "Uninit",
+        AccountStatus::AccStateActive => "Active",
+        AccountStatus::AccStateFrozen => "Frozen",
+        AccountStatus::AccStateNonexist => "NonExist",
+    }
+}
@@ -0,0 +1,200 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::*;
+use std::fmt;
+use std::str::FromStr;
+use ton_block::{AnycastInfo, MsgAddress, MsgAddressInt};
+use ton_types::SliceData;
+
+#[cfg(test)]
+#[path = "tests/test_address.rs"]
+mod tests;
+
+/// Form the address was given in
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AddressFormat {
+    /// Account id only, accepted by `parse_with_workchain` only
+    AccountId,
+    /// `[anycast:]workchain:account_id`
+    Raw,
+    /// User-friendly base64
+    Base64,
+    /// User-friendly url-safe base64
+    Base64Url,
+}
+
+/// Internal address parsed from any of the supported forms. Flags are set
+/// only for the user-friendly forms.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Address {
+    pub address: MsgAddressInt,
+    pub format: AddressFormat,
+    pub bounceable: Option<bool>,
+    pub testnet: Option<bool>,
+}
+
+impl Address {
+    pub fn new(address: MsgAddressInt) -> Self {
+        Self { address, format: AddressFormat::Raw, bounceable: None, testnet: None }
+    }
+
+    /// Parses address in raw, base64 or url-safe base64 form. Bare account id is rejected
+    /// since its workchain is unknown.
+    pub fn parse(string: &str) -> SdkResult<Self> {
+        Self::parse_impl(string, None)
+    }
+
+    /// Parses address like `parse` also accepting bare account id which is placed
+    /// into the given workchain
+    pub fn parse_with_workchain(string: &str, workchain_id: i32) -> SdkResult<Self> {
+        Self::parse_impl(string, Some(workchain_id))
+    }
+
+    fn parse_impl(string: &str, workchain_id: Option<i32>) -> SdkResult<Self> {
+        let string = string.trim();
+        if string.is_empty() {
+            bail!(SdkErrorKind::InvalidArg("Empty address".to_owned()));
+        }
+
+        if string.contains(':') {
+            Self::from_raw(string)
+        } else if string.len() == 64 && string.chars().all(|c| c.is_ascii_hexdigit()) {
+            let workchain_id = workchain_id.ok_or(SdkErrorKind::InvalidArg(format!(
+                "Account id without workchain is not allowed, use `workchain:account_id` form: {}", string)))?;
+            let address = Self::from_raw(&format!("{}:{}", workchain_id, string))?;
+            Ok(Self { format: AddressFormat::AccountId, ..address })
+        } else {
+            Self::from_base64(string)
+        }
+    }
+
+    /// Parses `[anycast:]workchain:account_id` form. Anycast prefix and account id are
+    /// hex strings, `_` completion tag is allowed for non-aligned values.
+    pub fn from_raw(string: &str) -> SdkResult<Self> {
+        let parts: Vec<&str> = string.split(':').collect();
+        let (anycast, workchain, address) = match parts.len() {
+            2 => (None, parts[0], parts[1]),
+            3 => (Some(parts[0]), parts[1], parts[2]),
+            _ => bail!(SdkErrorKind::InvalidArg(
+                format!("Raw address must be `[anycast:]workchain:account_id`: {}", string)))
+        };
+
+        let workchain_id = i32::from_str(workchain)
+            .map_err(|_| SdkErrorKind::InvalidArg(format!("Invalid workchain id: {}", workchain)))?;
+        let anycast = match anycast {
+            Some(prefix) => Some(AnycastInfo::with_rewrite_pfx(slice_from_hex(prefix)?)?),
+            None => None,
+        };
+        let address = slice_from_hex(address)?;
+
+        let address = if address.remaining_bits() == 256 && workchain_id >= -128 && workchain_id < 128 {
+            MsgAddressInt::with_standart(anycast, workchain_id as i8, address.into())?
+        } else {
+            MsgAddressInt::with_variant(anycast, workchain_id, address)?
+        };
+
+        Ok(Self::new(address))
+    }
+
+    /// Parses user-friendly form checking its length, CRC16 and tag. Both standard and
+    /// url-safe alphabets are accepted.
+    pub fn from_base64(string: &str) -> SdkResult<Self> {
+        let decoded = ton_abi::format::decode_user_friendly_address(string)
+            .map_err(|reason| SdkErrorKind::InvalidArg(format!("Invalid user-friendly address {}: {}", string, reason)))?;
+
+        let address = match decoded.address {
+            MsgAddress::AddrStd(address) => MsgAddressInt::AddrStd(address),
+            _ => bail!(SdkErrorKind::InvalidArg(format!("Invalid user-friendly address {}", string)))
+        };
+
+        Ok(Self {
+            address,
+            format: if decoded.url { AddressFormat::Base64Url } else { AddressFormat::Base64 },
+            bounceable: Some(decoded.bounceable),
+            testnet: Some(decoded.testnet),
+        })
+    }
+
+    pub fn workchain_id(&self) -> i32 {
+        match &self.address {
+            MsgAddressInt::AddrStd(addr) => addr.workchain_id as i32,
+            MsgAddressInt::AddrVar(addr) => addr.workchain_id,
+            MsgAddressInt::AddrNone => 0,
+        }
+    }
+
+    /// Anycast rewrite prefix in hex
+    pub fn anycast(&self) -> Option<String> {
+        let anycast = match &self.address {
+            MsgAddressInt::AddrStd(addr) => &addr.anycast,
+            MsgAddressInt::AddrVar(addr) => &addr.anycast,
+            MsgAddressInt::AddrNone => &None,
+        };
+        anycast.as_ref().map(|anycast| anycast.rewrite_pfx.to_hex_string())
+    }
+
+    pub fn account_id(&self) -> String {
+        self.address.get_address().to_hex_string()
+    }
+
+    /// Raw form `[anycast:]workchain:account_id`
+    pub fn to_raw(&self) -> String {
+        match self.anycast() {
+            Some(anycast) => format!("{}:{}:{}", anycast, self.workchain_id(), self.account_id()),
+            None => format!("{}:{}", self.workchain_id(), self.account_id()),
+        }
+    }
+
+    /// User-friendly form. Available only for std addresses without anycast.
+    pub fn to_base64(&self, bounceable: bool, testnet: bool, as_url: bool) -> SdkResult<String> {
+        let address = match &self.address {
+            MsgAddressInt::AddrStd(address) => MsgAddress::AddrStd(address.clone()),
+            _ => MsgAddress::AddrNone,
+        };
+        ton_abi::format::encode_user_friendly_address(&address, bounceable, testnet, as_url)
+            .ok_or_else(|| SdkErrorKind::InvalidData(
+                "User-friendly form is available for std addresses without anycast only".to_owned()).into())
+    }
+}
+
+impl FromStr for Address {
+    type Err = SdkError;
+
+    fn from_str(string: &str) -> SdkResult<Self> {
+        Self::parse(string)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_raw())
+    }
+}
+
+// Hex string with optional `_` completion tag
+fn slice_from_hex(string: &str) -> SdkResult<SliceData> {
+    let mut hex = string.to_owned();
+    if hex.ends_with('_') {
+        hex.pop();
+    } else {
+        hex.push('8');
+    }
+    if hex.len() % 2 != 0 {
+        hex.push('0');
+    }
+    let data = hex::decode(&hex)
+        .map_err(|err| SdkErrorKind::InvalidArg(format!("Invalid hex {}: {}", string, err)))?;
+    Ok(SliceData::new(data))
+}
@@ -24,7 +24,7 @@ use ton_types::cells_serialization::{deserialize_cells_tree, BagOfCells};
 use ton_types::{Cell, SliceData};
 use ton_block::AccountId;
 
-pub use ton_abi::json_abi::DecodedMessage;
+pub use ton_abi::json_abi::{DecodedFunctionCall, DecodedMessage, VerifiedSignature};
 pub use ton_abi::token::{Token, TokenValue, Tokenizer};
 
 #[cfg(feature = "node_interaction")]
@@ -37,6 +37,8 @@ const ACCOUNT_FIELDS: &str = r#"
     id
     acc_type
     balance
+    last_paid
+    last_trans_lt
     code
     data
 "#;
@@ -53,6 +55,10 @@ pub struct Contract {
     pub acc_type: AccountStatus,
     #[serde(deserialize_with = "json_helper::deserialize_uint_from_string")]
     pub balance: u128,
+    pub last_paid: Option<u32>,
+    /// Logical time of the last transaction of the account, 0 if it is not loaded
+    #[serde(deserialize_with = "json_helper::deserialize_uint_from_string")]
+    pub last_trans_lt: u128,
     #[serde(deserialize_with = "json_helper::deserialize_tree_of_cells_opt_cell")]
     pub code: Option<Cell>,
     #[serde(deserialize_with = "json_helper::deserialize_tree_of_cells_opt_cell")]
@@ -177,6 +183,14 @@ impl ContractImage {
         }
     }
 
+    // Returns representation hash of the image code
+    pub fn code_hash(&self) -> SdkResult<Vec<u8>> {
+        match &self.state_init.code {
+            Some(cell) => Ok(cell.repr_hash().as_slice().to_vec()),
+            None => bail!(SdkErrorKind::InvalidData("State init has no code".to_owned()))
+        }
+    }
+
     pub fn serialize(&self) -> SdkResult<Vec<u8>> {
         let cell = self.state_init.write_to_new_cell()?;
 
@@ -220,42 +234,11 @@ impl ContractImage {
 }
 
 pub fn decode_std_base64(data: &str) -> SdkResult<MsgAddressInt> {
-    // conversion from base64url
-    let data = data.replace('_', "/").replace('-', "+");
-
-    let vec = base64::decode(&data)?;
-
-    // check CRC and address tag
-    let mut crc = crc_any::CRC::crc16xmodem();
-    crc.digest(&vec[..34]);
-
-    if crc.get_crc_vec_be() != &vec[34..36] || vec[0] & 0x3f != 0x11 {
-        bail!(SdkErrorKind::InvalidArg(data.to_owned()));
-    };
-
-    Ok(MsgAddressInt::with_standart(None, vec[1] as i8, vec[2..34].into())?)
+    Ok(Address::from_base64(data)?.address)
 }
 
 pub fn encode_base64(address: &MsgAddressInt, bounceable: bool, test: bool, as_url: bool) -> SdkResult<String> {
-    if let MsgAddressInt::AddrStd(address) = address {
-        let mut tag = if bounceable { 0x11 } else { 0x51 };
-        if test { tag |= 0x80 };
-        let mut vec = vec![tag];
-        vec.extend_from_slice(&address.workchain_id.to_be_bytes());
-        vec.append(&mut address.address.get_bytestring(0));
-
-        let mut crc = crc_any::CRC::crc16xmodem();
-        crc.digest(&vec);
-        vec.extend_from_slice(&crc.get_crc_vec_be());
-
-        let result = base64::encode(&vec);
-
-        if as_url {
-            Ok(result.replace('/', "_").replace('+', "-"))
-        } else {
-            Ok(result)
-        }
-    } else { bail!(SdkErrorKind::InvalidData("Non-std address".to_owned())) }
+    Address::new(address.clone()).to_base64(bounceable, test, as_url)
 }
 
 #[allow(dead_code)]
@@ -302,6 +285,25 @@ impl Contract {
         Ok(acc)
     }
 
+    // Asynchronously loads account state produced by the transaction with given logical time
+    // or by a later one. Waits until such state appears
+    pub fn load_wait_transaction_lt(address: &MsgAddressInt, lt: u128) -> SdkResult<Contract> {
+        let value = queries_helper::wait_for(
+            CONTRACTS_TABLE_NAME,
+            &json!({
+                "id": {
+                    "eq": address.to_string()
+                },
+                "last_trans_lt": { "ge": format!("0x{:x}", lt) }
+            }).to_string(),
+            ACCOUNT_FIELDS)?;
+
+        let acc: Contract = serde_json::from_value(value)
+            .map_err(|err| SdkErrorKind::InvalidData(format!("error parsing account: {}", err)))?;
+
+        Ok(acc)
+    }
+
     // Asynchronously loads a Contract's json representation
     // or null if message with given id is not exists
     pub fn load_json(id: AccountId) -> SdkResult<Box<dyn Stream<Item = String, Error = SdkError>>> {
@@ -478,6 +480,48 @@ impl Contract {
         Self::decode_function_response_json(abi, function, slice, internal)
     }
 
+    /// Decodes output parameters returned by contract function call from serialized message body,
+    /// values are written in given format
+    pub fn decode_function_response_from_bytes_json_with_format(
+        abi: String, function: String, response: &[u8], internal: bool, format: AbiValueFormat
+    ) -> SdkResult<String> {
+
+        let slice = Self::deserialize_tree_to_slice(response)?;
+
+        Self::decode_function_response_json_with_format(abi, function, slice, internal, format)
+    }
+
+    /// Decodes output parameters returned by contract function call, values are written in given format
+    pub fn decode_function_response_json_with_format(
+        abi: String, function: String, response: SliceData, internal: bool, format: AbiValueFormat
+    ) -> SdkResult<String> {
+
+        ton_abi::json_abi::decode_function_response_with_format(abi, function, response, internal, format)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))
+    }
+
+    /// Decodes output parameters returned by contract function call. Only elements within
+    /// `pagination` are decoded for each array and map, values are written in given format
+    pub fn decode_function_response_json_paginated(
+        abi: String, function: String, response: SliceData, pagination: Option<AbiPagination>, format: AbiValueFormat
+    ) -> SdkResult<String> {
+
+        ton_abi::json_abi::decode_function_response_paginated(abi, function, response, pagination, format)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))
+    }
+
+    /// Decodes output parameters returned by contract function call from serialized message body.
+    /// Only elements within `pagination` are decoded for each array and map, values are written
+    /// in given format
+    pub fn decode_function_response_from_bytes_json_paginated(
+        abi: String, function: String, response: &[u8], pagination: Option<AbiPagination>, format: AbiValueFormat
+    ) -> SdkResult<String> {
+
+        let slice = Self::deserialize_tree_to_slice(response)?;
+
+        Self::decode_function_response_json_paginated(abi, function, slice, pagination, format)
+    }
+
     /// Decodes output parameters returned by contract function call 
     pub fn decode_unknown_function_response_json(abi: String, response: SliceData, internal: bool) 
         -> SdkResult<DecodedMessage> {
@@ -495,6 +539,18 @@ impl Contract {
         Self::decode_unknown_function_response_json(abi, slice, internal)
     }
 
+    /// Decodes output parameters returned by contract function call from serialized message body,
+    /// values are written in given format
+    pub fn decode_unknown_function_response_from_bytes_json_with_format(
+        abi: String, response: &[u8], internal: bool, format: AbiValueFormat
+    ) -> SdkResult<DecodedMessage> {
+
+        let slice = Self::deserialize_tree_to_slice(response)?;
+
+        ton_abi::json_abi::decode_unknown_function_response_with_format(abi, slice, internal, format)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))
+    }
+
     /// Decodes output parameters returned by contract function call 
     pub fn decode_unknown_function_call_json(abi: String, response: SliceData, internal: bool) 
         -> SdkResult<DecodedMessage> {
@@ -512,6 +568,58 @@ impl Contract {
         Self::decode_unknown_function_call_json(abi, slice, internal)
     }
 
+    /// Decodes input parameters of contract function call from serialized message body together
+    /// with the message kind and external call header. If `internal` is `None` message kind is detected
+    pub fn decode_unknown_function_call_with_header_from_bytes_json(
+        abi: String, body: &[u8], internal: Option<bool>, format: AbiValueFormat
+    ) -> SdkResult<DecodedFunctionCall> {
+
+        let slice = Self::deserialize_tree_to_slice(body)?;
+
+        ton_abi::json_abi::decode_unknown_function_call_with_header(abi, slice, internal, format)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))
+    }
+
+    /// Decodes serialized message body by one of the ABIs in `registry`. Returns decoded body
+    /// and its parameters as JSON
+    pub fn decode_any_message_from_bytes_json(
+        registry: &AbiRegistry, body: &[u8], code_hash: Option<&[u8]>, internal: Option<bool>, format: AbiValueFormat
+    ) -> SdkResult<(AbiDecodedBody, String)> {
+
+        let slice = Self::deserialize_tree_to_slice(body)?;
+
+        ton_abi::json_abi::decode_any_message_body(registry, slice, code_hash, internal, format)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))
+    }
+
+    /// Checks signature of external function call from serialized message body
+    pub fn verify_function_call_signature_from_bytes(abi: String, function: Option<String>, body: &[u8])
+        -> SdkResult<VerifiedSignature> {
+
+        let slice = Self::deserialize_tree_to_slice(body)?;
+
+        ton_abi::json_abi::verify_function_call_signature(abi, function, slice)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))
+    }
+
+    /// Decodes public contract variables from serialized contract data
+    pub fn decode_data_from_bytes_json(abi: String, data: &[u8]) -> SdkResult<String> {
+
+        let slice = Self::deserialize_tree_to_slice(data)?;
+
+        ton_abi::json_abi::decode_contract_data(&abi, slice)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))
+    }
+
+    /// Reads public key from serialized contract data
+    pub fn get_public_key_from_data(data: &[u8]) -> SdkResult<Option<Vec<u8>>> {
+
+        let slice = Self::deserialize_tree_to_slice(data)?;
+
+        AbiContract::get_pubkey(slice)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))
+    }
+
     // ------- Call constructing functions -------
 
     // Packs given inputs by abi into Message struct.
@@ -640,7 +748,7 @@ impl Contract {
         Self::serialize_message(message)
     }
 
-    fn create_message(address: MsgAddressInt, msg_body: SliceData) -> SdkResult<TvmMessage> {
+    pub fn create_message(address: MsgAddressInt, msg_body: SliceData) -> SdkResult<TvmMessage> {
 
         let mut msg_header = ExternalInboundMessageHeader::default();
         msg_header.dst = address;
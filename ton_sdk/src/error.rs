@@ -97,6 +97,10 @@ error_chain! {
             description("Signature error"),
             display("Signature error: {}", inner)
         }
+        CodeHashMismatch(expected: String, actual: String) {
+            description("Code hash mismatch"),
+            display("Code hash mismatch: expected {}, actual {}", expected, actual)
+        }
         NotInitialized {
             description("SDK is not initialized")
         }
@@ -29,7 +29,6 @@ extern crate sha2;
 extern crate base64;
 extern crate chrono;
 extern crate failure;
-extern crate crc_any;
 
 #[cfg(feature = "node_interaction")]
 #[macro_use]
@@ -49,6 +48,15 @@ extern crate reqwest;
 pub use ton_abi::json_abi;
 pub use ton_abi::Contract as AbiContract;
 pub use ton_abi::Function as AbiFunction;
+pub use ton_abi::IntFormat as AbiIntFormat;
+pub use ton_abi::ValueFormat as AbiValueFormat;
+pub use ton_abi::token::Pagination as AbiPagination;
+pub use ton_abi::format::CellTree as AbiCellTree;
+pub use ton_abi::AbiRegistry;
+pub use ton_abi::registry::BodyType as AbiBodyType;
+pub use ton_abi::registry::DecodedBody as AbiDecodedBody;
+pub use ton_abi::ValidationError as AbiValidationError;
+pub use ton_abi::validate as validate_abi;
 
 #[allow(deprecated)]
 #[macro_use]
@@ -58,9 +66,21 @@ pub use error::*;
 mod contract;
 pub use contract::*;
 
+mod address;
+pub use address::*;
+
 mod message;
 pub use message::*;
 
+mod multisig;
+pub use multisig::*;
+
+mod upgrade;
+pub use upgrade::*;
+
+mod account;
+pub use account::*;
+
 mod local_tvm;
 
 #[cfg(feature = "node_interaction")]
@@ -0,0 +1,341 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::*;
+use ed25519_dalek::{Keypair, PublicKey, Signature};
+use sha2::Sha512;
+use ton_block::MsgAddressInt;
+use ton_types::{BuilderData, Cell};
+use std::str::FromStr;
+use chrono::Utc;
+
+#[cfg(test)]
+#[path = "tests/test_multisig.rs"]
+mod tests;
+
+/// ABI of the standard multisignature wallet
+pub const MULTISIG_ABI: &str = r#"{
+    "ABI version": 1,
+    "functions": [
+        {
+            "name": "constructor",
+            "inputs": [
+                {"name":"owners","type":"uint256[]"},
+                {"name":"reqConfirms","type":"uint8"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "sendTransaction",
+            "inputs": [
+                {"name":"dest","type":"address"},
+                {"name":"value","type":"uint128"},
+                {"name":"bounce","type":"bool"},
+                {"name":"flags","type":"uint8"},
+                {"name":"payload","type":"cell"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "submitTransaction",
+            "inputs": [
+                {"name":"dest","type":"address"},
+                {"name":"value","type":"uint128"},
+                {"name":"bounce","type":"bool"},
+                {"name":"allBalance","type":"bool"},
+                {"name":"payload","type":"cell"}
+            ],
+            "outputs": [
+                {"name":"transId","type":"uint64"}
+            ]
+        },
+        {
+            "name": "confirmTransaction",
+            "inputs": [
+                {"name":"transactionId","type":"uint64"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "isConfirmed",
+            "inputs": [
+                {"name":"mask","type":"uint32"},
+                {"name":"index","type":"uint8"}
+            ],
+            "outputs": [
+                {"name":"confirmed","type":"bool"}
+            ]
+        },
+        {
+            "name": "getTransactionIds",
+            "inputs": [],
+            "outputs": [
+                {"name":"ids","type":"uint64[]"}
+            ]
+        },
+        {
+            "name": "getCustodians",
+            "inputs": [],
+            "outputs": [
+                {"components":[
+                    {"name":"index","type":"uint8"},
+                    {"name":"pubkey","type":"uint256"}
+                ],"name":"custodians","type":"tuple[]"}
+            ]
+        }
+    ],
+    "events": [],
+    "data": []
+}"#;
+
+pub const MULTISIG_SUBMIT_FUNCTION: &str = "submitTransaction";
+pub const MULTISIG_CONFIRM_FUNCTION: &str = "confirmTransaction";
+
+/// Wallet call prepared for one custodian. Every custodian signs own message with own
+/// timestamp, so messages of different custodians are never the same.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CustodianCall {
+    /// Message without signature, base64 encoded bag of cells
+    #[serde(rename = "unsignedMessage")]
+    pub unsigned_message: String,
+    /// Hash of unsigned message body in hex
+    #[serde(rename = "dataToSign")]
+    pub data_to_sign: String,
+}
+
+impl CustodianCall {
+    /// Builds unsigned message with given time in milliseconds in the call header
+    fn new(address: &str, function: &str, input: &serde_json::Value, time: u64) -> SdkResult<Self> {
+        let address = MsgAddressInt::from_str(address)
+            .map_err(|err| SdkErrorKind::InvalidData(format!("Invalid wallet address: {}", err)))?;
+        let contract = ton_abi::Contract::load(MULTISIG_ABI.as_bytes())
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))?;
+        let function = contract.function(function)
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))?;
+        let (body, data_to_sign) = Tokenizer::tokenize_all_with_path(&function.input_params(), input, "input")
+            .and_then(|tokens| function.create_unsigned_call_with_time(&tokens, false, time))
+            .map_err(|err| SdkError::from(SdkErrorKind::AbiError(err)))?;
+        let (message, _id) = Contract::serialize_message(Contract::create_message(address, body.into())?)?;
+
+        Ok(Self {
+            unsigned_message: base64::encode(&message),
+            data_to_sign: hex::encode(&data_to_sign),
+        })
+    }
+
+    /// Returns time in milliseconds from the header of the call
+    fn time(&self) -> SdkResult<u64> {
+        let message = Contract::deserialize_message(&base64::decode(&self.unsigned_message)?)?;
+        let mut body = message.body()
+            .ok_or(SdkErrorKind::InvalidData("No message body".to_owned()))?;
+        body.get_next_u32().and_then(|_id| body.get_next_u64())
+            .map_err(|_| SdkErrorKind::InvalidData("No call header in message body".to_owned()).into())
+    }
+
+    /// Recomputes hash of the unsigned message body and checks that it matches `dataToSign`
+    pub fn unsigned_hash(&self) -> SdkResult<Vec<u8>> {
+        let message = Contract::deserialize_message(&base64::decode(&self.unsigned_message)?)?;
+        let body = message.body()
+            .ok_or(SdkErrorKind::InvalidData("No message body".to_owned()))?;
+        let hash = Cell::from(&BuilderData::from_slice(&body)).repr_hash().as_slice().to_vec();
+
+        if hex::decode(&self.data_to_sign)? != hash {
+            bail!(SdkErrorKind::InvalidData("Data to sign does not match unsigned message".to_owned()));
+        }
+        Ok(hash)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CustodianSignature {
+    /// Custodian public key in hex
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    /// Message signed by the custodian
+    #[serde(flatten)]
+    pub call: CustodianCall,
+    /// Detached signature of the unsigned message hash in hex
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SignatureCheck {
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    pub valid: bool,
+}
+
+/// Multisig wallet call waiting for custodian signatures. Serialized to JSON it can be
+/// passed between custodians, each of them adding own signed message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MultisigPayload {
+    /// Wallet address
+    pub address: String,
+    pub function: String,
+    pub input: serde_json::Value,
+    #[serde(default)]
+    pub signatures: Vec<CustodianSignature>,
+}
+
+impl MultisigPayload {
+    fn new(address: &MsgAddressInt, function: &str, input: serde_json::Value) -> SdkResult<Self> {
+        let payload = Self {
+            address: address.to_string(),
+            function: function.to_owned(),
+            input,
+            signatures: Vec::new(),
+        };
+        // fail early on input not matching the wallet ABI
+        payload.custodian_call()?;
+        Ok(payload)
+    }
+
+    /// Creates `submitTransaction` call. `payload` is base64 encoded bag of cells,
+    /// empty cell is sent if it is not set.
+    pub fn submit_transaction(
+        wallet: &MsgAddressInt,
+        dest: &MsgAddressInt,
+        value: u128,
+        bounce: bool,
+        all_balance: bool,
+        payload: Option<&str>
+    ) -> SdkResult<Self> {
+        let input = serde_json::json!({
+            "dest": dest.to_string(),
+            "value": value.to_string(),
+            "bounce": bounce,
+            "allBalance": all_balance,
+            "payload": payload.unwrap_or(""),
+        });
+        Self::new(wallet, MULTISIG_SUBMIT_FUNCTION, input)
+    }
+
+    /// Creates `confirmTransaction` call
+    pub fn confirm_transaction(wallet: &MsgAddressInt, transaction_id: u64) -> SdkResult<Self> {
+        let input = serde_json::json!({
+            "transactionId": format!("0x{:x}", transaction_id),
+        });
+        Self::new(wallet, MULTISIG_CONFIRM_FUNCTION, input)
+    }
+
+    pub fn to_json(&self) -> SdkResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> SdkResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Builds new unsigned message of the wallet call with current timestamp. Each custodian
+    /// signs own message which is passed to `add_signature` with the signature
+    pub fn custodian_call(&self) -> SdkResult<CustodianCall> {
+        self.custodian_call_with_time(Utc::now().timestamp_millis() as u64)
+    }
+
+    /// Builds new unsigned message of the wallet call with given time in milliseconds
+    pub fn custodian_call_with_time(&self, time: u64) -> SdkResult<CustodianCall> {
+        CustodianCall::new(&self.address, &self.function, &self.input, time)
+    }
+
+    /// Checks that the message calls the function of the wallet with the input of the payload
+    /// and returns hash of the message body
+    fn check_call(&self, call: &CustodianCall) -> SdkResult<Vec<u8>> {
+        let expected = self.custodian_call_with_time(call.time()?)?;
+        if expected.unsigned_message != call.unsigned_message {
+            bail!(SdkErrorKind::InvalidData(format!(
+                "Message is not a call of {} of wallet {} with the payload input",
+                self.function, self.address)));
+        }
+        call.unsigned_hash()
+    }
+
+    /// Adds custodian signature of the message built by `custodian_call`. The message and
+    /// the signature are verified before adding, previous signature of the same custodian
+    /// is replaced.
+    pub fn add_signature(&mut self, call: CustodianCall, public_key: &[u8], signature: &[u8]) -> SdkResult<()> {
+        let hash = self.check_call(&call)?;
+        verify(&hash, public_key, signature)?;
+
+        let public_key = hex::encode(public_key);
+        if self.function == MULTISIG_SUBMIT_FUNCTION {
+            if self.signatures.iter().any(|sign| sign.public_key != public_key) {
+                bail!(SdkErrorKind::InvalidOperation(
+                    "Transaction can be submitted by one custodian only".to_owned()));
+            }
+        }
+
+        self.signatures.retain(|sign| sign.public_key != public_key);
+        self.signatures.push(CustodianSignature {
+            public_key,
+            call,
+            signature: hex::encode(signature),
+        });
+        Ok(())
+    }
+
+    /// Builds and signs own message of the custodian
+    pub fn sign(&mut self, key_pair: &Keypair) -> SdkResult<()> {
+        self.sign_with_time(key_pair, Utc::now().timestamp_millis() as u64)
+    }
+
+    /// Builds and signs own message of the custodian with given time in milliseconds
+    pub fn sign_with_time(&mut self, key_pair: &Keypair, time: u64) -> SdkResult<()> {
+        let call = self.custodian_call_with_time(time)?;
+        let hash = call.unsigned_hash()?;
+        let signature = key_pair.sign::<Sha512>(&hash).to_bytes();
+        self.add_signature(call, &key_pair.public.to_bytes(), &signature)
+    }
+
+    /// Checks every collected signature against the hash of its message
+    pub fn verify_signatures(&self) -> SdkResult<Vec<SignatureCheck>> {
+        let mut result = Vec::new();
+        for sign in &self.signatures {
+            let valid = self.check_call(&sign.call).ok()
+                .and_then(|hash| hex::decode(&sign.public_key).ok().map(|key| (hash, key)))
+                .and_then(|(hash, key)| hex::decode(&sign.signature).ok().map(|signature| (hash, key, signature)))
+                .map(|(hash, key, signature)| verify(&hash, &key, &signature).is_ok())
+                .unwrap_or(false);
+            result.push(SignatureCheck { public_key: sign.public_key.clone(), valid });
+        }
+        Ok(result)
+    }
+
+    /// Builds final messages, one for each collected signature
+    pub fn messages(&self) -> SdkResult<Vec<(Vec<u8>, MessageId)>> {
+        if self.signatures.is_empty() {
+            bail!(SdkErrorKind::InvalidOperation("No signatures collected".to_owned()));
+        }
+
+        let mut result = Vec::new();
+        for sign in &self.signatures {
+            let hash = self.check_call(&sign.call)?;
+            let public_key = hex::decode(&sign.public_key)?;
+            let signature = hex::decode(&sign.signature)?;
+            verify(&hash, &public_key, &signature)?;
+            let message = base64::decode(&sign.call.unsigned_message)?;
+            result.push(Contract::add_sign_to_message(&signature, &public_key, &message)?);
+        }
+        Ok(result)
+    }
+}
+
+fn verify(hash: &[u8], public_key: &[u8], signature: &[u8]) -> SdkResult<()> {
+    let public_key = PublicKey::from_bytes(public_key)
+        .map_err(|err| SdkErrorKind::Signature(err))?;
+    let signature = Signature::from_bytes(signature)
+        .map_err(|err| SdkErrorKind::Signature(err))?;
+    public_key.verify::<Sha512>(hash, &signature)
+        .map_err(|err| SdkErrorKind::Signature(err))?;
+    Ok(())
+}
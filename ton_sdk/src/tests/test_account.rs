@@ -0,0 +1,60 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+
+#[test]
+fn test_account_info() {
+    let mut state_init = std::fs::File::open("src/tests/Subscription.tvc")
+        .expect("Unable to open Subscription contract file");
+    let image = ContractImage::from_state_init(&mut state_init).unwrap();
+    let code = image.get_serialized_code().unwrap();
+    let data = image.get_serialized_data().unwrap();
+    let code_hash = hex::encode(&image.code_hash().unwrap());
+
+    let contract = Contract::from_json(&serde_json::json!({
+        "id": "0:1111111111111111111111111111111111111111111111111111111111111111",
+        "acc_type": 1,
+        "balance": "0x3b9aca00",
+        "last_paid": 1576526553,
+        "code": base64::encode(&code),
+        "data": base64::encode(&data),
+    }).to_string()).unwrap();
+
+    let info = contract.info();
+    assert_eq!(info.status, "Active");
+    assert_eq!(info.code_hash, Some(code_hash));
+    assert_eq!(info.data_hash.as_ref().map(|hash| hash.len()), Some(64));
+    assert_eq!(info.last_paid, Some(1576526553));
+
+    let code_cell = contract.code.clone().unwrap();
+    assert!(info.depth >= code_cell.repr_depth());
+    assert!(info.storage_usage.cells > 1);
+    assert!(info.storage_usage.bits >= code_cell.bit_length() as u64);
+
+    // shared cells are counted once
+    let doubled = Contract {
+        data: contract.code.clone(),
+        code: contract.code.clone(),
+        ..Default::default()
+    };
+    let code_only = Contract { code: contract.code.clone(), ..Default::default() };
+    assert_eq!(doubled.storage_usage(), code_only.storage_usage());
+
+    let empty = Contract::from_json(r#"{ "acc_type": 0, "last_paid": null }"#).unwrap().info();
+    assert_eq!(empty.status, "Uninit");
+    assert_eq!(empty.code_hash, None);
+    assert_eq!(empty.depth, 0);
+    assert_eq!(empty.storage_usage, StorageUsage::default());
+}
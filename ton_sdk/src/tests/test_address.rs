@@ -0,0 +1,87 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+
+const ACCOUNT_ID: &str = "fcb91a3a3816d0f7b8c2c76108b8a9bc5a6b7a55bd79f8ab101c52db29232260";
+
+#[test]
+fn test_user_friendly_forms() {
+    let raw = format!("-1:{}", ACCOUNT_ID);
+
+    let address = Address::parse("kf/8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYIny").unwrap();
+    assert_eq!(address.format, AddressFormat::Base64);
+    assert_eq!(address.bounceable, Some(true));
+    assert_eq!(address.testnet, Some(true));
+    assert_eq!(address.to_raw(), raw);
+
+    let address = Address::parse("kf_8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15-KsQHFLbKSMiYIny").unwrap();
+    assert_eq!(address.format, AddressFormat::Base64Url);
+    assert_eq!(address.to_raw(), raw);
+
+    let address = Address::parse("UQD8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYJD1").unwrap();
+    assert_eq!(address.bounceable, Some(false));
+    assert_eq!(address.testnet, Some(false));
+    assert_eq!(address.workchain_id(), 0);
+
+    assert_eq!(address.to_base64(true, false, false).unwrap(), "EQD8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYM0w");
+    assert_eq!(address.to_base64(false, false, true).unwrap(), "UQD8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15-KsQHFLbKSMiYJD1");
+
+    // broken checksum
+    assert!(Address::parse("UQD8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYJD2").is_err());
+    // unknown tag
+    assert!(Address::parse("AQD8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYJD1").is_err());
+    // padded or truncated forms decoding to 36 bytes are not user-friendly addresses
+    assert!(Address::from_base64("UQD8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYJD1=").is_err());
+    assert!(Address::from_base64(" UQD8uRo6OBbQ97jCx2EIuKm8Wmt6Vb15+KsQHFLbKSMiYJD1").is_err());
+}
+
+#[test]
+fn test_raw_forms() {
+    // workchain of bare account id must be given explicitly
+    assert!(Address::parse(ACCOUNT_ID).is_err());
+    let address = Address::parse_with_workchain(ACCOUNT_ID, 0).unwrap();
+    assert_eq!(address.format, AddressFormat::AccountId);
+    assert_eq!(address.to_raw(), format!("0:{}", ACCOUNT_ID));
+    assert_eq!(address.bounceable, None);
+    let address = Address::parse_with_workchain(ACCOUNT_ID, -1).unwrap();
+    assert_eq!(address.to_raw(), format!("-1:{}", ACCOUNT_ID));
+
+    let raw = format!("-1:{}", ACCOUNT_ID);
+    let address: Address = raw.parse().unwrap();
+    assert_eq!(address.format, AddressFormat::Raw);
+    assert_eq!(address.address, MsgAddressInt::from_str(&raw).unwrap());
+    assert_eq!(address.to_string(), raw);
+
+    let anycast = format!("a5:-1:{}", ACCOUNT_ID);
+    let address = Address::parse(&anycast).unwrap();
+    assert_eq!(address.anycast(), Some("a5".to_owned()));
+    assert_eq!(address.to_raw(), anycast);
+    assert!(address.to_base64(true, false, false).is_err());
+
+    let address = Address::parse("5:abcd").unwrap();
+    match address.address {
+        MsgAddressInt::AddrVar(_) => (),
+        _ => panic!("addr_var expected"),
+    }
+    assert_eq!(address.to_raw(), "5:abcd");
+
+    let address = Address::parse(&format!("1000:{}", ACCOUNT_ID)).unwrap();
+    assert_eq!(address.workchain_id(), 1000);
+    assert!(address.to_base64(true, false, false).is_err());
+
+    assert!(Address::parse("").is_err());
+    assert!(Address::parse("x:abcd").is_err());
+    assert!(Address::parse("0:zz").is_err());
+}
@@ -0,0 +1,130 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use std::str::FromStr;
+use rand::rngs::OsRng;
+use ton_types::SliceData;
+
+const WALLET: &str = "0:1111111111111111111111111111111111111111111111111111111111111111";
+const DEST: &str = "0:2222222222222222222222222222222222222222222222222222222222222222";
+
+fn generate_keys() -> Keypair {
+    Keypair::generate::<Sha512, _>(&mut OsRng::new().unwrap())
+}
+
+#[test]
+fn test_multisig_confirm_collects_signatures() {
+    let wallet = MsgAddressInt::from_str(WALLET).unwrap();
+    let first = generate_keys();
+    let second = generate_keys();
+
+    let mut payload = MultisigPayload::confirm_transaction(&wallet, 0x5e00000000000001).unwrap();
+    payload.sign_with_time(&first, 1_600_000_000_000).unwrap();
+
+    // payload is passed to the next custodian as JSON, the next message gets own timestamp
+    let mut payload = MultisigPayload::from_json(&payload.to_json().unwrap()).unwrap();
+    payload.sign_with_time(&second, 1_600_000_000_001).unwrap();
+    // signing twice replaces the signature
+    payload.sign_with_time(&second, 1_600_000_000_002).unwrap();
+    assert_eq!(payload.signatures.len(), 2);
+
+    // every custodian signs own message
+    assert_ne!(payload.signatures[0].call.unsigned_message, payload.signatures[1].call.unsigned_message);
+    assert_ne!(payload.signatures[0].call.data_to_sign, payload.signatures[1].call.data_to_sign);
+
+    // signature of other data is rejected
+    let foreign = first.sign::<Sha512>(&[1, 2, 3]).to_bytes();
+    let call = payload.custodian_call().unwrap();
+    assert!(payload.add_signature(call, &first.public.to_bytes(), &foreign).is_err());
+
+    let checks = payload.verify_signatures().unwrap();
+    assert!(checks.iter().all(|check| check.valid));
+
+    let messages = payload.messages().unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_ne!(messages[0].1.to_string(), messages[1].1.to_string());
+    let mut bodies = Vec::new();
+    for (message, _) in messages {
+        let message = Contract::deserialize_message(&message).unwrap();
+        let mut body = message.body().unwrap();
+        // signature is placed into the first reference
+        let mut sign = SliceData::from(body.checked_drain_reference().unwrap());
+        assert_eq!(sign.get_next_bytes(64).unwrap().len(), 64);
+        bodies.push(Cell::from(&BuilderData::from_slice(&body)).repr_hash());
+    }
+    // bodies differ by timestamp even without signatures
+    assert_ne!(bodies[0], bodies[1]);
+
+    // tampered signature is reported
+    payload.signatures[0].signature = hex::encode(&foreign[..]);
+    let checks = payload.verify_signatures().unwrap();
+    assert!(!checks[0].valid);
+    assert!(checks[1].valid);
+    assert!(payload.messages().is_err());
+}
+
+#[test]
+fn test_multisig_submit_single_custodian() {
+    let wallet = MsgAddressInt::from_str(WALLET).unwrap();
+    let dest = MsgAddressInt::from_str(DEST).unwrap();
+
+    let mut payload = MultisigPayload::submit_transaction(
+        &wallet, &dest, 1_000_000_000, true, false, None).unwrap();
+    assert_eq!(payload.input["value"], "1000000000");
+
+    payload.sign(&generate_keys()).unwrap();
+    assert!(payload.sign(&generate_keys()).is_err());
+    assert_eq!(payload.messages().unwrap().len(), 1);
+
+    // data to sign must match the message
+    payload.signatures[0].call.data_to_sign = hex::encode(&[0u8; 32]);
+    assert!(payload.signatures[0].call.unsigned_hash().is_err());
+    assert!(!payload.verify_signatures().unwrap()[0].valid);
+}
+
+#[test]
+fn test_multisig_rejects_foreign_call() {
+    let wallet = MsgAddressInt::from_str(WALLET).unwrap();
+    let other_wallet = MsgAddressInt::from_str(DEST).unwrap();
+    let keys = generate_keys();
+
+    let mut payload = MultisigPayload::confirm_transaction(&wallet, 1).unwrap();
+    let sign = |call: &CustodianCall| keys.sign::<Sha512>(&call.unsigned_hash().unwrap()).to_bytes();
+
+    // correctly signed calls of other wallet or with other input are not accepted
+    let foreign_calls = vec![
+        MultisigPayload::confirm_transaction(&other_wallet, 1).unwrap().custodian_call().unwrap(),
+        MultisigPayload::confirm_transaction(&wallet, 2).unwrap().custodian_call().unwrap(),
+        MultisigPayload::submit_transaction(&wallet, &other_wallet, 1, true, false, None).unwrap()
+            .custodian_call().unwrap(),
+    ];
+    for call in foreign_calls {
+        let signature = sign(&call);
+        assert!(payload.add_signature(call, &keys.public.to_bytes(), &signature).is_err());
+    }
+    assert!(payload.signatures.is_empty());
+
+    let call = payload.custodian_call().unwrap();
+    let signature = sign(&call);
+    payload.add_signature(call, &keys.public.to_bytes(), &signature).unwrap();
+    assert!(payload.verify_signatures().unwrap()[0].valid);
+
+    // replaced call is caught when messages are built
+    let call = MultisigPayload::confirm_transaction(&other_wallet, 1).unwrap().custodian_call().unwrap();
+    payload.signatures[0].signature = hex::encode(&sign(&call)[..]);
+    payload.signatures[0].call = call;
+    assert!(!payload.verify_signatures().unwrap()[0].valid);
+    assert!(payload.messages().is_err());
+}
@@ -0,0 +1,88 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use std::str::FromStr;
+
+const ADDRESS: &str = "0:1111111111111111111111111111111111111111111111111111111111111111";
+
+fn load_image(file: &str) -> ContractImage {
+    let mut state_init = std::fs::File::open(file).expect("Unable to open contract file");
+    ContractImage::from_state_init(&mut state_init).expect("Unable to parse contract file")
+}
+
+fn deployed_contract(image: ContractImage) -> Contract {
+    let state_init = image.state_init();
+    Contract {
+        id: MsgAddressInt::from_str(ADDRESS).unwrap(),
+        code: state_init.code,
+        data: state_init.data,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_compare_code() {
+    let contract = deployed_contract(load_image("src/tests/Subscription.tvc"));
+
+    let same = contract.compare_code(&load_image("src/tests/Subscription.tvc")).unwrap();
+    assert!(same.matches);
+    assert_eq!(same.account_code_hash, Some(same.image_code_hash.clone()));
+
+    let other = load_image("src/tests/Wallet.tvc");
+    let comparison = contract.compare_code(&other).unwrap();
+    assert!(!comparison.matches);
+    assert_eq!(comparison.image_code_hash, hex::encode(&other.code_hash().unwrap()));
+
+    contract.check_code_hash(&load_image("src/tests/Subscription.tvc").code_hash().unwrap()).unwrap();
+    assert!(contract.check_code_hash(&other.code_hash().unwrap()).is_err());
+
+    // account without code never matches
+    let empty = Contract::default();
+    assert!(!empty.compare_code(&other).unwrap().matches);
+    assert!(empty.check_code_hash(&other.code_hash().unwrap()).is_err());
+}
+
+#[test]
+fn test_upgrade_message() {
+    let address = MsgAddressInt::from_str(ADDRESS).unwrap();
+    let image = load_image("src/tests/Wallet.tvc");
+
+    let (message, _) = Contract::construct_upgrade_message(address.clone(), &image, None, None).unwrap();
+    let message = Contract::deserialize_message(&message).unwrap();
+
+    let decoded = Contract::decode_unknown_function_call_json(
+        UPGRADE_ABI.to_owned(), message.body().unwrap(), false).unwrap();
+    assert_eq!(decoded.function_name, UPGRADE_FUNCTION);
+
+    let params: serde_json::Value = serde_json::from_str(&decoded.params).unwrap();
+    let code = base64::decode(params["code"].as_str().unwrap()).unwrap();
+    assert_eq!(code, image.get_serialized_code().unwrap());
+
+    let unsigned = Contract::get_upgrade_message_bytes_for_signing(address, &image, None).unwrap();
+    assert_eq!(unsigned.data_to_sign.len(), 32);
+}
+
+#[test]
+fn test_transaction_lt() {
+    // upgrade verification waits for the account state with `last_trans_lt` not less than
+    // the upgrade transaction lt
+    let transaction: Transaction = serde_json::from_str(
+        r#"{ "id": "01", "lt": "0x1a2b", "now": 1 }"#).unwrap();
+    assert_eq!(transaction.lt, 0x1a2b);
+
+    let contract: Contract = serde_json::from_str(&format!(
+        r#"{{ "id": "{}", "last_trans_lt": "0x1a2c" }}"#, ADDRESS)).unwrap();
+    assert!(contract.last_trans_lt >= transaction.lt);
+}
@@ -52,6 +52,9 @@ pub struct Transaction {
     #[serde(deserialize_with = "json_helper::deserialize_tr_state")]
     pub status: TransactionProcessingStatus,
     pub now: u32,
+    /// Logical time of the transaction, 0 if it is not loaded
+    #[serde(deserialize_with = "json_helper::deserialize_uint_from_string")]
+    pub lt: u128,
     pub in_msg: Option<MessageId>,
     pub out_msgs: Vec<MessageId>,
     pub aborted: bool,
@@ -147,6 +150,7 @@ pub const TRANSACTION_FIELDS_ORDINARY: &str = r#"
        status_change 
     }
     in_msg
+    lt
     now
     out_msgs
     status
@@ -0,0 +1,159 @@
+/*
+* Copyright 2018-2019 TON DEV SOLUTIONS LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.  You may obtain a copy of the
+* License at: https://ton.dev/licenses
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::*;
+use ed25519_dalek::Keypair;
+use ton_block::MsgAddressInt;
+
+#[cfg(feature = "node_interaction")]
+use futures::stream::Stream;
+
+#[cfg(test)]
+#[path = "tests/test_upgrade.rs"]
+mod tests;
+
+/// ABI of the standard `upgrade(cell code)` method
+pub const UPGRADE_ABI: &str = r#"{
+    "ABI version": 1,
+    "functions": [
+        {
+            "name": "upgrade",
+            "inputs": [
+                {"name":"code","type":"cell"}
+            ],
+            "outputs": []
+        }
+    ],
+    "events": [],
+    "data": []
+}"#;
+
+pub const UPGRADE_FUNCTION: &str = "upgrade";
+
+/// Result of comparing deployed contract code with local image code
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CodeComparison {
+    /// Code hash of the deployed contract in hex, `None` if account has no code
+    #[serde(rename = "accountCodeHash")]
+    pub account_code_hash: Option<String>,
+    /// Code hash of the image in hex
+    #[serde(rename = "imageCodeHash")]
+    pub image_code_hash: String,
+    pub matches: bool,
+}
+
+impl Contract {
+    /// Returns representation hash of the contract code or `None` if account has no code
+    pub fn code_hash(&self) -> Option<Vec<u8>> {
+        self.code.as_ref().map(|code| code.repr_hash().as_slice().to_vec())
+    }
+
+    /// Compares contract code hash with the code hash of the image
+    pub fn compare_code(&self, image: &ContractImage) -> SdkResult<CodeComparison> {
+        Ok(self.compare_code_hash(&image.code_hash()?))
+    }
+
+    /// Compares contract code hash with the known one
+    pub fn compare_code_hash(&self, expected: &[u8]) -> CodeComparison {
+        let account_code_hash = self.code_hash();
+
+        CodeComparison {
+            matches: account_code_hash.as_ref().map(|hash| hash.as_slice()) == Some(expected),
+            account_code_hash: account_code_hash.map(|hash| hex::encode(&hash)),
+            image_code_hash: hex::encode(expected),
+        }
+    }
+
+    /// Checks that contract code hash equals to the expected one
+    pub fn check_code_hash(&self, expected: &[u8]) -> SdkResult<()> {
+        match self.code_hash() {
+            Some(ref hash) if hash.as_slice() == expected => Ok(()),
+            hash => bail!(SdkErrorKind::CodeHashMismatch(
+                hex::encode(expected),
+                hash.map(|hash| hex::encode(&hash)).unwrap_or_default()))
+        }
+    }
+
+    // ------- Upgrade constructing functions -------
+
+    // Packs code of the given image into `upgrade` call message.
+    // `abi` is the contract ABI containing `upgrade(cell code)` method,
+    // standard `UPGRADE_ABI` is used if it is not set.
+    // Returns message's bag of cells and identifier.
+    pub fn construct_upgrade_message(address: MsgAddressInt, image: &ContractImage, abi: Option<String>,
+        key_pair: Option<&Keypair>) -> SdkResult<(Vec<u8>, MessageId)> {
+
+        Self::construct_call_message_json(
+            address,
+            UPGRADE_FUNCTION.to_owned(),
+            Self::upgrade_input(image)?,
+            abi.unwrap_or(UPGRADE_ABI.to_owned()),
+            false,
+            key_pair)
+    }
+
+    // Packs code of the given image into `upgrade` call message without sign
+    // and returns data to sign.
+    // Sign should be then added with `add_sign_to_message` function
+    pub fn get_upgrade_message_bytes_for_signing(address: MsgAddressInt, image: &ContractImage,
+        abi: Option<String>) -> SdkResult<MessageToSign> {
+
+        Self::get_call_message_bytes_for_signing(
+            address,
+            UPGRADE_FUNCTION.to_owned(),
+            Self::upgrade_input(image)?,
+            abi.unwrap_or(UPGRADE_ABI.to_owned()))
+    }
+
+    fn upgrade_input(image: &ContractImage) -> SdkResult<String> {
+        let code = image.get_serialized_code()?;
+
+        Ok(serde_json::json!({
+            "code": base64::encode(&code),
+        }).to_string())
+    }
+}
+
+#[cfg(feature = "node_interaction")]
+impl Contract {
+
+    // Asynchronously calls contract `upgrade` method with the code of the given image.
+    // To get calling result - need to load message,
+    // it's id and processing status is returned by this function
+    pub fn upgrade(address: MsgAddressInt, image: &ContractImage, abi: Option<String>, key_pair: Option<&Keypair>)
+        -> SdkResult<Box<dyn Stream<Item = Transaction, Error = SdkError>>> {
+
+        let (message, id) = Self::construct_upgrade_message(address, image, abi, key_pair)?;
+
+        Self::send_serialized_message(&id, &message)?;
+
+        Self::subscribe_transaction_processing(&id)
+    }
+
+    // Loads deployed contract and checks that its code hash equals to the expected one.
+    // If upgrade transaction is given, waits for the account state produced by it,
+    // otherwise the current state of the active account is checked
+    pub fn verify_code_hash(address: &MsgAddressInt, expected: &[u8], upgrade: Option<&Transaction>)
+        -> SdkResult<Contract>
+    {
+        let contract = match upgrade {
+            Some(transaction) => Self::load_wait_transaction_lt(address, transaction.lt)?,
+            None => Self::load_wait_deployed(address)?,
+        };
+
+        contract.check_code_hash(expected)?;
+
+        Ok(contract)
+    }
+}